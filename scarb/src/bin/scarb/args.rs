@@ -7,13 +7,14 @@ use std::ffi::OsString;
 
 use anyhow::Result;
 use camino::Utf8PathBuf;
+use clap::error::ErrorKind;
 use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
 use smol_str::SmolStr;
 use url::Url;
 
 use clap_complete::Shell;
 use scarb::compiler::Profile;
-use scarb::core::PackageName;
+use scarb::core::{InliningStrategy, PackageName};
 use scarb::manifest_editor::DepId;
 use scarb::manifest_editor::SectionArgs;
 use scarb::version;
@@ -70,6 +71,10 @@ pub struct ScarbArgs {
     #[arg(long, env = "SCARB_OFFLINE", hide_short_help = true)]
     pub offline: bool,
 
+    /// Run without accessing the network and require the lockfile to be up to date.
+    #[arg(long, env = "SCARB_FROZEN", hide_short_help = true)]
+    pub frozen: bool,
+
     /// Directory for all cache data stored by Scarb.
     #[arg(
         long,
@@ -105,6 +110,25 @@ pub struct ScarbArgs {
     #[arg(long, env = "SCARB_NO_PREBUILT_PROC_MACROS")]
     pub no_prebuilt_proc_macros: bool,
 
+    /// Only allow path dependencies located within these directories.
+    #[arg(
+        long,
+        value_delimiter = ',',
+        env = "SCARB_ALLOWED_PATH_DEP_ROOTS",
+        value_name = "DIRECTORIES",
+        hide_short_help = true
+    )]
+    pub allowed_path_dep_roots: Option<Vec<Utf8PathBuf>>,
+
+    /// Resolve a Git dependency at a precise revision, overriding the lockfile.
+    #[arg(
+        long,
+        value_name = "PACKAGE=REV",
+        value_parser = parse_precise_override,
+        hide_short_help = true
+    )]
+    pub precise: Vec<(PackageName, String)>,
+
     /// Specify the profile to use.
     #[command(flatten)]
     pub profile_spec: ProfileSpec,
@@ -124,6 +148,31 @@ impl ScarbArgs {
         }
     }
 
+    /// Reject `--profiles` combined with `--profile`, `--release` or `--dev`.
+    ///
+    /// These are global and subcommand arguments respectively, so `conflicts_with_all` cannot
+    /// be expressed between them in `clap`.
+    pub fn check_conflicts(&self) -> Result<(), clap::Error> {
+        let (Command::Build(build) | Command::Check(build)) = &self.command else {
+            return Ok(());
+        };
+        if build.profiles.is_empty() {
+            return Ok(());
+        }
+        let conflicting = match &self.profile_spec {
+            ProfileSpec { release: true, .. } => "--release",
+            ProfileSpec { dev: true, .. } => "--dev",
+            ProfileSpec {
+                profile: Some(_), ..
+            } => "--profile <PROFILE>",
+            _ => return Ok(()),
+        };
+        Err(Self::command().error(
+            ErrorKind::ArgumentConflict,
+            format!("the argument '--profiles <PROFILES>' cannot be used with '{conflicting}'"),
+        ))
+    }
+
     pub fn get_builtin_subcommands() -> BTreeMap<String, Option<String>> {
         Self::command()
             .get_subcommands()
@@ -150,6 +199,8 @@ pub enum CacheSubcommand {
 pub enum Command {
     // Keep these sorted alphabetically.
     // External should go last.
+    /// Report entrypoint changes between two Starknet contract class files.
+    AbiDiff(AbiDiffArgs),
     /// Add dependencies to a Scarb.toml manifest file.
     Add(AddArgs),
     /// Remove dependencies from a manifest file.
@@ -159,19 +210,25 @@ pub enum Command {
     Build(BuildArgs),
     /// Expand macros.
     Expand(ExpandArgs),
+    /// Print a detailed explanation of a lint diagnostic code.
+    Explain(ExplainArgs),
     /// Manipulate packages cache.
     #[clap(subcommand)]
     Cache(CacheSubcommand),
     /// Analyze the current package and report errors, but don't build Sierra files.
     Check(BuildArgs),
+    /// Check that every `build-external-contracts` selector matches at least one contract.
+    CheckSelectors(CheckSelectorsArgs),
     /// Remove generated artifacts.
     Clean,
     /// Generate shell completions for Scarb.
     Completions(CompletionsArgs),
+    /// Print components of compilation units of a package, with identities of their crates.
+    Components(ComponentsArgs),
     /// List installed commands.
     Commands,
     /// Fetch dependencies of packages from the network.
-    Fetch,
+    Fetch(FetchArgs),
     /// Format project files.
     Fmt(FmtArgs),
     /// Create a new Scarb package in the existing directory.
@@ -183,6 +240,8 @@ pub enum Command {
     Metadata(MetadataArgs),
     /// Create a new Scarb package at PATH.
     New(NewArgs),
+    /// List targets of packages, with their kinds, names and parameters.
+    Targets(TargetsArgs),
     /// Display a tree visualisation of a dependency graph.
     #[command(after_help = "\
         WARNING: The JSON output of this command is unstable across Scarb releases.
@@ -258,6 +317,89 @@ pub struct BuildArgs {
     /// Do not error on `cairo-version` mismatch.
     #[arg(long, env = "SCARB_IGNORE_CAIRO_VERSION")]
     pub ignore_cairo_version: bool,
+
+    /// Do not compile contracts selected with `build-external-contracts` target property.
+    #[arg(long, default_value_t = false)]
+    pub no_external_contracts: bool,
+
+    /// Print `.gitignore` patterns matching artifacts written by this build.
+    #[arg(long, default_value_t = false)]
+    pub report_ignore: bool,
+
+    /// Format of reported diagnostics.
+    #[arg(long, value_enum, default_value_t = MessageFormat::Human)]
+    pub message_format: MessageFormat,
+
+    /// Print time spent in each build phase after the build.
+    #[arg(long, default_value_t = false)]
+    pub time_report: bool,
+
+    /// Compile Starknet contracts twice and fail if the results are not byte-identical.
+    #[arg(long, default_value_t = false)]
+    pub verify_deterministic: bool,
+
+    /// Comma separated list of profiles to build, one after another, instead of the selected one.
+    ///
+    /// Cannot be used with `--profile`, `--release` or `--dev`.
+    #[arg(long, value_delimiter = ',')]
+    pub profiles: Vec<SmolStr>,
+
+    /// Override the inlining strategy of all compiled units: `avoid`, `default` or `small=WEIGHT`.
+    #[arg(long, value_name = "STRATEGY", value_parser = parse_inlining_strategy)]
+    pub inlining_strategy: Option<InliningStrategy>,
+
+    /// Print Starknet contracts which would be built, without compiling them.
+    #[arg(
+        long,
+        default_value_t = false,
+        conflicts_with_all = ["test", "no_external_contracts", "verify_deterministic"]
+    )]
+    pub list_contracts: bool,
+}
+
+/// Format of diagnostics printed by the compiler and the linter.
+#[derive(ValueEnum, Clone, Copy, Debug)]
+pub enum MessageFormat {
+    /// Multi-line diagnostics, including a snippet of the relevant source code.
+    Human,
+    /// One line per diagnostic: `path:line:col: severity[code]: message`.
+    Short,
+}
+
+/// Arguments accepted by the `check-selectors` command.
+#[derive(Parser, Clone, Debug)]
+pub struct CheckSelectorsArgs {
+    /// Specify package(s) to operate on.
+    #[command(flatten)]
+    pub packages_filter: PackagesFilter,
+
+    /// Specify features to enable.
+    #[command(flatten)]
+    pub features: FeaturesSpec,
+
+    /// Do not error on `cairo-version` mismatch.
+    #[arg(long, env = "SCARB_IGNORE_CAIRO_VERSION")]
+    pub ignore_cairo_version: bool,
+}
+
+/// Arguments accepted by the `components` command.
+#[derive(Parser, Clone, Debug)]
+pub struct ComponentsArgs {
+    /// Specify package to operate on.
+    #[command(flatten)]
+    pub packages_filter: PackagesFilter,
+
+    /// Specify features to enable.
+    #[command(flatten)]
+    pub features: FeaturesSpec,
+
+    /// Do not error on `cairo-version` mismatch.
+    #[arg(long, env = "SCARB_IGNORE_CAIRO_VERSION")]
+    pub ignore_cairo_version: bool,
+
+    /// Only print components of the compilation unit of the target with this name.
+    #[arg(long)]
+    pub target_name: Option<String>,
 }
 
 /// Arguments accepted by the `expand` command.
@@ -341,12 +483,16 @@ pub struct InitArgs {
 #[derive(Parser, Clone, Debug)]
 pub struct MetadataArgs {
     /// Format version.
-    #[arg(long, value_name = "VERSION")]
-    pub format_version: u64,
+    #[arg(long, value_name = "VERSION", required_unless_present = "lockfile")]
+    pub format_version: Option<u64>,
     /// Output information only about the workspace members and don't fetch dependencies.
     #[arg(long)]
     pub no_deps: bool,
 
+    /// Output contents of the resolved lockfile as JSON, instead of workspace metadata.
+    #[arg(long, conflicts_with_all = ["format_version", "no_deps"])]
+    pub lockfile: bool,
+
     /// Specify features to enable.
     #[command(flatten)]
     pub features: FeaturesSpec,
@@ -366,6 +512,14 @@ pub struct NewArgs {
     pub init: InitArgs,
 }
 
+/// Arguments accepted by the `fetch` command.
+#[derive(Parser, Clone, Debug)]
+pub struct FetchArgs {
+    /// Only report which sources would be fetched, do not fetch them nor update the lockfile.
+    #[arg(long, default_value_t = false)]
+    pub dry_run: bool,
+}
+
 /// Arguments accepted by the `fmt` command.
 #[derive(Parser, Clone, Debug)]
 pub struct FmtArgs {
@@ -386,6 +540,16 @@ pub struct FmtArgs {
     pub path: Option<Utf8PathBuf>,
 }
 
+/// Arguments accepted by the `abi-diff` command.
+#[derive(Parser, Clone, Debug)]
+pub struct AbiDiffArgs {
+    /// Path to the old version of the contract class file.
+    pub old: Utf8PathBuf,
+
+    /// Path to the new version of the contract class file.
+    pub new: Utf8PathBuf,
+}
+
 /// Arguments accepted by the `add` command.
 #[derive(Parser, Clone, Debug)]
 pub struct AddArgs {
@@ -498,6 +662,13 @@ pub struct TestArgs {
     /// Specify features to enable.
     #[command(flatten)]
     pub features: FeaturesSpec,
+
+    /// Do not error on `cairo-version` mismatch when compiling tests.
+    ///
+    /// This overrides a safety check: packages requiring a different Cairo version may fail to
+    /// compile or behave differently than their authors intended.
+    #[arg(long, env = "SCARB_IGNORE_CAIRO_VERSION")]
+    pub ignore_cairo_version: bool,
 }
 
 /// Arguments accepted by both the `package` and the `publish` command.
@@ -595,9 +766,48 @@ pub struct LintArgs {
     #[arg(short, long, default_value_t = false, env = "SCARB_LINT_DENY_WARNINGS")]
     pub deny_warnings: bool,
 
+    /// Fail if more than this many warnings are reported across all linted packages.
+    #[arg(long, value_name = "N", conflicts_with = "deny_warnings")]
+    pub max_warnings: Option<usize>,
+
     /// Path to a file or directory to lint. If provided, only this file or directory will be linted.
     #[clap(value_name = "SCARB_ACTION_PATH")]
     pub path: Option<Utf8PathBuf>,
+
+    /// Only report diagnostics in Cairo files staged for commit in Git.
+    #[arg(long, default_value_t = false)]
+    pub staged: bool,
+
+    /// Only report diagnostics in Cairo files changed since this Git revision.
+    #[arg(long, value_name = "REV", conflicts_with = "staged")]
+    pub since: Option<String>,
+
+    /// Also write reported diagnostics to this file. Relative paths are resolved against the target directory.
+    #[arg(long, value_name = "PATH")]
+    pub output_file: Option<Utf8PathBuf>,
+
+    /// Format of reported diagnostics.
+    #[arg(long, value_enum, default_value_t = MessageFormat::Human)]
+    pub message_format: MessageFormat,
+
+    /// List all available lint rules with their default severity, without linting anything.
+    #[arg(long, default_value_t = false)]
+    pub list_rules: bool,
+
+    /// Lint package manifests, like unused dependencies, instead of Cairo code.
+    #[arg(long, default_value_t = false, conflicts_with = "fix")]
+    pub manifest: bool,
+
+    /// Also report diagnostics in code generated by procedural macros and compiler plugins.
+    #[arg(long, default_value_t = false, conflicts_with_all = ["fix", "manifest"])]
+    pub include_generated: bool,
+}
+
+/// Arguments accepted by the `explain` command.
+#[derive(Parser, Clone, Debug)]
+pub struct ExplainArgs {
+    /// Lint code to explain, e.g. `bool_comparison`.
+    pub code: String,
 }
 
 /// Arguments accepted by the `completions` command.
@@ -609,6 +819,14 @@ pub struct CompletionsArgs {
     pub shell: Option<Shell>,
 }
 
+/// Arguments accepted by the `targets` command.
+#[derive(Parser, Clone, Debug)]
+pub struct TargetsArgs {
+    /// Specify the package(s) to operate on.
+    #[command(flatten)]
+    pub packages_filter: PackagesFilter,
+}
+
 /// Arguments accepted by the `tree` command.
 #[derive(Parser, Clone, Debug)]
 pub struct TreeCommandArgs {
@@ -631,6 +849,11 @@ pub struct TreeCommandArgs {
     /// Include the `core` package in the dependency tree.
     #[arg(long)]
     pub core: bool,
+
+    /// Instead of the tree, print repositories and registries referenced by multiple sources,
+    /// e.g. at different Git revisions.
+    #[arg(long, conflicts_with_all = ["prune", "depth", "no_dedupe", "core"])]
+    pub duplicate_sources: bool,
 }
 
 /// Git reference specification arguments.
@@ -652,6 +875,30 @@ pub struct GitRefGroup {
     pub rev: Option<String>,
 }
 
+fn parse_precise_override(s: &str) -> Result<(PackageName, String)> {
+    let Some((name, precise)) = s.split_once('=') else {
+        anyhow::bail!("expected `PACKAGE=REV`, got `{s}`");
+    };
+    Ok((PackageName::try_new(name)?, precise.to_string()))
+}
+
+fn parse_inlining_strategy(s: &str) -> Result<InliningStrategy> {
+    match s {
+        "avoid" => Ok(InliningStrategy::Avoid),
+        "default" => Ok(InliningStrategy::Default),
+        _ => {
+            let Some(weight) = s.strip_prefix("small=") else {
+                anyhow::bail!("expected `avoid`, `default` or `small=WEIGHT`, got `{s}`");
+            };
+            let weight: usize = weight
+                .parse()
+                .map_err(|_| anyhow::anyhow!("invalid inlining weight `{weight}`"))?;
+            anyhow::ensure!(weight > 0, "inlining weight must be a positive integer");
+            Ok(InliningStrategy::InlineSmallFunctions(weight))
+        }
+    }
+}
+
 /// Profile specifier.
 #[derive(Parser, Clone, Debug)]
 #[group(multiple = true)]
@@ -683,7 +930,9 @@ impl ProfileSpec {
 
 #[cfg(test)]
 mod tests {
-    use clap::CommandFactory;
+    use clap::error::ErrorKind;
+    use clap::{CommandFactory, Parser};
+    use test_case::test_case;
 
     use super::ScarbArgs;
 
@@ -691,4 +940,28 @@ mod tests {
     fn verify() {
         ScarbArgs::command().debug_assert();
     }
+
+    #[test_case(&["--release", "build"]; "release")]
+    #[test_case(&["--dev", "check"]; "dev")]
+    #[test_case(&["--profile", "custom", "build"]; "profile")]
+    fn profiles_conflict_with_profile_spec(args: &[&str]) {
+        let args = ScarbArgs::try_parse_from(
+            ["scarb"]
+                .into_iter()
+                .chain(args.iter().copied())
+                .chain(["--profiles", "dev,release"]),
+        )
+        .unwrap();
+        assert_eq!(
+            args.check_conflicts().unwrap_err().kind(),
+            ErrorKind::ArgumentConflict
+        );
+    }
+
+    #[test]
+    fn profiles_alone_do_not_conflict() {
+        let args =
+            ScarbArgs::try_parse_from(["scarb", "build", "--profiles", "dev,release"]).unwrap();
+        assert!(args.check_conflicts().is_ok());
+    }
 }
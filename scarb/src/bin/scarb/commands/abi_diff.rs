@@ -0,0 +1,21 @@
+use anyhow::{Result, ensure};
+
+use scarb::core::Config;
+use scarb::ops;
+
+use crate::args::AbiDiffArgs;
+
+#[tracing::instrument(skip_all, level = "info")]
+pub fn run(args: AbiDiffArgs, config: &Config) -> Result<()> {
+    let diff = ops::abi_diff(&args.old, &args.new)?;
+    let is_breaking = diff.is_breaking();
+
+    config.ui().force_print(diff);
+
+    ensure!(
+        !is_breaking,
+        "breaking changes detected in contract interface: {}",
+        args.new
+    );
+    Ok(())
+}
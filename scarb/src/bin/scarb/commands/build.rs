@@ -1,12 +1,30 @@
+use std::fmt::Write;
+
 use anyhow::Result;
+use serde::{Serialize, Serializer};
 
-use crate::args::BuildArgs;
+use crate::args::{BuildArgs, MessageFormat};
+use scarb::compiler::Profile;
+use scarb::compiler::diagnostics::DiagnosticsFormat;
 use scarb::core::Config;
 use scarb::ops;
-use scarb::ops::CompileOpts;
+use scarb::ops::{CompileOpts, ListContractsOpts, PackageContract};
+use scarb_ui::Message;
 
 #[tracing::instrument(skip_all, level = "info")]
-pub fn run(args: BuildArgs, config: &Config) -> Result<()> {
+pub fn run(args: BuildArgs, config: &mut Config) -> Result<()> {
+    config.set_diagnostics_format(args.message_format.into());
+    if args.profiles.is_empty() {
+        return build(&args, config);
+    }
+    for profile in &args.profiles {
+        config.set_profile(Profile::new(profile.clone())?);
+        build(&args, config)?;
+    }
+    Ok(())
+}
+
+fn build(args: &BuildArgs, config: &Config) -> Result<()> {
     let ws = ops::read_workspace(config.manifest_path(), config)?;
     let packages = args
         .packages_filter
@@ -14,12 +32,85 @@ pub fn run(args: BuildArgs, config: &Config) -> Result<()> {
         .into_iter()
         .map(|p| p.id)
         .collect::<Vec<_>>();
+    if args.list_contracts {
+        let opts = ListContractsOpts {
+            features: args.features.clone().try_into()?,
+            ignore_cairo_version: args.ignore_cairo_version,
+        };
+        let contracts = ops::list_contracts(packages, opts, &ws)?;
+        config.ui().force_print(ContractsList { contracts });
+        return Ok(());
+    }
     let opts = CompileOpts::try_new(
-        args.features,
+        args.features.clone(),
         args.ignore_cairo_version,
         args.test,
-        args.target_names,
-        args.target_kinds,
-    )?;
+        args.target_names.clone(),
+        args.target_kinds.clone(),
+    )?
+    .with_no_external_contracts(args.no_external_contracts)
+    .with_report_ignore(args.report_ignore)
+    .with_verify_deterministic(args.verify_deterministic)
+    .with_inlining_strategy(args.inlining_strategy.clone());
     ops::compile(packages, opts, &ws)
 }
+
+#[derive(Serialize)]
+struct ContractsList {
+    contracts: Vec<PackageContract>,
+}
+
+impl Message for ContractsList {
+    fn text(self) -> String {
+        if self.contracts.is_empty() {
+            return "no contracts found".to_string();
+        }
+        let header = ["PACKAGE", "CONTRACT", "MODULE PATH", "SELECTOR"];
+        let rows = self
+            .contracts
+            .iter()
+            .map(|contract| {
+                [
+                    contract.package_name.to_string(),
+                    contract.contract_name.clone(),
+                    contract.module_path.clone(),
+                    contract.selector.clone().unwrap_or_else(|| "-".to_string()),
+                ]
+            })
+            .collect::<Vec<_>>();
+        let widths: Vec<usize> = (0..header.len())
+            .map(|column| {
+                rows.iter()
+                    .map(|row| row[column].len())
+                    .chain([header[column].len()])
+                    .max()
+                    .unwrap()
+            })
+            .collect();
+
+        let mut text = String::new();
+        for row in [header.map(String::from)].iter().chain(&rows) {
+            let line = row
+                .iter()
+                .zip(&widths)
+                .map(|(cell, width)| format!("{cell:<width$}"))
+                .collect::<Vec<_>>()
+                .join("  ");
+            writeln!(text, "{}", line.trim_end()).unwrap();
+        }
+        text.trim_end().to_string()
+    }
+
+    fn structured<S: Serializer>(self, ser: S) -> Result<S::Ok, S::Error> {
+        self.contracts.serialize(ser)
+    }
+}
+
+impl From<MessageFormat> for DiagnosticsFormat {
+    fn from(format: MessageFormat) -> Self {
+        match format {
+            MessageFormat::Human => DiagnosticsFormat::Human,
+            MessageFormat::Short => DiagnosticsFormat::Short,
+        }
+    }
+}
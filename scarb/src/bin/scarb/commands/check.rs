@@ -1,12 +1,30 @@
-use anyhow::Result;
+use anyhow::{Result, ensure};
 
 use crate::args::BuildArgs;
+use scarb::compiler::Profile;
 use scarb::core::Config;
 use scarb::ops;
 use scarb::ops::CompileOpts;
 
 #[tracing::instrument(skip_all, level = "info")]
-pub fn run(args: BuildArgs, config: &Config) -> Result<()> {
+pub fn run(args: BuildArgs, config: &mut Config) -> Result<()> {
+    ensure!(
+        !args.verify_deterministic,
+        "`--verify-deterministic` is not supported by `scarb check`, as it does not compile \
+        contracts, use `scarb build --verify-deterministic` instead"
+    );
+    config.set_diagnostics_format(args.message_format.into());
+    if args.profiles.is_empty() {
+        return check(&args, config);
+    }
+    for profile in &args.profiles {
+        config.set_profile(Profile::new(profile.clone())?);
+        check(&args, config)?;
+    }
+    Ok(())
+}
+
+fn check(args: &BuildArgs, config: &Config) -> Result<()> {
     let ws = ops::read_workspace(config.manifest_path(), config)?;
     let packages = args
         .packages_filter
@@ -15,11 +33,14 @@ pub fn run(args: BuildArgs, config: &Config) -> Result<()> {
         .map(|p| p.id)
         .collect::<Vec<_>>();
     let opts = CompileOpts::try_new(
-        args.features,
+        args.features.clone(),
         args.ignore_cairo_version,
         args.test,
-        args.target_names,
-        args.target_kinds,
-    )?;
+        args.target_names.clone(),
+        args.target_kinds.clone(),
+    )?
+    .with_no_external_contracts(args.no_external_contracts)
+    .with_report_ignore(args.report_ignore)
+    .with_inlining_strategy(args.inlining_strategy.clone());
     ops::check(packages, opts, &ws)
 }
@@ -0,0 +1,32 @@
+use anyhow::{Result, ensure};
+
+use scarb::core::Config;
+use scarb::ops;
+use scarb::ops::CheckSelectorsOpts;
+
+use crate::args::CheckSelectorsArgs;
+
+#[tracing::instrument(skip_all, level = "info")]
+pub fn run(args: CheckSelectorsArgs, config: &Config) -> Result<()> {
+    let ws = ops::read_workspace(config.manifest_path(), config)?;
+    let packages = args
+        .packages_filter
+        .match_many(&ws)?
+        .into_iter()
+        .map(|p| p.id)
+        .collect::<Vec<_>>();
+    let opts = CheckSelectorsOpts {
+        features: args.features.try_into()?,
+        ignore_cairo_version: args.ignore_cairo_version,
+    };
+    let check = ops::check_selectors(packages, opts, &ws)?;
+    let unmatched = check.unmatched_count();
+
+    config.ui().force_print(check);
+
+    ensure!(
+        unmatched == 0,
+        "{unmatched} external contract selector(s) did not match any contract"
+    );
+    Ok(())
+}
@@ -0,0 +1,22 @@
+use anyhow::Result;
+use smol_str::ToSmolStr;
+
+use scarb::core::Config;
+use scarb::ops;
+use scarb::ops::InspectComponentsOpts;
+
+use crate::args::ComponentsArgs;
+
+#[tracing::instrument(skip_all, level = "info")]
+pub fn run(args: ComponentsArgs, config: &Config) -> Result<()> {
+    let ws = ops::read_workspace(config.manifest_path(), config)?;
+    let package = args.packages_filter.match_one(&ws)?;
+    let opts = InspectComponentsOpts {
+        features: args.features.try_into()?,
+        ignore_cairo_version: args.ignore_cairo_version,
+        target_name: args.target_name.map(|name| name.to_smolstr()),
+    };
+    let components = ops::inspect_components(package, opts, &ws)?;
+    config.ui().force_print(components);
+    Ok(())
+}
@@ -0,0 +1,20 @@
+use crate::args::ExplainArgs;
+use anyhow::Result;
+use scarb::core::Config;
+
+#[tracing::instrument(skip_all, level = "info")]
+pub fn run(args: ExplainArgs, config: &Config) -> Result<()> {
+    do_explain(args, config)
+}
+
+#[cfg(feature = "scarb-lint")]
+fn do_explain(args: ExplainArgs, config: &Config) -> Result<()> {
+    let explanation = scarb::ops::explain_lint(&args.code)?;
+    config.ui().print(explanation.trim_end());
+    Ok(())
+}
+
+#[cfg(not(feature = "scarb-lint"))]
+fn do_explain(_args: ExplainArgs, _config: &Config) -> Result<()> {
+    anyhow::bail!("scarb was not compiled with the `lint` command enabled")
+}
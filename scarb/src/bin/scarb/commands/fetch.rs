@@ -2,11 +2,41 @@ use anyhow::Result;
 
 use scarb::core::Config;
 use scarb::ops;
+use scarb_ui::components::Status;
+
+use crate::args::FetchArgs;
 
 #[tracing::instrument(skip_all, level = "info")]
-pub fn run(config: &Config) -> Result<()> {
+pub fn run(args: FetchArgs, config: &mut Config) -> Result<()> {
+    if args.dry_run {
+        // Resolve against the global cache only.
+        config.set_offline(true);
+    }
+    let config = &*config;
     let ws = ops::read_workspace(config.manifest_path(), config)?;
 
+    if args.dry_run {
+        let plan = ops::fetch_plan(&ws)?;
+        for entry in &plan.entries {
+            let status = if entry.cached { "Cached" } else { "Missing" };
+            let kind = if entry.source_id.is_git() {
+                "git repository"
+            } else {
+                "registry"
+            };
+            config.ui().print(Status::new(
+                status,
+                &format!("{kind} {}", entry.source_id.canonical_url),
+            ));
+        }
+        let missing = plan.missing().count();
+        config.ui().print(format!(
+            "{missing} of {} source(s) would be fetched",
+            plan.entries.len()
+        ));
+        return Ok(());
+    }
+
     match ops::resolve_workspace(&ws) {
         Ok(_) => Ok(()),
         Err(e) => Err(e),
@@ -3,7 +3,8 @@ use anyhow::Result;
 use scarb::core::Config;
 
 #[tracing::instrument(skip_all, level = "info")]
-pub fn run(args: LintArgs, config: &Config) -> Result<()> {
+pub fn run(args: LintArgs, config: &mut Config) -> Result<()> {
+    config.set_diagnostics_format(args.message_format.into());
     do_lint(args, config)
 }
 
@@ -11,6 +12,11 @@ pub fn run(args: LintArgs, config: &Config) -> Result<()> {
 fn do_lint(args: LintArgs, config: &Config) -> Result<()> {
     use scarb::ops::{self, LintOptions};
 
+    if args.list_rules {
+        config.ui().print(ops::list_lint_rules());
+        return Ok(());
+    }
+
     let ws = ops::read_workspace(config.manifest_path(), config)?;
     let packages = args
         .packages_filter
@@ -26,7 +32,13 @@ fn do_lint(args: LintArgs, config: &Config) -> Result<()> {
             ignore_cairo_version: args.ignore_cairo_version,
             features: args.features.try_into()?,
             deny_warnings: args.deny_warnings,
+            max_warnings: args.max_warnings,
             path: args.path,
+            staged: args.staged,
+            since: args.since,
+            output_file: args.output_file,
+            manifest: args.manifest,
+            include_generated: args.include_generated,
         },
         &ws,
     )
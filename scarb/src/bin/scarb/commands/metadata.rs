@@ -10,9 +10,17 @@ use crate::args::MetadataArgs;
 pub fn run(args: MetadataArgs, config: &Config) -> Result<()> {
     let ws = ops::read_workspace(config.manifest_path(), config)?;
 
+    if args.lockfile {
+        let lockfile = ops::lockfile_json(&ws)?;
+        config.ui().force_print(MachineMessage(lockfile));
+        return Ok(());
+    }
+
     let features = args.features.try_into()?;
     let opts = ops::MetadataOptions {
-        version: args.format_version,
+        version: args
+            .format_version
+            .expect("format version is required unless printing lockfile"),
         no_deps: args.no_deps,
         features,
         ignore_cairo_version: args.ignore_cairo_version,
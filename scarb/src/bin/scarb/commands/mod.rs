@@ -6,15 +6,19 @@ use scarb::core::Config;
 
 use crate::args::{CacheSubcommand, Command};
 
+mod abi_diff;
 pub mod add;
 pub mod build;
 pub mod cache_clean;
 pub mod cache_path;
 pub mod check;
+mod check_selectors;
 pub mod clean;
 pub mod commands;
 mod completions;
+mod components;
 mod expand;
+mod explain;
 pub mod external;
 pub mod fetch;
 pub mod fmt;
@@ -28,6 +32,7 @@ mod proc_macro_server;
 pub mod publish;
 pub mod remove;
 pub mod run;
+mod targets;
 pub mod test;
 mod tree;
 mod update;
@@ -37,17 +42,21 @@ pub fn run(command: Command, config: &mut Config) -> Result<()> {
 
     match command {
         // Keep these sorted alphabetically.
+        AbiDiff(args) => abi_diff::run(args, config),
         Add(args) => add::run(args, config),
         Build(args) => build::run(args, config),
         Expand(args) => expand::run(args, config),
+        Explain(args) => explain::run(args, config),
         Cache(CacheSubcommand::Clean) => cache_clean::run(config),
         Cache(CacheSubcommand::Path) => cache_path::run(config),
         Check(args) => check::run(args, config),
+        CheckSelectors(args) => check_selectors::run(args, config),
         Clean => clean::run(config),
         Completions(args) => completions::run(args, config),
         Commands => commands::run(config),
+        Components(args) => components::run(args, config),
         External(args) => external::run(args, config),
-        Fetch => fetch::run(config),
+        Fetch(args) => fetch::run(args, config),
         Fmt(args) => fmt::run(args, config),
         Init(args) => init::run(args, config),
         ManifestPath => manifest_path::run(config),
@@ -59,6 +68,7 @@ pub fn run(command: Command, config: &mut Config) -> Result<()> {
         Lint(args) => lint::run(args, config),
         Remove(args) => remove::run(args, config),
         Run(args) => run::run(args, config),
+        Targets(args) => targets::run(args, config),
         Test(args) => test::run(args, config),
         Tree(args) => tree::run(args, config),
         Update => update::run(config),
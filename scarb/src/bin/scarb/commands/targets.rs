@@ -0,0 +1,89 @@
+use anyhow::Result;
+use serde::{Serialize, Serializer};
+use smol_str::SmolStr;
+
+use scarb::core::{Config, Package, PackageId, TargetKind};
+use scarb::ops;
+use scarb_ui::Message;
+
+use crate::args::TargetsArgs;
+
+#[tracing::instrument(skip_all, level = "info")]
+pub fn run(args: TargetsArgs, config: &Config) -> Result<()> {
+    let ws = ops::read_workspace(config.manifest_path(), config)?;
+    let packages = args
+        .packages_filter
+        .match_many(&ws)?
+        .iter()
+        .map(PackageTargets::new)
+        .collect();
+
+    config.ui().force_print(TargetsList { packages });
+
+    Ok(())
+}
+
+#[derive(Serialize)]
+struct TargetsList {
+    packages: Vec<PackageTargets>,
+}
+
+#[derive(Serialize)]
+struct PackageTargets {
+    package: PackageId,
+    targets: Vec<TargetInfo>,
+}
+
+#[derive(Serialize)]
+struct TargetInfo {
+    kind: TargetKind,
+    name: SmolStr,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    group_id: Option<SmolStr>,
+    params: toml::Value,
+}
+
+impl PackageTargets {
+    fn new(package: &Package) -> Self {
+        let targets = package
+            .manifest
+            .targets
+            .iter()
+            .map(|target| TargetInfo {
+                kind: target.kind.clone(),
+                name: target.name.clone(),
+                group_id: target.group_id.clone(),
+                params: target.params.clone(),
+            })
+            .collect();
+        Self {
+            package: package.id,
+            targets,
+        }
+    }
+}
+
+impl Message for TargetsList {
+    fn text(self) -> String {
+        let mut out = String::new();
+        for package in self.packages {
+            out.push_str(&format!("{}\n", package.package));
+            for target in package.targets {
+                out.push_str(&format!("    {} {}", target.kind, target.name));
+                if let Some(group_id) = target.group_id {
+                    out.push_str(&format!(" (group: {group_id})"));
+                }
+                out.push('\n');
+            }
+        }
+
+        // Trim any trailing whitespace in-place.
+        out.truncate(out.trim_end().len());
+
+        out
+    }
+
+    fn structured<S: Serializer>(self, ser: S) -> Result<S::Ok, S::Error> {
+        self.packages.serialize(ser)
+    }
+}
@@ -16,6 +16,13 @@ pub fn run(args: TestArgs, config: &Config) -> Result<()> {
     let features_opts: FeaturesOpts = args.features.clone().try_into()?;
     validate_features(&packages, &features_opts)?;
     packages.iter().try_for_each(|package| {
-        ops::execute_test_subcommand(package, &args.args, &ws, args.features.clone()).map(|_| ())
+        ops::execute_test_subcommand(
+            package,
+            &args.args,
+            &ws,
+            args.features.clone(),
+            args.ignore_cairo_version,
+        )
+        .map(|_| ())
     })
 }
@@ -1,6 +1,7 @@
 use crate::args::TreeCommandArgs;
 use anyhow::Result;
-use scarb::core::{Config, Package, PackageId, TargetKind};
+use itertools::Itertools;
+use scarb::core::{Config, Package, PackageId, SourceId, TargetKind};
 use scarb::ops;
 use scarb_ui::Message;
 use serde::{Serialize, Serializer};
@@ -19,6 +20,13 @@ pub fn run(args: TreeCommandArgs, config: &Config) -> Result<()> {
 
     let resolve = ops::resolve_workspace(&ws)?;
 
+    if args.duplicate_sources {
+        config
+            .ui()
+            .force_print(duplicate_sources(packages, &resolve));
+        return Ok(());
+    }
+
     let forest = build(packages, &resolve, &args)?;
 
     config.ui().force_print(forest);
@@ -181,6 +189,84 @@ impl From<anyhow::Error> for BuildError {
     }
 }
 
+/// Groups sources of all packages reachable from `packages` by the repository they point to,
+/// keeping only repositories referenced by more than one source.
+fn duplicate_sources(packages: Vec<PackageId>, resolve: &ops::WorkspaceResolve) -> SourceGroups {
+    let mut visited = HashSet::new();
+    let mut stack = packages;
+    while let Some(package_id) = stack.pop() {
+        if visited.insert(package_id) {
+            stack.extend(resolve.resolve.package_dependencies(package_id));
+        }
+    }
+
+    let mut groups: Vec<Vec<SourceId>> = Vec::new();
+    for source_id in visited
+        .into_iter()
+        .map(|package_id| package_id.source_id)
+        .filter(|source_id| !source_id.is_std())
+        .unique()
+        .sorted()
+    {
+        match groups
+            .iter_mut()
+            .find(|group| group[0].same_repository(source_id))
+        {
+            Some(group) => group.push(source_id),
+            None => groups.push(vec![source_id]),
+        }
+    }
+
+    SourceGroups(
+        groups
+            .into_iter()
+            .filter(|group| group.len() > 1)
+            .map(|group| SourceGroup {
+                canonical_url: group[0].canonical_url.to_string(),
+                sources: group
+                    .into_iter()
+                    .map(|source_id| source_id.to_pretty_url())
+                    .collect(),
+            })
+            .collect(),
+    )
+}
+
+#[derive(Serialize)]
+struct SourceGroups(Vec<SourceGroup>);
+
+#[derive(Serialize)]
+struct SourceGroup {
+    canonical_url: String,
+    sources: Vec<String>,
+}
+
+impl Message for SourceGroups {
+    fn text(self) -> String {
+        if self.0.is_empty() {
+            return "no repositories referenced by multiple sources".to_string();
+        }
+        let mut out = String::new();
+        for group in self.0 {
+            writeln!(
+                out,
+                "{} is referenced by {} sources:",
+                group.canonical_url,
+                group.sources.len()
+            )
+            .unwrap();
+            for source in group.sources {
+                writeln!(out, "    {source}").unwrap();
+            }
+        }
+        out.trim_end().to_string()
+    }
+
+    fn structured<S: Serializer>(self, ser: S) -> Result<S::Ok, S::Error> {
+        self.0.serialize(ser)
+    }
+}
+
 #[derive(Default, Serialize)]
 struct Tree {
     #[serde(skip_serializing_if = "Option::is_none")]
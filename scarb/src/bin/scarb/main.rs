@@ -13,13 +13,16 @@ use scarb::process::WillExecReplace;
 use scarb_ui::Ui;
 use scarb_ui::args::VerbositySpec;
 
+use crate::args::Command;
 use crate::errors::ErrorWithExitCode;
+use crate::time_report::TimeReportLayer;
 
 mod args;
 mod commands;
 mod errors;
 mod fsx;
 mod interactive;
+mod time_report;
 
 fn main() -> ExitCode {
     // NOTE: Never ever create droppable objects in `main`.
@@ -31,13 +34,26 @@ fn main() -> ExitCode {
 
 fn main_that_can_exec_replace() -> Result<ExitCode, WillExecReplace> {
     let args = ScarbArgs::parse();
+    if let Err(err) = args.check_conflicts() {
+        err.exit();
+    }
 
     // Pre-create Ui used in logging and error reporting, because we will move `args` to `cli_main`.
     let ui = Ui::new(args.verbose.clone().into(), args.output_format());
 
-    let _guard = init_logging(args.verbose.clone(), &ui);
+    let time_report =
+        matches!(&args.command, Command::Build(build) | Command::Check(build) if build.time_report)
+            .then(TimeReportLayer::default);
+
+    let _guard = init_logging(args.verbose.clone(), &ui, time_report.clone());
+
+    let result = cli_main(args);
 
-    match cli_main(args) {
+    if let Some(time_report) = time_report {
+        ui.print(time_report.report());
+    }
+
+    match result {
         Ok(()) => Ok(ExitCode::SUCCESS),
         Err(err) => match err.downcast::<WillExecReplace>() {
             Ok(err) => Err(err),
@@ -46,7 +62,11 @@ fn main_that_can_exec_replace() -> Result<ExitCode, WillExecReplace> {
     }
 }
 
-fn init_logging(verbose: VerbositySpec, ui: &Ui) -> Option<impl Drop> {
+fn init_logging(
+    verbose: VerbositySpec,
+    ui: &Ui,
+    time_report: Option<TimeReportLayer>,
+) -> Option<impl Drop> {
     use chrono::Local;
     use std::fs;
 
@@ -120,10 +140,15 @@ fn init_logging(verbose: VerbositySpec, ui: &Ui) -> Option<impl Drop> {
         None
     };
 
+    // Only spans of Scarb itself are measured, so that the overhead of the report stays low.
+    let time_report_layer = time_report
+        .map(|layer| layer.with_filter(Targets::new().with_target("scarb", LevelFilter::TRACE)));
+
     tracing::subscriber::set_global_default(
         tracing_subscriber::registry()
             .with(fmt_layer)
-            .with(profile_layer),
+            .with(profile_layer)
+            .with(time_report_layer),
     )
     .expect("could not set up global logger");
 
@@ -161,10 +186,13 @@ fn cli_main(args: ScarbArgs) -> Result<()> {
         .ui_verbosity(args.verbose.clone().into())
         .ui_output_format(ui_output_format)
         .offline(args.offline)
+        .frozen(args.frozen)
         .log_filter_directive(Some(scarb_log))
         .profile(args.profile_spec.determine()?)
         .load_proc_macros(!args.no_proc_macros)
         .load_prebuilt_proc_macros(!args.no_prebuilt_proc_macros)
+        .allowed_path_dep_roots(args.allowed_path_dep_roots)
+        .precise_overrides(args.precise.into_iter().collect())
         .build()?;
 
     commands::run(args.command, &mut config)
@@ -0,0 +1,111 @@
+//! Collection of build phase timings for the `--time-report` flag of `scarb build`.
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use scarb_ui::Message;
+use serde::{Serialize, Serializer};
+use tracing::Subscriber;
+use tracing::span::{Attributes, Id};
+use tracing_subscriber::Layer;
+use tracing_subscriber::layer::Context;
+use tracing_subscriber::registry::LookupSpan;
+
+/// Build phases, in the order they happen during a build.
+const PHASES: [&str; 5] = ["resolve", "db-build", "sierra-gen", "casm-gen", "serialize"];
+
+/// Maps names of spans emitted by Scarb to build phases they belong to.
+fn phase_of(span_name: &str) -> Option<&'static str> {
+    match span_name {
+        "resolve_workspace_with_opts" => Some("resolve"),
+        "build_scarb_root_database" => Some("db-build"),
+        "compile_sierra" | "compile_test" | "compile_starknet" => Some("sierra-gen"),
+        "compile_casm" | "compile_starknet_casm" => Some("casm-gen"),
+        name if name.starts_with("serialize_") => Some("serialize"),
+        _ => None,
+    }
+}
+
+/// A tracing layer summing up durations of spans by the build phase they belong to.
+#[derive(Clone, Default)]
+pub struct TimeReportLayer {
+    durations: Arc<Mutex<BTreeMap<&'static str, Duration>>>,
+}
+
+struct SpanStart(Instant);
+
+impl TimeReportLayer {
+    pub fn report(&self) -> TimeReport {
+        let durations = self.durations.lock().unwrap();
+        let phases = PHASES
+            .iter()
+            .filter_map(|phase| {
+                durations.get(phase).map(|duration| PhaseTime {
+                    phase,
+                    seconds: duration.as_secs_f64(),
+                })
+            })
+            .collect();
+        TimeReport { phases }
+    }
+}
+
+impl<S> Layer<S> for TimeReportLayer
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+{
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if phase_of(attrs.metadata().name()).is_none() {
+            return;
+        }
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(SpanStart(Instant::now()));
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        let Some(span) = ctx.span(&id) else {
+            return;
+        };
+        let Some(phase) = phase_of(span.name()) else {
+            return;
+        };
+        let Some(elapsed) = span
+            .extensions()
+            .get::<SpanStart>()
+            .map(|SpanStart(start)| start.elapsed())
+        else {
+            return;
+        };
+        *self.durations.lock().unwrap().entry(phase).or_default() += elapsed;
+    }
+}
+
+#[derive(Serialize)]
+pub struct TimeReport {
+    phases: Vec<PhaseTime>,
+}
+
+#[derive(Serialize)]
+struct PhaseTime {
+    phase: &'static str,
+    seconds: f64,
+}
+
+impl Message for TimeReport {
+    fn text(self) -> String {
+        let mut text = String::from("time report:");
+        for PhaseTime { phase, seconds } in &self.phases {
+            write!(text, "\n  {phase:<12}{seconds:>8.2}s").unwrap();
+        }
+        let total: f64 = self.phases.iter().map(|phase| phase.seconds).sum();
+        write!(text, "\n  {:<12}{total:>8.2}s", "total").unwrap();
+        text
+    }
+
+    fn structured<S: Serializer>(self, ser: S) -> Result<S::Ok, S::Error> {
+        self.serialize(ser)
+    }
+}
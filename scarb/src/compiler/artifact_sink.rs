@@ -0,0 +1,23 @@
+use std::fmt;
+
+use anyhow::Result;
+use camino::Utf8Path;
+
+#[cfg(doc)]
+use crate::core::config::ConfigBuilder;
+
+/// Destination of artifacts emitted by Scarb compilers.
+///
+/// By default, artifacts are written to files in the target directory.
+/// Applications embedding Scarb can provide a custom sink with [`ConfigBuilder::artifact_sink`],
+/// for example, to keep artifacts in memory or upload them directly.
+pub trait ArtifactSink: Send + Sync {
+    /// Receives contents of an artifact, which would be written to `path` otherwise.
+    fn write(&self, path: &Utf8Path, contents: &[u8]) -> Result<()>;
+}
+
+impl fmt::Debug for dyn ArtifactSink {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ArtifactSink")
+    }
+}
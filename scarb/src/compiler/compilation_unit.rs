@@ -58,6 +58,16 @@ pub struct CairoCompilationUnit {
     ///
     /// Each individual component can override this value.
     pub cfg_set: CfgSet,
+
+    /// Whether contracts selected with the `build-external-contracts` target property should be
+    /// compiled in this unit.
+    ///
+    /// This allows skipping external contracts without modifying the package manifest.
+    pub build_external_contracts: bool,
+
+    /// Whether Starknet contracts should be compiled twice, to check that compilation output
+    /// is deterministic.
+    pub verify_deterministic: bool,
 }
 
 /// An object that has enough information so that Scarb knows how to build procedural macro with it.
@@ -109,10 +119,16 @@ pub struct CompilationUnitComponent {
 impl CompilationUnitComponent {
     /// Returns a [`CrateId`] of a crate associated with the [`CompilationUnitComponent`].
     pub fn crate_id(&self, db: &dyn FilesGroup) -> CrateId {
-        db.intern_crate(CrateLongId::Real {
+        db.intern_crate(self.crate_long_id())
+    }
+
+    /// Returns a [`CrateLongId`] identifying the crate associated with the
+    /// [`CompilationUnitComponent`], as interned in the database.
+    pub fn crate_long_id(&self) -> CrateLongId {
+        CrateLongId::Real {
             name: self.cairo_package_name(),
             discriminator: self.id.to_discriminator(),
-        })
+        }
     }
 }
 
@@ -211,6 +227,22 @@ pub trait CompilationUnitAttributes {
         component
     }
 
+    /// Returns all components providing main crates of this unit, starting with the
+    /// [`main_component`][Self::main_component].
+    ///
+    /// Currently, units are built for a single package, so this is always just the main component.
+    fn main_components(&self) -> Vec<&CompilationUnitComponent> {
+        let main_package_id = self.main_package_id();
+        let mut components = vec![self.main_component()];
+        components.extend(
+            self.components()
+                .iter()
+                .skip(1)
+                .filter(|component| component.package.id == main_package_id),
+        );
+        components
+    }
+
     fn id(&self) -> String {
         format!("{}-{}", self.main_package_id().name, self.digest())
     }
@@ -312,13 +344,10 @@ impl CompilationUnitAttributes for ProcMacroCompilationUnit {
 impl CairoCompilationUnit {
     pub fn core_package_component(&self) -> Option<&CompilationUnitComponent> {
         // NOTE: This uses the order invariant of `component` field.
-        if self.components.len() < 2 {
-            None
-        } else {
-            let component = &self.components[1];
-            assert!(component.package.id.is_core());
-            Some(component)
-        }
+        //   Packages opting out of `core` (`no-std = true`) do not have it among components.
+        self.components
+            .get(1)
+            .filter(|component| component.package.id.is_core())
     }
 
     pub fn target_dir(&self, ws: &Workspace<'_>) -> Filesystem {
@@ -505,3 +534,87 @@ impl ComponentTarget {
             .collect()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use cairo_lang_filesystem::cfg::CfgSet;
+    use camino::Utf8PathBuf;
+    use semver::Version;
+
+    use super::{CairoCompilationUnit, CompilationUnitAttributes, CompilationUnitComponent};
+    use crate::compiler::{ComponentTarget, DefaultForProfile, Profile};
+    use crate::core::{
+        ManifestBuilder, ManifestCompilerConfig, Package, PackageId, PackageName, SourceId,
+        Summary, Target, TargetKind,
+    };
+
+    fn component(package_id: PackageId, target_name: &str) -> CompilationUnitComponent {
+        let summary = Summary::builder()
+            .package_id(package_id)
+            .no_core(package_id.name == PackageName::CORE)
+            .build();
+        let target = Target::without_params(TargetKind::LIB, target_name, "src/lib.cairo");
+        let manifest = Box::new(
+            ManifestBuilder::default()
+                .summary(summary)
+                .targets(vec![target.clone()])
+                .build()
+                .unwrap(),
+        );
+        let package = Package::new(package_id, Utf8PathBuf::new(), manifest);
+        CompilationUnitComponent::try_new(package, ComponentTarget::new_single(target), None)
+            .unwrap()
+    }
+
+    fn unit(components: Vec<CompilationUnitComponent>) -> CairoCompilationUnit {
+        CairoCompilationUnit {
+            main_package_id: components[0].package.id,
+            components,
+            cairo_plugins: Vec::new(),
+            profile: Profile::DEV,
+            compiler_config: ManifestCompilerConfig::default_for_profile(&Profile::DEV),
+            cfg_set: CfgSet::new(),
+            build_external_contracts: true,
+            verify_deterministic: false,
+        }
+    }
+
+    fn package_id(name: &str) -> PackageId {
+        PackageId::new(
+            PackageName::new(name),
+            Version::new(1, 0, 0),
+            SourceId::mock_path(),
+        )
+    }
+
+    #[test]
+    fn single_main_component() {
+        let unit = unit(vec![
+            component(package_id("hello"), "hello"),
+            component(package_id("core"), "core"),
+            component(package_id("dep"), "dep"),
+        ]);
+        let main = unit
+            .main_components()
+            .into_iter()
+            .map(|component| component.target_name())
+            .collect::<Vec<_>>();
+        assert_eq!(main, vec!["hello"]);
+    }
+
+    #[test]
+    fn multiple_main_components() {
+        let unit = unit(vec![
+            component(package_id("hello"), "hello"),
+            component(package_id("core"), "core"),
+            component(package_id("hello"), "hello_extra"),
+            component(package_id("dep"), "dep"),
+        ]);
+        let main = unit
+            .main_components()
+            .into_iter()
+            .map(|component| component.target_name())
+            .collect::<Vec<_>>();
+        assert_eq!(main, vec!["hello", "hello_extra"]);
+    }
+}
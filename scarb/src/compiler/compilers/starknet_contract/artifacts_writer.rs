@@ -1,8 +1,18 @@
-use crate::compiler::compilers::Props;
+use crate::FINGERPRINT_DIR_NAME;
+use crate::compiler::compilers::starknet_contract::native::{
+    NATIVE_LIBRARY_EXTENSION, compile_native,
+};
 use crate::compiler::compilers::starknet_contract::{ContractFileStemCalculator, ContractSelector};
-use crate::compiler::helpers::write_json_with_byte_count;
+use crate::compiler::compilers::{ArtifactsFormat, ContractsLayout, Props};
+use crate::compiler::helpers::{
+    write_artifact, write_atomically, write_gzipped_artifact, write_gzipped_json_with_byte_count,
+    write_json_with_byte_count, write_string,
+};
+use crate::compiler::incremental::is_fresh;
 use crate::core::{PackageName, Workspace};
 use crate::flock::Filesystem;
+use crate::internal::fsx;
+use anyhow::{Context, bail, ensure};
 use cairo_lang_compiler::db::RootDatabase;
 use cairo_lang_defs::ids::NamedLanguageElementId;
 use cairo_lang_starknet::contract::ContractDeclaration;
@@ -10,17 +20,24 @@ use cairo_lang_starknet_classes::abi::Contract;
 use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
 use cairo_lang_starknet_classes::contract_class::{ContractClass, ContractEntryPoints};
 use cairo_lang_utils::bigint::BigUintAsHex;
+use camino::{Utf8Path, Utf8PathBuf};
 use indoc::formatdoc;
 use itertools::{Itertools, izip};
 use scarb_stable_hash::short_hash;
-use serde::Serialize;
+use scarb_ui::components::Status;
+use serde::{Deserialize, Serialize};
 use smol_str::SmolStr;
-use tracing::trace_span;
+use std::collections::{BTreeMap, HashMap};
+use std::io::Write;
+use tracing::{trace, trace_span};
 
 const MAX_SIERRA_PROGRAM_FELTS: usize = 81290;
 const MAX_CASM_PROGRAM_FELTS: usize = 81290;
-const MAX_CONTRACT_CLASS_BYTES: usize = 4089446;
-const MAX_COMPILED_CONTRACT_CLASS_BYTES: usize = 4089446;
+pub const MAX_CONTRACT_CLASS_BYTES: usize = 4089446;
+pub const MAX_COMPILED_CONTRACT_CLASS_BYTES: usize = 4089446;
+
+const CONTRACTS_FINGERPRINT_DIR_NAME: &str = "contracts";
+const NESTED_CONTRACTS_DIR_NAME: &str = "contracts";
 
 // Represents a contract in the Starknet network as defined in Starknet JSON-RPC spec:
 // https://github.com/starkware-libs/starknet-specs/blob/2030a650be4e40cfa34d5051a0334f375384a421/api/starknet_api_openrpc.json#L3030
@@ -43,10 +60,80 @@ impl<'a> ContractClassNoDebug<'a> {
     }
 }
 
+/// Digest of the code and entry points of a contract class, used to detect duplicate classes.
+///
+/// Sierra programs encode names of types declared in the contract module,
+/// so compiled classes are compared whenever these are available.
+/// The ABI and debug info are not included, as they do not affect execution.
+fn class_digest(class: &ContractClass, casm_class: Option<&CasmContractClass>) -> String {
+    match casm_class {
+        Some(casm_class) => {
+            let entry_points = &casm_class.entry_points_by_type;
+            let entry_points = [
+                &entry_points.external,
+                &entry_points.l1_handler,
+                &entry_points.constructor,
+            ]
+            .map(|entry_points| {
+                entry_points
+                    .iter()
+                    .map(|entry_point| {
+                        (
+                            &entry_point.selector,
+                            entry_point.offset,
+                            &entry_point.builtins,
+                        )
+                    })
+                    .collect_vec()
+            });
+            let bytecode = casm_class
+                .bytecode
+                .iter()
+                .map(|felt| &felt.value)
+                .collect_vec();
+            short_hash((bytecode, entry_points))
+        }
+        None => {
+            let entry_points = &class.entry_points_by_type;
+            let entry_points = [
+                &entry_points.external,
+                &entry_points.l1_handler,
+                &entry_points.constructor,
+            ]
+            .map(|entry_points| {
+                entry_points
+                    .iter()
+                    .map(|entry_point| (&entry_point.selector, entry_point.function_idx))
+                    .collect_vec()
+            });
+            let program = class
+                .sierra_program
+                .iter()
+                .map(|felt| &felt.value)
+                .collect_vec();
+            short_hash((program, entry_points))
+        }
+    }
+}
+
 #[derive(Debug, Serialize)]
 struct StarknetArtifacts {
     version: usize,
     contracts: Vec<ContractArtifacts>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    bundle: Option<String>,
+    #[serde(skip_serializing_if = "ArtifactsFormat::is_json")]
+    format: ArtifactsFormat,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    encoding: Option<ArtifactsEncoding>,
+}
+
+/// Compression applied to contract class files, recorded in the artifacts manifest so that
+/// consumers know how to read them.
+#[derive(Clone, Copy, Debug, Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum ArtifactsEncoding {
+    Gzip,
 }
 
 impl Default for StarknetArtifacts {
@@ -54,18 +141,89 @@ impl Default for StarknetArtifacts {
         Self {
             version: 1,
             contracts: Vec::new(),
+            bundle: None,
+            format: ArtifactsFormat::default(),
+            encoding: None,
         }
     }
 }
 
 impl StarknetArtifacts {
-    fn finish(&mut self) {
+    /// Names of all artifact files referenced by this manifest.
+    fn file_names(&self) -> impl Iterator<Item = &String> {
+        self.contracts
+            .iter()
+            .flat_map(|contract| {
+                let ContractArtifact {
+                    sierra,
+                    sierra_text,
+                    casm,
+                    native,
+                } = &contract.artifacts;
+                [sierra, sierra_text, casm, native]
+            })
+            .chain([&self.bundle])
+            .flatten()
+    }
+
+    /// Reorders contracts to follow their order in a previously written artifacts manifest,
+    /// keeping contracts absent from it at the end, in their current order.
+    fn preserve_order_of(&mut self, previous: &PreviousStarknetArtifacts) {
+        let positions: HashMap<&str, usize> = previous
+            .contracts
+            .iter()
+            .enumerate()
+            .map(|(position, contract)| (contract.id.as_str(), position))
+            .collect();
+        // Sorting is stable, so new contracts stay sorted among themselves.
+        self.contracts.sort_by_key(|contract| {
+            positions
+                .get(contract.id.as_str())
+                .copied()
+                .unwrap_or(usize::MAX)
+        });
+    }
+
+    fn finish(&mut self, sort_by_name: bool) {
         assert!(
             self.contracts.iter().map(|it| &it.id).all_unique(),
             "Artifacts IDs must be unique."
         );
 
-        self.contracts.sort_unstable_by_key(|it| it.id.clone());
+        if sort_by_name {
+            self.contracts.sort_unstable_by(|a, b| {
+                (&a.package_name, &a.contract_name, &a.module_path).cmp(&(
+                    &b.package_name,
+                    &b.contract_name,
+                    &b.module_path,
+                ))
+            });
+        } else {
+            self.contracts.sort_unstable_by_key(|it| it.id.clone());
+        }
+    }
+}
+
+/// The part of an artifacts manifest written by a previous build, which is needed to preserve
+/// the order of contracts.
+#[derive(Debug, Deserialize)]
+struct PreviousStarknetArtifacts {
+    contracts: Vec<PreviousContractArtifacts>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PreviousContractArtifacts {
+    id: String,
+}
+
+impl PreviousStarknetArtifacts {
+    /// Reads the artifacts manifest, returning `None` if it does not exist or cannot be parsed,
+    /// as it is then simply overwritten.
+    fn read(path: &Utf8Path) -> Option<Self> {
+        let contents = fsx::read_to_string(path).ok()?;
+        serde_json::from_str(&contents)
+            .inspect_err(|err| trace!("ignoring unreadable artifacts manifest {path}: {err}"))
+            .ok()
     }
 }
 
@@ -75,6 +233,8 @@ struct ContractArtifacts {
     package_name: PackageName,
     contract_name: String,
     module_path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    selector: Option<String>,
     artifacts: ContractArtifact,
 }
 
@@ -90,6 +250,7 @@ impl ContractArtifacts {
             package_name,
             contract_name: contract_name.to_owned(),
             module_path: module_path.to_owned(),
+            selector: None,
             artifacts: ContractArtifact::default(),
         }
     }
@@ -98,25 +259,68 @@ impl ContractArtifacts {
 #[derive(Debug, Default, Serialize)]
 struct ContractArtifact {
     sierra: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sierra_text: Option<String>,
     casm: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    native: Option<String>,
+}
+
+/// Contract classes of a single contract, as stored in the contracts bundle file.
+#[derive(Debug, Serialize)]
+struct ContractBundleEntry<'a> {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sierra: Option<&'a ContractClass>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    casm: Option<&'a CasmContractClass>,
 }
 
 pub struct ArtifactsWriter {
     sierra: bool,
+    sierra_text: bool,
     casm: bool,
+    native: bool,
+    bundle: bool,
+    bundle_only: bool,
+    layout: ContractsLayout,
+    format: ArtifactsFormat,
+    compress: bool,
+    preserve_contracts_order: bool,
+    sort_contracts_by_name: bool,
+    allow_duplicate_classes: bool,
+    size_limits_deny: bool,
+    max_contract_class_size: usize,
+    max_compiled_contract_class_size: usize,
     target_dir: Filesystem,
     target_name: SmolStr,
     extension_prefix: Option<String>,
+    artifacts_manifest_name: Option<String>,
+    selector_provenance: Option<HashMap<String, ContractSelector>>,
 }
 
 impl ArtifactsWriter {
     pub fn new(target_name: SmolStr, target_dir: Filesystem, props: Props) -> Self {
         Self {
             sierra: props.sierra,
-            casm: props.casm,
+            sierra_text: props.emit_sierra_text,
+            casm: props.casm.is_enabled(),
+            native: props.native,
+            bundle: props.bundle || props.bundle_only,
+            bundle_only: props.bundle_only,
+            layout: props.layout,
+            format: props.format,
+            compress: props.compress,
+            preserve_contracts_order: props.preserve_contracts_order,
+            sort_contracts_by_name: props.sort_contracts_by_name,
+            allow_duplicate_classes: props.allow_duplicate_classes,
+            size_limits_deny: props.contract_size_limits_deny,
+            max_contract_class_size: props.max_contract_class_size,
+            max_compiled_contract_class_size: props.max_compiled_contract_class_size,
             target_dir,
             target_name,
             extension_prefix: None,
+            artifacts_manifest_name: props.artifacts_manifest_name,
+            selector_provenance: None,
         }
     }
 
@@ -127,6 +331,164 @@ impl ArtifactsWriter {
         }
     }
 
+    /// Record the `build-external-contracts` selector which included each contract in the
+    /// artifacts manifest, with `selectors` keyed by the contract definition path.
+    pub fn with_selector_provenance(self, selectors: HashMap<String, ContractSelector>) -> Self {
+        Self {
+            selector_provenance: Some(selectors),
+            ..self
+        }
+    }
+
+    /// Warn about exceeded Starknet size limit, or fail if `contract-size-limits-deny` is set.
+    fn report_size_limit_exceeded(
+        &self,
+        ws: &Workspace<'_>,
+        message: String,
+    ) -> anyhow::Result<()> {
+        if self.size_limits_deny {
+            bail!("{}", message.trim_end());
+        }
+        ws.config().ui().warn(message);
+        Ok(())
+    }
+
+    /// Serializes a contract class in the configured artifacts format, exactly as written to its
+    /// file (before optional compression).
+    pub fn serialize_class(&self, name: &str, class: &impl Serialize) -> anyhow::Result<Vec<u8>> {
+        match self.format {
+            ArtifactsFormat::Json => serde_json::to_vec(class).map_err(anyhow::Error::from),
+            ArtifactsFormat::Msgpack => rmp_serde::to_vec_named(class).map_err(anyhow::Error::from),
+        }
+        .with_context(|| format!("failed to serialize {name}"))
+    }
+
+    /// Writes a contract class file, unless an identical one is already present in the target
+    /// directory, returning the size of the class serialized to JSON in bytes, which is what
+    /// Starknet size limits apply to.
+    ///
+    /// Each contract class file has a fingerprint, which is the digest of its serialized contents.
+    /// Skipping identical writes keeps file modification times intact on no-op rebuilds,
+    /// so that tools watching the target directory are not triggered needlessly.
+    /// Fingerprints are not used if artifacts are passed to a custom artifact sink.
+    ///
+    /// With `compress` enabled, the class is gzipped while being written, and the fingerprint
+    /// is the digest of its contents before compression.
+    fn write_contract_class(
+        &self,
+        file_name: &str,
+        ws: &Workspace<'_>,
+        class: &impl Serialize,
+    ) -> anyhow::Result<usize> {
+        let bytes = self.serialize_class(file_name, class)?;
+        let json_size = match self.format {
+            ArtifactsFormat::Json => bytes.len(),
+            ArtifactsFormat::Msgpack => serde_json::to_vec(class)
+                .with_context(|| format!("failed to serialize {file_name}"))?
+                .len(),
+        };
+
+        if ws.config().artifact_sink().is_some() {
+            self.write_class_bytes(file_name, ws, class, &bytes)?;
+            return Ok(json_size);
+        }
+
+        let digest = short_hash(&bytes);
+        let fingerprint_dir = self
+            .target_dir
+            .child(FINGERPRINT_DIR_NAME)
+            .child(CONTRACTS_FINGERPRINT_DIR_NAME);
+
+        let path = self.target_dir.path_unchecked().join(file_name);
+        if path.exists() && is_fresh(&fingerprint_dir, file_name, &digest)? {
+            trace!("contract class `{file_name}` is fresh, skipping write");
+            ws.record_artifact_written(&path);
+            return Ok(json_size);
+        }
+
+        self.write_class_bytes(file_name, ws, class, &bytes)?;
+
+        write_atomically(
+            file_name,
+            "fingerprint file",
+            &fingerprint_dir,
+            ws,
+            |mut file| {
+                file.write_all(digest.as_bytes())
+                    .with_context(|| format!("failed to write fingerprint of {file_name}"))
+            },
+        )?;
+        Ok(json_size)
+    }
+
+    /// Writes serialized `bytes` of a contract class, compressing them if `compress` is enabled.
+    ///
+    /// Compressed JSON classes are serialized straight into the gzip stream, and both original
+    /// and compressed sizes are reported.
+    fn write_class_bytes(
+        &self,
+        file_name: &str,
+        ws: &Workspace<'_>,
+        class: &impl Serialize,
+        bytes: &[u8],
+    ) -> anyhow::Result<()> {
+        if !self.compress {
+            return write_artifact(file_name, "output file", &self.target_dir, ws, |writer| {
+                writer
+                    .write_all(bytes)
+                    .with_context(|| format!("failed to write {file_name}"))
+            });
+        }
+
+        let (original_size, compressed_size) = match self.format {
+            ArtifactsFormat::Json => write_gzipped_json_with_byte_count(
+                file_name,
+                "output file",
+                &self.target_dir,
+                ws,
+                class,
+            )?,
+            ArtifactsFormat::Msgpack => {
+                write_gzipped_artifact(file_name, "output file", &self.target_dir, ws, |writer| {
+                    writer
+                        .write_all(bytes)
+                        .with_context(|| format!("failed to write {file_name}"))?;
+                    Ok(bytes.len())
+                })?
+            }
+        };
+        ws.config().ui().print(Status::new(
+            "Compressed",
+            &format!("{file_name} from {original_size} to {compressed_size} bytes"),
+        ));
+        Ok(())
+    }
+
+    /// Warn about different contracts which compiled to identical contract classes, as this usually
+    /// signals an accidental duplicate.
+    ///
+    /// The `class_digests` map digests of contract classes to paths of contracts they belong to.
+    fn report_duplicate_classes(
+        &self,
+        ws: &Workspace<'_>,
+        class_digests: BTreeMap<String, Vec<String>>,
+    ) {
+        for contract_paths in class_digests.into_values() {
+            if contract_paths.len() < 2 {
+                continue;
+            }
+            let contract_paths = contract_paths
+                .iter()
+                .sorted()
+                .map(|path| format!("`{path}`"))
+                .join(", ");
+            ws.config().ui().warn(format!(
+                "contracts {contract_paths} compiled to identical contract classes, \
+                set `allow-duplicate-classes = true` if this is intended"
+            ));
+        }
+    }
+
     pub fn write(
         self,
         contract_paths: Vec<String>,
@@ -135,14 +497,26 @@ impl ArtifactsWriter {
         casm_classes: &[Option<CasmContractClass>],
         db: &mut RootDatabase,
         ws: &Workspace<'_>,
-    ) -> anyhow::Result<()> {
-        let span = trace_span!("serialize_starknet");
+    ) -> anyhow::Result<Utf8PathBuf> {
+        let span = trace_span!("serialize_starknet", contracts = contracts.len());
         let _guard = span.enter();
 
-        let mut artifacts = StarknetArtifacts::default();
+        let mut artifacts = StarknetArtifacts {
+            format: self.format,
+            encoding: self.compress.then_some(ArtifactsEncoding::Gzip),
+            ..Default::default()
+        };
+        let class_extension = if self.compress {
+            format!("{}.gz", self.format.extension())
+        } else {
+            self.format.extension().to_string()
+        };
+        let mut bundle: BTreeMap<String, ContractBundleEntry<'_>> = BTreeMap::new();
+        let mut class_digests: BTreeMap<String, Vec<String>> = BTreeMap::new();
         let mut file_stem_calculator = ContractFileStemCalculator::new(contract_paths);
         let extension_prefix = self
             .extension_prefix
+            .as_ref()
             .map(|ext| format!(".{ext}"))
             .unwrap_or_default();
 
@@ -154,7 +528,12 @@ impl ArtifactsWriter {
             let package_name = contract_selector.package();
             let contract_stem = file_stem_calculator.get_stem(contract_selector.full_path());
 
-            let file_stem = format!("{}_{}", self.target_name, contract_stem.clone());
+            let file_stem = match self.layout {
+                ContractsLayout::Flat => format!("{}_{}", self.target_name, contract_stem),
+                ContractsLayout::Nested => {
+                    format!("{NESTED_CONTRACTS_DIR_NAME}/{package_name}/{contract_stem}")
+                }
+            };
 
             let mut artifact = ContractArtifacts::new(
                 package_name,
@@ -162,87 +541,174 @@ impl ArtifactsWriter {
                 contract_selector.full_path().as_str(),
                 &declaration.module_id().full_path(db),
             );
+            if let Some(selectors) = &self.selector_provenance {
+                artifact.selector = selectors
+                    .get(&contract_selector.full_path())
+                    .map(ContractSelector::full_path);
+            }
+
+            if !self.allow_duplicate_classes {
+                class_digests
+                    .entry(class_digest(class, casm_class.as_ref()))
+                    .or_default()
+                    .push(contract_selector.full_path());
+            }
 
             if self.sierra {
                 let sierra_felts = class.sierra_program.len();
                 if sierra_felts > MAX_SIERRA_PROGRAM_FELTS {
-                    ws.config().ui().warn(formatdoc! {r#"
+                    self.report_size_limit_exceeded(
+                        ws,
+                        formatdoc! {r#"
                         Sierra program exceeds maximum byte-code size on Starknet for contract `{}`:
                         {MAX_SIERRA_PROGRAM_FELTS} felts allowed. Actual size: {sierra_felts} felts.
-                    "#, contract_stem.clone()});
+                    "#, contract_stem.clone()},
+                    )?;
                 }
 
-                let file_name = format!("{file_stem}{extension_prefix}.contract_class.json");
-
-                let class_size = write_json_with_byte_count(
-                    &file_name,
-                    "output file",
-                    &self.target_dir,
-                    ws,
-                    class,
-                )?;
-                if class_size > MAX_CONTRACT_CLASS_BYTES {
+                let class_size = if self.bundle_only {
+                    serde_json::to_vec(class)?.len()
+                } else {
+                    let file_name =
+                        format!("{file_stem}{extension_prefix}.contract_class.{class_extension}");
+                    let class_size = self.write_contract_class(&file_name, ws, class)?;
+                    artifact.artifacts.sierra = Some(file_name);
+                    class_size
+                };
+                let max_class_size = self.max_contract_class_size;
+                if class_size > max_class_size {
                     // Debug info is omitted on Starknet.
-                    // Only warn if size without debug info exceeds the limit as well.
+                    // Only report if size without debug info exceeds the limit as well.
                     let rpc_class = ContractClassNoDebug::new(class);
                     let rpc_class_size = serde_json::to_vec(&rpc_class)?.len();
 
-                    if rpc_class_size > MAX_CONTRACT_CLASS_BYTES {
-                        ws.config().ui().warn(formatdoc! {r#"
+                    if rpc_class_size > max_class_size {
+                        self.report_size_limit_exceeded(ws, formatdoc! {r#"
                             Contract class size exceeds maximum allowed size on Starknet for contract `{}`:
-                            {MAX_CONTRACT_CLASS_BYTES} bytes allowed. Actual size (without debug info): {rpc_class_size} bytes.
-                        "#, contract_stem.clone()});
+                            {max_class_size} bytes allowed. Actual size (without debug info): {rpc_class_size} bytes.
+                        "#, contract_stem.clone()})?;
                     }
                 }
-                artifact.artifacts.sierra = Some(file_name);
+            }
+
+            if self.sierra_text {
+                let mut program = class.extract_sierra_program().with_context(|| {
+                    format!("{contract_name}: failed to extract Sierra program from contract class")
+                })?;
+                if let Some(debug_info) = &class.sierra_program_debug_info {
+                    debug_info.clone().populate(&mut program);
+                }
+
+                let file_name = format!("{file_stem}{extension_prefix}.contract_class.sierra");
+                write_string(&file_name, "output file", &self.target_dir, ws, program)?;
+                artifact.artifacts.sierra_text = Some(file_name);
             }
 
             if self.casm {
                 if let Some(casm_class) = casm_class {
                     let casm_felts = casm_class.bytecode.len();
                     if casm_felts > MAX_CASM_PROGRAM_FELTS {
-                        ws.config().ui().warn(formatdoc! {r#"
+                        self.report_size_limit_exceeded(ws, formatdoc! {r#"
                             CASM program exceeds maximum byte-code size on Starknet for contract `{}`:
                             {MAX_CASM_PROGRAM_FELTS} felts allowed. Actual size: {casm_felts} felts.
-                        "#, contract_stem.clone()});
+                        "#, contract_stem.clone()})?;
                     }
 
-                    let file_name =
-                        format!("{file_stem}{extension_prefix}.compiled_contract_class.json");
-
-                    let compiled_class_size = write_json_with_byte_count(
-                        &file_name,
-                        "output file",
-                        &self.target_dir,
-                        ws,
-                        casm_class,
-                    )?;
-                    if compiled_class_size > MAX_COMPILED_CONTRACT_CLASS_BYTES {
-                        ws.config().ui().warn(formatdoc! {r#"
+                    let compiled_class_size = if self.bundle_only {
+                        serde_json::to_vec(casm_class)?.len()
+                    } else {
+                        let file_name = format!(
+                            "{file_stem}{extension_prefix}.compiled_contract_class.{class_extension}"
+                        );
+                        let compiled_class_size =
+                            self.write_contract_class(&file_name, ws, casm_class)?;
+                        artifact.artifacts.casm = Some(file_name);
+                        compiled_class_size
+                    };
+                    let max_compiled_class_size = self.max_compiled_contract_class_size;
+                    if compiled_class_size > max_compiled_class_size {
+                        self.report_size_limit_exceeded(ws, formatdoc! {r#"
                             Compiled contract class size exceeds maximum allowed size on Starknet for contract `{}`:
-                            {MAX_COMPILED_CONTRACT_CLASS_BYTES} bytes allowed. Actual size: {compiled_class_size} bytes.
-                        "#, contract_stem.clone()});
+                            {max_compiled_class_size} bytes allowed. Actual size: {compiled_class_size} bytes.
+                        "#, contract_stem.clone()})?;
                     }
-                    artifact.artifacts.casm = Some(file_name);
                 }
             }
 
+            if self.native {
+                let file_name = format!(
+                    "{file_stem}{extension_prefix}.contract_class.{NATIVE_LIBRARY_EXTENSION}"
+                );
+                let path = self.target_dir.path_existent()?.join(&file_name);
+                if let Some(parent) = path.parent() {
+                    fsx::create_dir_all(parent)?;
+                }
+                compile_native(class, &path).with_context(|| {
+                    format!("{contract_name}: failed to compile contract to native library")
+                })?;
+                ws.record_artifact_written(&path);
+                artifact.artifacts.native = Some(file_name);
+            }
+
+            if self.bundle {
+                bundle.insert(
+                    contract_stem,
+                    ContractBundleEntry {
+                        sierra: self.sierra.then_some(class),
+                        casm: casm_class.as_ref().filter(|_| self.casm),
+                    },
+                );
+            }
+
             artifacts.contracts.push(artifact);
         }
 
-        artifacts.finish();
+        self.report_duplicate_classes(ws, class_digests);
 
-        write_json_with_byte_count(
-            &format!(
+        if self.bundle {
+            let bundle_file_name = format!(
+                "{}{extension_prefix}.contracts.bundle.json",
+                self.target_name
+            );
+            write_json_with_byte_count(
+                &bundle_file_name,
+                "contracts bundle file",
+                &self.target_dir,
+                ws,
+                &bundle,
+            )?;
+            artifacts.bundle = Some(bundle_file_name);
+        }
+
+        artifacts.finish(self.sort_contracts_by_name);
+
+        let artifacts_file_name = match &self.artifacts_manifest_name {
+            Some(name) => {
+                ensure!(
+                    !artifacts.file_names().any(|file_name| file_name == name),
+                    "artifacts manifest name `{name}` collides with a contract artifact file name"
+                );
+                name.clone()
+            }
+            None => format!(
                 "{}{extension_prefix}.starknet_artifacts.json",
                 self.target_name
             ),
+        };
+        if self.preserve_contracts_order {
+            let path = self.target_dir.path_unchecked().join(&artifacts_file_name);
+            if let Some(previous) = PreviousStarknetArtifacts::read(&path) {
+                artifacts.preserve_order_of(&previous);
+            }
+        }
+        write_json_with_byte_count(
+            &artifacts_file_name,
             "starknet artifacts file",
             &self.target_dir,
             ws,
             &artifacts,
         )?;
 
-        Ok(())
+        Ok(self.target_dir.path_existent()?.join(artifacts_file_name))
     }
 }
@@ -1,35 +1,61 @@
-use anyhow::{Context, Result, ensure};
-use cairo_lang_compiler::CompilerConfig;
+use anyhow::{Context, Result, anyhow, bail, ensure};
 use cairo_lang_compiler::db::RootDatabase;
+use cairo_lang_compiler::diagnostics::DiagnosticsReporter;
+use cairo_lang_compiler::{CompilerConfig, ensure_diagnostics};
 use cairo_lang_defs::db::DefsGroup;
-use cairo_lang_defs::ids::{ModuleId, NamedLanguageElementId};
+use cairo_lang_defs::ids::{LanguageElementId, ModuleId, NamedLanguageElementId};
+use cairo_lang_filesystem::cfg::{Cfg, CfgSet};
+use cairo_lang_filesystem::db::FilesGroup;
 use cairo_lang_filesystem::ids::{CrateId, CrateLongId};
+use cairo_lang_parser::db::ParserGroup;
+use cairo_lang_plugins::plugins::HasItemsInCfgEx;
 use cairo_lang_semantic::db::SemanticGroup;
 use cairo_lang_semantic::items::us::SemanticUseEx;
 use cairo_lang_semantic::items::visibility::Visibility;
 use cairo_lang_semantic::resolve::ResolvedGenericItem::Module;
-use cairo_lang_starknet::compile::compile_prepared_db;
+use cairo_lang_starknet::compile::compile_contract_with_prepared_and_checked_db;
 use cairo_lang_starknet::contract::{ContractDeclaration, find_contracts, module_contract};
 use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
 use cairo_lang_starknet_classes::contract_class::ContractClass;
-use cairo_lang_syntax::node::TypedSyntaxNode;
-use cairo_lang_syntax::node::ast::OptionAliasClause;
+use cairo_lang_syntax::node::ast::{MaybeModuleBody, ModuleItem, OptionAliasClause};
+use cairo_lang_syntax::node::db::SyntaxGroup;
+use cairo_lang_syntax::node::helpers::{BodyItems, QueryAttrs};
+use cairo_lang_syntax::node::{Terminal, TypedStablePtr, TypedSyntaxNode, ast};
+use camino::Utf8Path;
 use itertools::Itertools;
+use salsa::ParallelDatabase;
 use serde::{Deserialize, Serialize};
-use smol_str::SmolStr;
-use std::collections::HashSet;
+use serde_untagged::UntaggedEnumVisitor;
+use std::collections::{HashMap, HashSet};
+use std::io::{self, IsTerminal};
 use std::iter::zip;
-use tracing::{debug, trace, trace_span};
+use std::num::NonZeroUsize;
+use std::process::Command;
+use std::sync::Mutex;
+use std::thread;
+use tracing::{Span, debug, field, trace, trace_span};
 
+use super::artifacts_writer::{MAX_COMPILED_CONTRACT_CLASS_BYTES, MAX_CONTRACT_CLASS_BYTES};
 use super::contract_selector::ContractSelector;
-use crate::compiler::compilers::starknet_contract::contract_selector::GLOB_PATH_SELECTOR;
-use crate::compiler::compilers::starknet_contract::validations::check_allowed_libfuncs;
+use super::determinism::ensure_deterministic;
+use super::native;
+use super::selectors_cache::{ExpandedSelectors, ExpandedSelectorsKey};
+use crate::compiler::compilers::starknet_contract::contract_selector::CAIRO_PATH_SEPARATOR;
+use crate::compiler::compilers::starknet_contract::validations::{
+    check_allowed_libfuncs, check_required_interfaces, ensure_contracts_selected,
+    ensure_external_contracts_dependencies,
+};
 use crate::compiler::compilers::{ArtifactsWriter, ensure_gas_enabled};
+use crate::compiler::db::{ScarbDatabase, build_scarb_root_database};
 use crate::compiler::helpers::{build_compiler_config, collect_main_crate_ids};
-use crate::compiler::{CairoCompilationUnit, CompilationUnitAttributes, Compiler};
-use crate::core::{TargetKind, Workspace};
+use crate::compiler::{
+    CairoCompilationUnit, CompilationUnitAttributes, CompilationUnitComponent, Compiler,
+};
+use crate::core::{PackageName, TargetKind, Workspace};
 use crate::internal::serdex::RelativeUtf8PathBuf;
+use crate::process::exec_piping;
 use scarb_ui::Ui;
+use scarb_ui::components::{Spinner, SpinnerHandle, Status};
 
 // TODO(#111): starknet-contract should be implemented as an extension.
 pub struct StarknetContractCompiler;
@@ -38,24 +64,208 @@ pub struct StarknetContractCompiler;
 #[serde(rename_all = "kebab-case")]
 pub struct Props {
     pub sierra: bool,
-    pub casm: bool,
+    pub emit_sierra_text: bool,
+    pub casm: CasmProps,
     pub casm_add_pythonic_hints: bool,
     pub allowed_libfuncs: bool,
     pub allowed_libfuncs_deny: bool,
     pub allowed_libfuncs_list: Option<SerdeListSelector>,
     pub build_external_contracts: Option<Vec<ContractSelector>>,
+    pub include_main_contracts: bool,
+    pub bundle: bool,
+    pub bundle_only: bool,
+    pub contract_size_limits_deny: bool,
+    pub max_contract_class_size: usize,
+    pub max_compiled_contract_class_size: usize,
+    pub post_build: Option<String>,
+    pub native: bool,
+    pub artifacts_manifest_name: Option<String>,
+    pub emit_selector_provenance: bool,
+    pub layout: ContractsLayout,
+    pub format: ArtifactsFormat,
+    pub compress: bool,
+    pub deny_unmatched_selectors: bool,
+    pub preserve_contracts_order: bool,
+    pub sort_contracts_by_name: bool,
+    pub allow_duplicate_classes: bool,
+    pub require_interfaces: Vec<RequiredInterface>,
 }
 
 impl Default for Props {
     fn default() -> Self {
         Self {
             sierra: true,
-            casm: false,
+            emit_sierra_text: false,
+            casm: CasmProps::Enabled(false),
             casm_add_pythonic_hints: false,
             allowed_libfuncs: true,
             allowed_libfuncs_deny: false,
             allowed_libfuncs_list: None,
             build_external_contracts: None,
+            include_main_contracts: true,
+            bundle: false,
+            bundle_only: false,
+            contract_size_limits_deny: false,
+            max_contract_class_size: MAX_CONTRACT_CLASS_BYTES,
+            max_compiled_contract_class_size: MAX_COMPILED_CONTRACT_CLASS_BYTES,
+            post_build: None,
+            native: false,
+            artifacts_manifest_name: None,
+            emit_selector_provenance: false,
+            layout: ContractsLayout::default(),
+            format: ArtifactsFormat::default(),
+            compress: false,
+            deny_unmatched_selectors: false,
+            preserve_contracts_order: false,
+            sort_contracts_by_name: true,
+            allow_duplicate_classes: false,
+            require_interfaces: Vec::new(),
+        }
+    }
+}
+
+/// Placement of contract class files in the target directory.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ContractsLayout {
+    /// `[target name]_[contract name].contract_class.json` files directly in the target directory.
+    #[default]
+    Flat,
+    /// `contracts/[package name]/[contract name].contract_class.json` files.
+    Nested,
+}
+
+/// Serialization format of contract class files.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ArtifactsFormat {
+    /// `.contract_class.json` files, as accepted by Starknet JSON-RPC.
+    #[default]
+    Json,
+    /// `.contract_class.msgpack` files, with the same structure as JSON ones, but smaller.
+    Msgpack,
+}
+
+impl ArtifactsFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            Self::Json => "json",
+            Self::Msgpack => "msgpack",
+        }
+    }
+
+    pub fn is_json(&self) -> bool {
+        *self == Self::Json
+    }
+}
+
+/// The `casm` target property, either a flag or a table of CASM compilation options.
+///
+/// Providing the table enables CASM generation.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum CasmProps {
+    Enabled(bool),
+    Options(CasmOptions),
+}
+
+/// Options of Sierra to CASM compilation of contracts.
+///
+/// Gas settings are deliberately not configurable, as Starknet only accepts contract classes
+/// compiled with gas usage checks and the default gas costs.
+#[derive(Debug, Default, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+pub struct CasmOptions {
+    /// Maximum size of the CASM bytecode of a single contract, in felts.
+    pub max_bytecode_size: Option<usize>,
+    pub add_pythonic_hints: Option<bool>,
+}
+
+impl<'de> Deserialize<'de> for CasmProps {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        UntaggedEnumVisitor::new()
+            .bool(|enabled| Ok(CasmProps::Enabled(enabled)))
+            .map(|map| map.deserialize().map(CasmProps::Options))
+            .deserialize(deserializer)
+    }
+}
+
+impl CasmProps {
+    pub fn is_enabled(&self) -> bool {
+        match self {
+            CasmProps::Enabled(enabled) => *enabled,
+            CasmProps::Options(_) => true,
+        }
+    }
+
+    fn options(&self) -> Option<&CasmOptions> {
+        match self {
+            CasmProps::Enabled(_) => None,
+            CasmProps::Options(options) => Some(options),
+        }
+    }
+}
+
+impl Props {
+    fn casm_add_pythonic_hints(&self) -> bool {
+        self.casm
+            .options()
+            .and_then(|options| options.add_pythonic_hints)
+            .unwrap_or(self.casm_add_pythonic_hints)
+    }
+
+    fn casm_max_bytecode_size(&self) -> usize {
+        self.casm
+            .options()
+            .and_then(|options| options.max_bytecode_size)
+            .unwrap_or(usize::MAX)
+    }
+
+    fn validate_casm_options(&self) -> Result<()> {
+        let Some(options) = self.casm.options() else {
+            return Ok(());
+        };
+        ensure!(
+            !self.casm_add_pythonic_hints || options.add_pythonic_hints.is_none(),
+            "`casm-add-pythonic-hints` and `casm.add-pythonic-hints` target properties \
+            cannot be used together, use `casm.add-pythonic-hints` only"
+        );
+        ensure!(
+            options.max_bytecode_size != Some(0),
+            "`casm.max-bytecode-size` target property must be greater than zero"
+        );
+        Ok(())
+    }
+}
+
+/// An interface which built contracts must implement, either by all contracts of the target,
+/// or only by the selected ones.
+#[derive(Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum RequiredInterface {
+    All(String),
+    Selected {
+        interface: String,
+        contracts: Vec<String>,
+    },
+}
+
+impl RequiredInterface {
+    pub fn interface(&self) -> &str {
+        match self {
+            RequiredInterface::All(interface) => interface,
+            RequiredInterface::Selected { interface, .. } => interface,
+        }
+    }
+
+    /// Full paths of contracts required to implement the interface, `None` meaning all contracts.
+    pub fn contracts(&self) -> Option<&[String]> {
+        match self {
+            RequiredInterface::All(_) => None,
+            RequiredInterface::Selected { contracts, .. } => Some(contracts),
         }
     }
 }
@@ -80,79 +290,212 @@ impl Compiler for StarknetContractCompiler {
         db: &mut RootDatabase,
         ws: &Workspace<'_>,
     ) -> Result<()> {
-        let props: Props = unit.main_component().targets.target_props()?;
-        if !props.sierra && !props.casm {
+        let mut props: Props = unit.main_component().targets.target_props()?;
+        if !unit.build_external_contracts {
+            props.build_external_contracts = None;
+        }
+        if !props.sierra && !props.casm.is_enabled() && !props.emit_sierra_text {
             ws.config().ui().warn(
-                "both Sierra and CASM Starknet contract targets have been disabled, \
+                "Sierra, textual Sierra and CASM Starknet contract targets have been disabled, \
                 Scarb will not produce anything",
             );
         }
 
+        ensure!(
+            !props.native || native::is_native_supported(),
+            native::NATIVE_NOT_BUILT_ERROR
+        );
+
+        props.validate_casm_options()?;
+
         ensure_gas_enabled(db)?;
 
-        if let Some(external_contracts) = props.build_external_contracts.clone() {
-            for path in external_contracts.iter() {
-                ensure!(
-                    path.0.matches(GLOB_PATH_SELECTOR).count() <= 1,
-                    "external contract path `{}` has multiple global path selectors, only one '*' selector is allowed",
-                    path.0
-                );
-            }
+        if let Some(name) = &props.artifacts_manifest_name {
+            ensure!(
+                name.ends_with(".json") && !name.contains(['/', '\\']),
+                "artifacts manifest name `{name}` must be a file name with `.json` extension"
+            );
         }
 
+        ensure_contracts_selected(
+            props.include_main_contracts,
+            &props.build_external_contracts,
+        )?;
+
         let target_dir = unit.target_dir(ws);
 
         let main_crate_ids = collect_main_crate_ids(unit, db);
 
         let compiler_config = build_compiler_config(db, unit, &main_crate_ids, cached_crates, ws);
 
-        let contracts = find_project_contracts(
+        let ProjectContracts {
+            contracts,
+            selectors,
+            ..
+        } = find_project_contracts(
             db,
-            ws.config().ui(),
+            ws,
             unit,
-            main_crate_ids.clone(),
+            if props.include_main_contracts {
+                main_crate_ids.clone()
+            } else {
+                Vec::new()
+            },
             props.build_external_contracts.clone(),
+            props.deny_unmatched_selectors,
         )?;
 
         let CompiledContracts {
             contract_paths,
             contracts,
             classes,
-        } = get_compiled_contracts(contracts, compiler_config, db)?;
+        } = get_compiled_contracts(contracts, compiler_config, db, ws.config().ui())?;
 
         check_allowed_libfuncs(&props, &contracts, &classes, db, unit, ws)?;
 
-        let casm_classes: Vec<Option<CasmContractClass>> = if props.casm {
-            let span = trace_span!("compile_starknet_casm");
-            let _guard = span.enter();
-
-            zip(&contracts, &classes)
-                .map(|(decl, class)| -> Result<_> {
-                    let contract_name = decl.submodule_id.name(db);
-                    let casm_class = CasmContractClass::from_contract_class(
-                        class.clone(),
-                        props.casm_add_pythonic_hints,
-                        usize::MAX,
-                    )
-                    .with_context(|| {
-                        format!("{contract_name}: failed to compile Sierra contract to CASM")
-                    })?;
-                    Ok(Some(casm_class))
-                })
-                .try_collect()?
+        check_required_interfaces(&props, &contracts, &classes, db, unit)?;
+
+        let casm_classes = compile_casm_classes(&props, &contracts, &classes, db)?;
+
+        let recompiled = if unit.verify_deterministic {
+            Some(recompile_contracts(&props, &contracts, db, unit, ws)?)
         } else {
-            classes.iter().map(|_| None).collect()
+            None
         };
 
         let target_name = &unit.main_component().target_name();
 
-        let writer = ArtifactsWriter::new(target_name.clone(), target_dir, props);
-        writer.write(contract_paths, &contracts, &classes, &casm_classes, db, ws)?;
+        let post_build = props.post_build.clone();
+        let emit_selector_provenance = props.emit_selector_provenance;
+        let mut writer = ArtifactsWriter::new(target_name.clone(), target_dir, props);
+        if emit_selector_provenance {
+            writer = writer.with_selector_provenance(selectors);
+        }
+
+        if let Some((recompiled_classes, recompiled_casm_classes)) = recompiled {
+            ensure_deterministic(
+                &writer,
+                &contract_paths,
+                (&classes, &casm_classes),
+                (&recompiled_classes, &recompiled_casm_classes),
+            )?;
+        }
+
+        let artifacts_path =
+            writer.write(contract_paths, &contracts, &classes, &casm_classes, db, ws)?;
+
+        if let Some(post_build) = post_build {
+            run_post_build(&post_build, &artifacts_path, ws)?;
+        }
 
         Ok(())
     }
 }
 
+/// Compiles `contracts` once again in a fresh database, so that no query results of the first
+/// compilation are reused, for the purpose of the determinism check.
+fn recompile_contracts(
+    props: &Props,
+    contracts: &[ContractDeclaration],
+    db: &RootDatabase,
+    unit: &CairoCompilationUnit,
+    ws: &Workspace<'_>,
+) -> Result<(Vec<ContractClass>, Vec<Option<CasmContractClass>>)> {
+    let ScarbDatabase {
+        db: mut fresh_db, ..
+    } = build_scarb_root_database(unit, ws, Default::default())?;
+    let contracts = contracts
+        .iter()
+        .map(|decl| {
+            let module_id = decl.module_id();
+            let crate_long_id = db.lookup_intern_crate(module_id.owning_crate(db));
+            let path = module_id.full_path(db);
+            find_contract_by_path(&fresh_db, crate_long_id, &path)
+                .with_context(|| format!("contract `{path}` not found in the second compilation"))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let main_crate_ids = collect_main_crate_ids(unit, &fresh_db);
+    let mut compiler_config = build_compiler_config(&fresh_db, unit, &main_crate_ids, &[], ws);
+    // Diagnostics have already been reported by the first compilation.
+    compiler_config.diagnostics_reporter = DiagnosticsReporter::ignoring();
+    let recompiled =
+        get_compiled_contracts(contracts, compiler_config, &mut fresh_db, ws.config().ui())?;
+    let casm_classes =
+        compile_casm_classes(props, &recompiled.contracts, &recompiled.classes, &fresh_db)?;
+    Ok((recompiled.classes, casm_classes))
+}
+
+/// Compile Sierra contract classes to CASM, if requested by the `casm` target property.
+fn compile_casm_classes(
+    props: &Props,
+    contracts: &[ContractDeclaration],
+    classes: &[ContractClass],
+    db: &RootDatabase,
+) -> Result<Vec<Option<CasmContractClass>>> {
+    if !props.casm.is_enabled() {
+        return Ok(classes.iter().map(|_| None).collect());
+    }
+    let add_pythonic_hints = props.casm_add_pythonic_hints();
+    let max_bytecode_size = props.casm_max_bytecode_size();
+
+    let span = trace_span!("compile_starknet_casm", contracts = classes.len());
+    let _guard = span.enter();
+
+    zip(contracts, classes)
+        .map(|(decl, class)| -> Result<_> {
+            let contract_name = decl.submodule_id.name(db);
+            let casm_class = CasmContractClass::from_contract_class(
+                class.clone(),
+                add_pythonic_hints,
+                max_bytecode_size,
+            )
+            .with_context(|| {
+                format!("{contract_name}: failed to compile Sierra contract to CASM")
+            })?;
+            Ok(Some(casm_class))
+        })
+        .try_collect()
+}
+
+/// Run user-defined `post-build` command on written Starknet artifacts.
+///
+/// The path to the artifacts file is passed as the last argument and as `SCARB_ARTIFACTS`
+/// environment variable, and the command is run in the target directory.
+fn run_post_build(post_build: &str, artifacts_path: &Utf8Path, ws: &Workspace<'_>) -> Result<()> {
+    let args = shell_words::split(post_build)
+        .with_context(|| format!("failed to parse `post-build` command: {post_build}"))?;
+    let Some((program, args)) = args.split_first() else {
+        bail!("`post-build` command must not be empty");
+    };
+
+    let mut cmd = Command::new(program);
+    cmd.args(args)
+        .arg(artifacts_path)
+        .env("SCARB_ARTIFACTS", artifacts_path)
+        .current_dir(
+            artifacts_path
+                .parent()
+                .expect("artifacts file must be in a directory"),
+        );
+
+    let ui = ws.config().ui();
+    let stderr = Mutex::new(Vec::new());
+    let result = exec_piping(
+        &mut cmd,
+        ws.config(),
+        |line: &str| ui.print(line),
+        |line: &str| stderr.lock().unwrap().push(line.to_string()),
+    );
+
+    result.map_err(|err| {
+        let stderr = stderr.into_inner().unwrap();
+        if !stderr.is_empty() {
+            ui.error(stderr.join("\n"));
+        }
+        err.context(format!("`post-build` command failed: {post_build}"))
+    })
+}
+
 pub struct CompiledContracts {
     pub contract_paths: Vec<String>,
     pub contracts: Vec<ContractDeclaration>,
@@ -161,8 +504,9 @@ pub struct CompiledContracts {
 
 pub fn get_compiled_contracts(
     contracts: Vec<ContractDeclaration>,
-    compiler_config: CompilerConfig<'_>,
+    mut compiler_config: CompilerConfig<'_>,
     db: &mut RootDatabase,
+    ui: Ui,
 ) -> Result<CompiledContracts> {
     let contract_paths = contracts
         .iter()
@@ -170,10 +514,11 @@ pub fn get_compiled_contracts(
         .collect::<Vec<_>>();
     trace!(contracts = ?contract_paths);
 
-    let span = trace_span!("compile_starknet");
+    let span = trace_span!("compile_starknet", contracts = contracts.len());
     let classes = {
         let _guard = span.enter();
-        compile_prepared_db(db, &contracts.iter().collect::<Vec<_>>(), compiler_config)?
+        ensure_diagnostics(db, &mut compiler_config.diagnostics_reporter)?;
+        compile_contracts_in_parallel(&contracts, &contract_paths, &compiler_config, db, ui, &span)?
     };
     Ok(CompiledContracts {
         contract_paths,
@@ -182,170 +527,791 @@ pub fn get_compiled_contracts(
     })
 }
 
+/// Generate contract classes for already checked `contracts`, using a pool of worker threads.
+///
+/// Each worker compiles a subset of contracts on its own snapshot of the database,
+/// because the database itself cannot be shared between threads.
+/// Returned classes are ordered as the input `contracts`.
+fn compile_contracts_in_parallel(
+    contracts: &[ContractDeclaration],
+    contract_paths: &[String],
+    compiler_config: &CompilerConfig<'_>,
+    db: &RootDatabase,
+    ui: Ui,
+    parent_span: &Span,
+) -> Result<Vec<ContractClass>> {
+    // The diagnostics reporter cannot be shared between threads, and diagnostics have already
+    // been checked at this point, so each worker gets a config without one.
+    let replace_ids = compiler_config.replace_ids;
+    let inlining_strategy = compiler_config.inlining_strategy;
+    let add_statements_functions = compiler_config.add_statements_functions;
+    let add_statements_code_locations = compiler_config.add_statements_code_locations;
+
+    let workers = thread::available_parallelism()
+        .map(NonZeroUsize::get)
+        .unwrap_or(1)
+        .clamp(1, contracts.len().max(1));
+    let progress = Mutex::new(ContractsCompilationProgress::new(ui, contracts.len()));
+
+    let classes = thread::scope(|s| {
+        let handles = (0..workers)
+            .map(|worker| {
+                let snapshot = db.snapshot();
+                let progress = &progress;
+                thread::Builder::new()
+                    .name(format!("scarb compile contracts {worker}"))
+                    .spawn_scoped(s, move || {
+                        let compiler_config = CompilerConfig {
+                            replace_ids,
+                            inlining_strategy,
+                            add_statements_functions,
+                            add_statements_code_locations,
+                            ..CompilerConfig::default()
+                        };
+                        zip(contracts, contract_paths)
+                            .enumerate()
+                            .skip(worker)
+                            .step_by(workers)
+                            .map(|(index, (decl, contract_path))| {
+                                let span = trace_span!(
+                                    parent: parent_span,
+                                    "compile_starknet_contract",
+                                    contract = %contract_path
+                                );
+                                let _guard = span.enter();
+                                let class = compile_contract_with_prepared_and_checked_db(
+                                    &snapshot,
+                                    decl,
+                                    &compiler_config,
+                                );
+                                progress.lock().unwrap().advance();
+                                (index, class)
+                            })
+                            .collect_vec()
+                    })
+                    .context("failed to spawn contract compiler thread")
+            })
+            .collect::<Result<Vec<_>>>()?;
+        handles
+            .into_iter()
+            .map(|handle| {
+                handle
+                    .join()
+                    .map_err(|_| anyhow!("contract compiler thread has panicked"))
+            })
+            .flatten_ok()
+            .collect::<Result<Vec<_>>>()
+    })?;
+
+    in_input_order(classes)
+}
+
+/// Restore the order of input contracts from `(index, result)` pairs produced by workers,
+/// so that the output does not depend on how contracts were distributed between threads.
+fn in_input_order<T>(mut results: Vec<(usize, Result<T>)>) -> Result<Vec<T>> {
+    results.sort_by_key(|(index, _)| *index);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+/// Reports the progress of compiling contracts one by one.
+///
+/// Renders a spinner when attached to a terminal, and falls back to periodic (verbose) status
+/// lines otherwise, so that non-interactive logs are not flooded.
+struct ContractsCompilationProgress {
+    ui: Ui,
+    spinner: Option<SpinnerHandle>,
+    interactive: bool,
+    current: usize,
+    total: usize,
+}
+
+impl ContractsCompilationProgress {
+    /// Number of status lines printed in non-interactive mode for the whole compilation.
+    const STATUS_LINES: usize = 10;
+
+    fn new(ui: Ui, total: usize) -> Self {
+        let interactive = io::stdout().is_terminal();
+        let spinner = interactive
+            .then(|| ui.widget(Spinner::new(Self::message(0, total))))
+            .flatten();
+        Self {
+            ui,
+            spinner,
+            interactive,
+            current: 0,
+            total,
+        }
+    }
+
+    fn advance(&mut self) {
+        self.current += 1;
+        let message = Self::message(self.current, self.total);
+        if let Some(spinner) = &self.spinner {
+            spinner.set_message(message);
+        } else if !self.interactive {
+            let step = self.total.div_ceil(Self::STATUS_LINES).max(1);
+            if self.current % step == 0 || self.current == self.total {
+                self.ui.verbose(Status::new("Compiling", &message));
+            }
+        }
+    }
+
+    fn message(current: usize, total: usize) -> String {
+        format!("contracts ({current}/{total})")
+    }
+}
+
+/// Contracts to be built for a compilation unit.
+pub struct ProjectContracts {
+    pub contracts: Vec<ContractDeclaration>,
+    /// The `build-external-contracts` selector which included each external contract,
+    /// keyed by the contract definition path.
+    pub selectors: HashMap<String, ContractSelector>,
+    /// All `build-external-contracts` selectors, resolved against the main package, in the order
+    /// they were provided.
+    pub external_selectors: Vec<ContractSelector>,
+    /// External contract selectors which did not match any contract, in the order they were
+    /// provided.
+    pub unmatched_selectors: Vec<ContractSelector>,
+}
+
 pub fn find_project_contracts(
     db: &dyn SemanticGroup,
-    ui: Ui,
+    ws: &Workspace<'_>,
     unit: &CairoCompilationUnit,
     main_crate_ids: Vec<CrateId>,
     external_contracts: Option<Vec<ContractSelector>>,
-) -> Result<Vec<ContractDeclaration>> {
-    let span = trace_span!("find_internal_contracts");
+    deny_unmatched_selectors: bool,
+) -> Result<ProjectContracts> {
+    let span = trace_span!("find_internal_contracts", contracts = field::Empty);
     let internal_contracts = {
         let _guard = span.enter();
-        find_contracts(db, &main_crate_ids)
+        let contracts = find_contracts(db, &main_crate_ids);
+        span.record("contracts", contracts.len());
+        contracts
     };
 
-    let span = trace_span!("find_external_contracts");
+    let span = trace_span!("find_external_contracts", contracts = field::Empty);
+    let mut selectors = HashMap::new();
+    let mut external_selectors = Vec::new();
+    let mut all_unmatched_selectors = Vec::new();
     let external_contracts: Vec<ContractDeclaration> = if let Some(external_contracts) =
         external_contracts
     {
         let _guard = span.enter();
+        let main_package = &unit.main_component().package.id.name;
+        let external_contracts = external_contracts
+            .iter()
+            .map(|selector| selector.resolve_crate(main_package))
+            .collect_vec();
         debug!("external contracts selectors: {:?}", external_contracts);
 
-        let crate_ids = external_contracts
+        ensure_external_contracts_dependencies(unit, &external_contracts)?;
+
+        let mut contracts = Vec::new();
+        let mut unmatched = HashSet::new();
+        let mut feature_gated_modules = Vec::new();
+        for (name, version) in external_contracts
             .iter()
-            .map(|selector| selector.package().into())
+            .map(|selector| (selector.package(), selector.version()))
             .unique()
-            .map(|name: SmolStr| {
-                let discriminator = unit
-                    .components()
-                    .iter()
-                    .find(|component| component.package.id.name.to_smol_str() == name)
-                    .and_then(|component| component.id.to_discriminator());
-                db.intern_crate(CrateLongId::Real {
-                    name,
-                    discriminator,
-                })
-            })
-            .collect::<Vec<_>>();
-        let contracts = find_contracts(db, crate_ids.as_ref());
-        let mut filtered_contracts: Vec<ContractDeclaration> = contracts
-            .into_iter()
-            .filter(|decl| {
-                let contract_path = decl.module_id().full_path(db.upcast());
-                external_contracts
-                    .iter()
-                    .any(|selector| contract_matches(selector, contract_path.as_str()))
-            })
-            .collect();
+        {
+            let selectors = external_contracts
+                .iter()
+                .filter(|selector| selector.package() == name && selector.version() == version)
+                .cloned()
+                .collect_vec();
 
-        let mut matched_selectors: HashSet<ContractSelector> = external_contracts
-            .iter()
-            .filter(|selector| {
-                filtered_contracts.iter().any(|decl| {
-                    let contract_path = decl.module_id().full_path(db.upcast());
-                    contract_matches(selector, contract_path.as_str())
-                })
-            })
-            .cloned()
-            .collect();
+            let Some(component) = select_component(unit, &name, version, &selectors)? else {
+                unmatched.extend(selectors);
+                continue;
+            };
+            let crate_id = db.intern_crate(CrateLongId::Real {
+                name: name.to_smol_str(),
+                discriminator: component.id.to_discriminator(),
+            });
 
-        // Find selected reexports.
-        for crate_id in crate_ids {
-            let modules = db.crate_modules(crate_id);
-            for module_id in modules.iter() {
-                let Ok(module_uses) = db.module_uses(*module_id) else {
+            // Components without own cfg set are compiled with the one of the unit.
+            let cfg_set = component.cfg_set.as_ref().unwrap_or(&unit.cfg_set);
+            let key = ExpandedSelectorsKey {
+                selectors: selectors.clone(),
+                component_id: component.id.clone(),
+                cfg_set: cfg_set.clone(),
+            };
+            let expanded = ws.contract_selectors_cache().get_or_expand(key, || {
+                let expanded = expand_contract_selectors(db, crate_id, cfg_set, &selectors);
+                // Hint only when expanding, so that units sharing the selectors do not repeat it.
+                for (selector, count) in &expanded.recursive_hints {
+                    let recursive = selector.to_recursive().unwrap();
+                    ws.config().ui().verbose(format!(
+                        "help: selector `{selector}` does not match {count} contract(s) defined \
+                        in nested submodules, use `{recursive}` to select them recursively",
+                        selector = selector.full_path(),
+                        recursive = recursive.full_path(),
+                    ));
+                }
+                expanded
+            });
+            for (crate_long_id, path, selector) in &expanded.contracts {
+                let Some(contract) = find_contract_by_path(db, crate_long_id.clone(), path) else {
+                    // Should not happen for units with the same cfg set, but report the selector
+                    // as unmatched rather than silently dropping the contract.
+                    unmatched.insert(selector.clone());
                     continue;
                 };
-                let module_with_reexport = module_id.full_path(db.upcast());
-                let matched_contracts = module_uses
-                    .iter()
-                    .filter_map(|(use_id, use_path)| {
-                        let use_alias = match use_path.alias_clause(db.upcast()) {
-                            OptionAliasClause::Empty(_) => None,
-                            OptionAliasClause::AliasClause(alias_clause) => Some(
-                                alias_clause
-                                    .alias(db.upcast())
-                                    .as_syntax_node()
-                                    .get_text_without_trivia(db.upcast()),
-                            ),
-                        };
-                        let visibility = db
-                            .module_item_info_by_name(*module_id, use_id.name(db.upcast()))
-                            .ok()??
-                            .visibility;
-                        if visibility == Visibility::Public {
-                            Some((db.use_resolved_item(*use_id).ok()?, use_alias))
-                        } else {
-                            None
-                        }
-                    })
-                    .filter_map(|(use_item, use_alias)| match use_item {
-                        Module(module_id) => {
-                            module_id.name(db.upcast());
-                            Some((module_id, use_alias))
-                        }
-                        _ => None,
-                    })
-                    .flat_map(|(module_id, use_alias)| {
-                        let exported_module_path = module_id.full_path(db.upcast());
-                        let exported_module_name =
-                            use_alias.unwrap_or_else(|| module_id.name(db.upcast()).to_string());
-                        let mut submodules = Vec::new();
-                        collect_modules_under(db.upcast(), &mut submodules, module_id);
-                        submodules
-                            .iter()
-                            .filter_map(|module_id| {
-                                let contract = module_contract(db, *module_id)?;
-                                let contract_path = contract.module_id().full_path(db.upcast());
-                                let exported_contract_path =
-                                    contract_path.replace(&exported_module_path, "");
-                                let exported_contract_path = format!(
-                                    "{}::{exported_module_name}{exported_contract_path}",
-                                    &module_with_reexport
-                                );
-                                let selectors_used = external_contracts
-                                    .iter()
-                                    .filter(|selector| {
-                                        contract_matches(selector, exported_contract_path.as_str())
-                                    })
-                                    .map(|c| (*c).clone())
-                                    .collect_vec();
-                                let any_matched = !selectors_used.is_empty();
-                                matched_selectors.extend(selectors_used);
-                                any_matched.then_some(contract)
-                            })
-                            .collect_vec()
-                    })
-                    .collect_vec();
-                filtered_contracts.extend(matched_contracts);
+                selectors
+                    .entry(path.clone())
+                    .or_insert_with(|| selector.clone());
+                contracts.push(contract);
             }
+            feature_gated_modules.extend(expanded.feature_gated_modules.iter().cloned());
+            unmatched.extend(expanded.unmatched_selectors.iter().cloned());
         }
-
-        let never_matched = external_contracts
+        let contracts = dedup_contracts(db, contracts);
+        let (feature_gated_selectors, unmatched_selectors): (Vec<_>, Vec<_>) = external_contracts
             .iter()
-            .filter(|selector| !matched_selectors.contains(*selector))
+            .filter(|selector| unmatched.contains(*selector))
+            .map(|selector| {
+                let gated = feature_gated_modules
+                    .iter()
+                    .find(|module| module.hides(selector));
+                (selector, gated)
+            })
+            .partition(|(_, gated)| gated.is_some());
+        let unmatched_selectors = unmatched_selectors
+            .into_iter()
+            .map(|(selector, _)| selector)
             .collect_vec();
-        if !never_matched.is_empty() {
-            let never_matched = never_matched
+
+        if !feature_gated_selectors.is_empty() {
+            let disabled = feature_gated_selectors
+                .into_iter()
+                .filter_map(|(selector, gated)| {
+                    gated.map(|gated| {
+                        format!(
+                            "`{}` (requires feature `{}` of package `{}`)",
+                            selector.full_path(),
+                            gated.features.join("`, `"),
+                            selector.package(),
+                        )
+                    })
+                })
+                .join(", ");
+            ws.config().ui().warn(format!(
+                "external contracts not found due to disabled features for selectors: {disabled}"
+            ));
+        }
+
+        if !unmatched_selectors.is_empty() {
+            let never_matched = unmatched_selectors
                 .iter()
                 .map(|selector| selector.full_path())
                 .collect_vec()
                 .join("`, `");
-            ui.warn(format!(
-                "external contracts not found for selectors: `{never_matched}`"
-            ));
+            let message = format!("external contracts not found for selectors: `{never_matched}`");
+            if deny_unmatched_selectors {
+                bail!("{message}");
+            }
+            ws.config().ui().warn(message);
         }
 
-        filtered_contracts
+        all_unmatched_selectors = external_contracts
+            .iter()
+            .filter(|selector| unmatched.contains(*selector))
+            .cloned()
+            .collect();
+        external_selectors = external_contracts;
+
+        span.record("contracts", contracts.len());
+        contracts
     } else {
         debug!("no external contracts selected");
         Vec::new()
     };
 
-    // Deduplicate found contracts by contract path.
-    let mut contracts_found = internal_contracts
+    Ok(ProjectContracts {
+        contracts: dedup_contracts(db, internal_contracts.into_iter().chain(external_contracts)),
+        selectors,
+        external_selectors,
+        unmatched_selectors: all_unmatched_selectors,
+    })
+}
+
+/// Finds contracts which would be built for a `starknet-contract` compilation unit, as configured
+/// by its target properties, without compiling them.
+pub fn find_unit_contracts(
+    db: &RootDatabase,
+    ws: &Workspace<'_>,
+    unit: &CairoCompilationUnit,
+) -> Result<ProjectContracts> {
+    let mut props: Props = unit.main_component().targets.target_props()?;
+    if !unit.build_external_contracts {
+        props.build_external_contracts = None;
+    }
+    let main_crate_ids = if props.include_main_contracts {
+        collect_main_crate_ids(unit, db)
+    } else {
+        Vec::new()
+    };
+    // Unmatched selectors are only reported here, so that they can be listed by the caller.
+    find_project_contracts(
+        db,
+        ws,
+        unit,
+        main_crate_ids,
+        props.build_external_contracts,
+        false,
+    )
+}
+
+/// Finds the component of the package `name` which external contract `selectors` refer to.
+///
+/// Returns `None` if no component matches the requested package version.
+pub(crate) fn select_component<'a>(
+    unit: &'a CairoCompilationUnit,
+    name: &PackageName,
+    version: Option<&str>,
+    selectors: &[ContractSelector],
+) -> Result<Option<&'a CompilationUnitComponent>> {
+    let candidates = unit
+        .components()
+        .iter()
+        .filter(|component| component.package.id.name == *name)
+        .collect_vec();
+
+    if let Some(version) = version {
+        return Ok(candidates
+            .into_iter()
+            .find(|component| component.package.id.version.to_string() == version));
+    }
+
+    let versions = candidates
+        .iter()
+        .map(|component| component.package.id.version.clone())
+        .unique()
+        .sorted()
+        .collect_vec();
+    if versions.len() > 1 {
+        let selector = &selectors[0];
+        bail!(
+            "external contract selector `{selector}` is ambiguous, package `{name}` is present \
+            in multiple versions: `{versions}`\n\
+            help: qualify the package with a version, e.g. `{name}@{latest}{CAIRO_PATH_SEPARATOR}{rest}`",
+            selector = selector.full_path(),
+            versions = versions.iter().join("`, `"),
+            latest = versions.last().unwrap(),
+            rest = selector
+                .path()
+                .split_once(CAIRO_PATH_SEPARATOR)
+                .map_or("", |(_, rest)| rest),
+        );
+    }
+    Ok(candidates.into_iter().next())
+}
+
+/// Result of matching [`ContractSelector`]s against contracts defined in a set of crates.
+pub struct ContractSelectorsResolution {
+    /// Contracts matched by at least one selector, deduplicated and sorted by their path.
+    pub contracts: Vec<ContractDeclaration>,
+    /// Selectors which did not match any contract, in the order they were provided.
+    pub unmatched_selectors: Vec<ContractSelector>,
+    /// The first selector matching each contract, keyed by the contract definition path.
+    pub selectors: HashMap<String, ContractSelector>,
+}
+
+/// Finds contracts defined in (or publicly re-exported from) `crate_ids` matching `selectors`.
+///
+/// This performs the same selection as the `build-external-contracts` target property, without
+/// compiling anything.
+/// Selectors with the `crate` prefix must be resolved with [`ContractSelector::resolve_crate`]
+/// beforehand, otherwise they match nothing.
+pub fn resolve_contract_selectors(
+    db: &dyn SemanticGroup,
+    crate_ids: &[CrateId],
+    selectors: &[ContractSelector],
+) -> ContractSelectorsResolution {
+    let contracts = find_contracts(db, crate_ids);
+    let mut contract_selectors: HashMap<String, ContractSelector> = HashMap::new();
+    let mut filtered_contracts: Vec<ContractDeclaration> = contracts
+        .into_iter()
+        .filter(|decl| {
+            let contract_path = decl.module_id().full_path(db.upcast());
+            let Some(selector) = selectors
+                .iter()
+                .find(|selector| selector.matches(contract_path.as_str()))
+            else {
+                return false;
+            };
+            contract_selectors.insert(contract_path, selector.clone());
+            true
+        })
+        .collect();
+
+    let mut matched_selectors: HashSet<ContractSelector> = selectors
+        .iter()
+        .filter(|selector| {
+            filtered_contracts.iter().any(|decl| {
+                let contract_path = decl.module_id().full_path(db.upcast());
+                selector.matches(contract_path.as_str())
+            })
+        })
+        .cloned()
+        .collect();
+
+    // Find selected reexports.
+    for crate_id in crate_ids {
+        let modules = db.crate_modules(*crate_id);
+        for module_id in modules.iter() {
+            let Ok(module_uses) = db.module_uses(*module_id) else {
+                continue;
+            };
+            let module_with_reexport = module_id.full_path(db.upcast());
+            let matched_contracts = module_uses
+                .iter()
+                .filter_map(|(use_id, use_path)| {
+                    let use_alias = match use_path.alias_clause(db.upcast()) {
+                        OptionAliasClause::Empty(_) => None,
+                        OptionAliasClause::AliasClause(alias_clause) => Some(
+                            alias_clause
+                                .alias(db.upcast())
+                                .as_syntax_node()
+                                .get_text_without_trivia(db.upcast()),
+                        ),
+                    };
+                    let visibility = db
+                        .module_item_info_by_name(*module_id, use_id.name(db.upcast()))
+                        .ok()??
+                        .visibility;
+                    if visibility == Visibility::Public {
+                        Some((db.use_resolved_item(*use_id).ok()?, use_alias))
+                    } else {
+                        None
+                    }
+                })
+                .filter_map(|(use_item, use_alias)| match use_item {
+                    Module(module_id) => {
+                        module_id.name(db.upcast());
+                        Some((module_id, use_alias))
+                    }
+                    _ => None,
+                })
+                .flat_map(|(module_id, use_alias)| {
+                    let exported_module_path = module_id.full_path(db.upcast());
+                    let exported_module_name =
+                        use_alias.unwrap_or_else(|| module_id.name(db.upcast()).to_string());
+                    let mut submodules = Vec::new();
+                    collect_modules_under(db.upcast(), &mut submodules, module_id);
+                    submodules
+                        .iter()
+                        .filter_map(|module_id| {
+                            let contract = module_contract(db, *module_id)?;
+                            let contract_path = contract.module_id().full_path(db.upcast());
+                            let exported_contract_path =
+                                contract_path.replace(&exported_module_path, "");
+                            let exported_contract_path = format!(
+                                "{}::{exported_module_name}{exported_contract_path}",
+                                &module_with_reexport
+                            );
+                            let selectors_used = selectors
+                                .iter()
+                                .filter(|selector| {
+                                    selector.matches(exported_contract_path.as_str())
+                                })
+                                .map(|c| (*c).clone())
+                                .collect_vec();
+                            let any_matched = !selectors_used.is_empty();
+                            if let Some(selector) = selectors_used.first() {
+                                contract_selectors
+                                    .entry(contract_path)
+                                    .or_insert_with(|| selector.clone());
+                            }
+                            matched_selectors.extend(selectors_used);
+                            any_matched.then_some(contract)
+                        })
+                        .collect_vec()
+                })
+                .collect_vec();
+            filtered_contracts.extend(matched_contracts);
+        }
+    }
+
+    let unmatched_selectors = selectors
+        .iter()
+        .filter(|selector| !matched_selectors.contains(*selector))
+        .cloned()
+        .collect_vec();
+
+    ContractSelectorsResolution {
+        contracts: dedup_contracts(db, filtered_contracts),
+        unmatched_selectors,
+        selectors: contract_selectors,
+    }
+}
+
+/// Deduplicates found contracts by contract path.
+///
+/// Contracts are identified by the path of their definition, not the path of the selector or
+/// re-export they were reached through, so the same contract is always built exactly once.
+fn dedup_contracts(
+    db: &dyn SemanticGroup,
+    contracts: impl IntoIterator<Item = ContractDeclaration>,
+) -> Vec<ContractDeclaration> {
+    let mut contracts_found = contracts
         .into_iter()
-        .chain(external_contracts)
         .map(|decl| (decl.module_id().full_path(db.upcast()), decl))
         .sorted_by_key(|(path, _)| path.clone())
         .collect_vec();
     contracts_found.dedup_by_key(|(path, _)| path.clone());
 
-    Ok(contracts_found
+    contracts_found
         .into_iter()
         .map(|(_path, decl)| decl)
-        .collect())
+        .collect()
+}
+
+/// Expands `selectors` against contracts defined in (or re-exported from) `crate_id`.
+fn expand_contract_selectors(
+    db: &dyn SemanticGroup,
+    crate_id: CrateId,
+    cfg_set: &CfgSet,
+    selectors: &[ContractSelector],
+) -> ExpandedSelectors {
+    let span = trace_span!("expand_contract_selectors");
+    let _guard = span.enter();
+
+    let resolution = resolve_contract_selectors(db, &[crate_id], selectors);
+    let feature_gated_modules = if resolution.unmatched_selectors.is_empty() {
+        Vec::new()
+    } else {
+        find_feature_gated_modules(db, crate_id, cfg_set)
+    };
+    let contracts = resolution
+        .contracts
+        .iter()
+        .map(|decl| {
+            let module_id = decl.module_id();
+            let crate_long_id = db.lookup_intern_crate(module_id.owning_crate(db.upcast()));
+            let path = module_id.full_path(db.upcast());
+            let selector = resolution.selectors[&path].clone();
+            (crate_long_id, path, selector)
+        })
+        .collect();
+
+    let recursive_hints = find_recursive_hints(db, crate_id, selectors, &resolution);
+
+    ExpandedSelectors {
+        contracts,
+        unmatched_selectors: resolution.unmatched_selectors,
+        feature_gated_modules,
+        recursive_hints,
+    }
+}
+
+/// Finds `*` selectors which matched some contracts, but leave out contracts defined in nested
+/// submodules that a recursive `**` selector would match.
+///
+/// This is best-effort guidance only, re-exported contracts are not taken into account.
+fn find_recursive_hints(
+    db: &dyn SemanticGroup,
+    crate_id: CrateId,
+    selectors: &[ContractSelector],
+    resolution: &ContractSelectorsResolution,
+) -> Vec<(ContractSelector, usize)> {
+    let candidates = selectors
+        .iter()
+        .filter(|selector| !resolution.unmatched_selectors.contains(selector))
+        .filter_map(|selector| Some((selector, selector.to_recursive()?)))
+        .collect_vec();
+    if candidates.is_empty() {
+        return Vec::new();
+    }
+
+    let contract_paths = find_contracts(db, &[crate_id])
+        .iter()
+        .map(|decl| decl.module_id().full_path(db.upcast()))
+        .collect_vec();
+    candidates
+        .into_iter()
+        .filter_map(|(selector, recursive)| {
+            let count = contract_paths
+                .iter()
+                .filter(|path| recursive.matches(path) && !selector.matches(path))
+                .count();
+            (count > 0).then(|| (selector.clone(), count))
+        })
+        .collect()
+}
+
+/// Finds the contract defined at `path` in the crate identified by `crate_long_id`.
+fn find_contract_by_path(
+    db: &dyn SemanticGroup,
+    crate_long_id: CrateLongId,
+    path: &str,
+) -> Option<ContractDeclaration> {
+    let mut module_id = ModuleId::CrateRoot(db.intern_crate(crate_long_id));
+    for segment in path.split(CAIRO_PATH_SEPARATOR).skip(1) {
+        let submodule_id = db
+            .module_submodules_ids(module_id)
+            .ok()?
+            .iter()
+            .copied()
+            .find(|submodule_id| submodule_id.name(db.upcast()) == segment)?;
+        module_id = ModuleId::Submodule(submodule_id);
+    }
+    module_contract(db, module_id)
+}
+
+/// A module removed from compilation, because it is gated behind a disabled feature.
+#[derive(Clone, Debug)]
+pub(crate) struct FeatureGatedModule {
+    path: String,
+    features: Vec<String>,
+}
+
+impl FeatureGatedModule {
+    /// Whether contracts selected by `selector` would be defined in this module.
+    fn hides(&self, selector: &ContractSelector) -> bool {
+        selector.matches(&self.path)
+            || selector
+                .path()
+                .starts_with(&format!("{}{CAIRO_PATH_SEPARATOR}", self.path))
+    }
+}
+
+/// Finds modules of `crate_id` which are removed by `#[cfg(feature: ...)]` attributes, because
+/// the required features are not enabled in `cfg_set`.
+///
+/// Such modules are not visible in the semantic model, so this looks at the syntax of all
+/// modules which did survive cfg evaluation.
+fn find_feature_gated_modules(
+    db: &dyn SemanticGroup,
+    crate_id: CrateId,
+    cfg_set: &CfgSet,
+) -> Vec<FeatureGatedModule> {
+    let syntax_db: &dyn SyntaxGroup = db.upcast();
+    let mut gated = Vec::new();
+    for module_id in db.crate_modules(crate_id).iter() {
+        let inline_body = match module_id {
+            ModuleId::Submodule(submodule_id) => {
+                match submodule_id
+                    .stable_ptr(db.upcast())
+                    .lookup(syntax_db)
+                    .body(syntax_db)
+                {
+                    MaybeModuleBody::Some(body) => Some(body),
+                    MaybeModuleBody::None(_) => None,
+                }
+            }
+            _ => None,
+        };
+        let items = if let Some(body) = inline_body {
+            body.items(syntax_db).elements(syntax_db).collect_vec()
+        } else {
+            let Ok(file_id) = db.module_main_file(*module_id) else {
+                continue;
+            };
+            let Ok(syntax) = db.file_module_syntax(file_id) else {
+                continue;
+            };
+            syntax.items(syntax_db).elements(syntax_db).collect_vec()
+        };
+
+        let modules = items
+            .into_iter()
+            .filter_map(|item| match item {
+                ModuleItem::Module(item_module) => Some(item_module),
+                _ => None,
+            })
+            .collect_vec();
+        // Cfg predicates are evaluated by the compiler's own config plugin logic.
+        let enabled = ModuleItems(modules.clone())
+            .iter_items_in_cfg(syntax_db, cfg_set)
+            .map(|item_module| item_module.stable_ptr(syntax_db))
+            .collect::<HashSet<_>>();
+
+        let module_path = module_id.full_path(db.upcast());
+        for item_module in modules {
+            if enabled.contains(&item_module.stable_ptr(syntax_db)) {
+                continue;
+            }
+            let mut features = Vec::new();
+            for attr in item_module.query_attr(syntax_db, "cfg") {
+                if let ast::OptionArgListParenthesized::ArgListParenthesized(args) =
+                    attr.arguments(syntax_db)
+                {
+                    collect_required_features(
+                        syntax_db,
+                        args.arguments(syntax_db),
+                        false,
+                        &mut features,
+                    );
+                }
+            }
+            let features = features
+                .into_iter()
+                .filter(|feature| !cfg_set.contains(&Cfg::kv("feature", feature.as_str())))
+                .unique()
+                .collect_vec();
+            if !features.is_empty() {
+                gated.push(FeatureGatedModule {
+                    path: format!(
+                        "{module_path}{CAIRO_PATH_SEPARATOR}{}",
+                        item_module.name(syntax_db).text(syntax_db)
+                    ),
+                    features,
+                });
+            }
+        }
+    }
+    gated
+}
+
+/// Module items of a single module, to be filtered with [`HasItemsInCfgEx`].
+struct ModuleItems(Vec<ast::ItemModule>);
+
+impl BodyItems for ModuleItems {
+    type Item = ast::ItemModule;
+
+    fn items_vec(&self, _db: &dyn SyntaxGroup) -> Vec<ast::ItemModule> {
+        self.0.clone()
+    }
+}
+
+/// Collects names of features which `#[cfg(...)]` arguments require to be enabled, skipping
+/// features nested in `not(...)`, which are required to be disabled instead.
+///
+/// Both string (`feature: "x"`) and short string (`feature: 'x'`) values are recognised.
+fn collect_required_features(
+    db: &dyn SyntaxGroup,
+    args: ast::ArgList,
+    negated: bool,
+    features: &mut Vec<String>,
+) {
+    for arg in args.elements(db) {
+        match arg.arg_clause(db) {
+            ast::ArgClause::Named(named) if !negated && named.name(db).text(db) == "feature" => {
+                let feature = match named.value(db) {
+                    ast::Expr::String(value) => value.string_value(db),
+                    ast::Expr::ShortString(value) => value.string_value(db),
+                    _ => None,
+                };
+                features.extend(feature);
+            }
+            ast::ArgClause::Unnamed(unnamed) => {
+                if let ast::Expr::FunctionCall(call) = unnamed.value(db) {
+                    let is_not =
+                        call.path(db).as_syntax_node().get_text_without_trivia(db) == "not";
+                    collect_required_features(
+                        db,
+                        call.arguments(db).arguments(db),
+                        negated ^ is_not,
+                        features,
+                    );
+                }
+            }
+            _ => {}
+        }
+    }
 }
 
 fn collect_modules_under(db: &dyn DefsGroup, modules: &mut Vec<ModuleId>, module_id: ModuleId) {
@@ -357,10 +1323,167 @@ fn collect_modules_under(db: &dyn DefsGroup, modules: &mut Vec<ModuleId>, module
     }
 }
 
-fn contract_matches(selector: &ContractSelector, contract_path: &str) -> bool {
-    if selector.is_wildcard() {
-        contract_path.starts_with(&selector.partial_path())
-    } else {
-        contract_path == selector.full_path()
+#[cfg(test)]
+mod tests {
+    use anyhow::anyhow;
+    use assert_fs::TempDir;
+    use assert_fs::prelude::*;
+    use camino::Utf8Path;
+    use indoc::indoc;
+    use itertools::Itertools;
+    use test_case::test_case;
+
+    use scarb_test_support::contracts::{BALANCE_CONTRACT, HELLO_CONTRACT};
+    use scarb_test_support::project_builder::ProjectBuilder;
+
+    use super::{ContractSelector, in_input_order, resolve_contract_selectors};
+    use crate::compiler::db::build_scarb_root_database;
+    use crate::compiler::helpers::collect_main_crate_ids;
+    use crate::compiler::{CompilationUnit, CompilationUnitAttributes};
+    use crate::core::{Config, PackageName, TargetKind};
+    use crate::ops::{self, CompilationUnitsOpts, FeaturesOpts, FeaturesSelector};
+
+    /// Matches `selectors` against contracts of the `hello` package, returning paths of matched
+    /// contracts and unmatched selectors.
+    fn resolve(selectors: &[ContractSelector]) -> (Vec<String>, Vec<String>) {
+        let t = TempDir::new().unwrap();
+        ProjectBuilder::start()
+            .name("hello")
+            .manifest_extra(indoc! {r#"
+                [[target.starknet-contract]]
+            "#})
+            .dep_starknet()
+            .lib_cairo("mod lorem;\nmod ipsum;")
+            .src("src/lorem.cairo", BALANCE_CONTRACT)
+            .src("src/ipsum.cairo", HELLO_CONTRACT)
+            .build(&t);
+        let cache_dir = TempDir::new().unwrap();
+        let config_dir = TempDir::new().unwrap();
+
+        let manifest_path = Utf8Path::from_path(t.child("Scarb.toml").path())
+            .unwrap()
+            .to_path_buf();
+        let config = Config::builder(manifest_path.clone())
+            .global_cache_dir_override(Some(Utf8Path::from_path(&cache_dir).unwrap().to_path_buf()))
+            .global_config_dir_override(Some(
+                Utf8Path::from_path(&config_dir).unwrap().to_path_buf(),
+            ))
+            .build()
+            .unwrap();
+        let ws = ops::read_workspace(&manifest_path, &config).unwrap();
+        let resolve = ops::resolve_workspace(&ws).unwrap();
+        let features = FeaturesOpts {
+            features: FeaturesSelector::Features(Vec::new()),
+            no_default_features: false,
+        };
+        let unit = ops::generate_compilation_units(
+            &resolve,
+            &features,
+            &ws,
+            CompilationUnitsOpts {
+                ignore_cairo_version: false,
+                load_prebuilt_macros: false,
+            },
+        )
+        .unwrap()
+        .into_iter()
+        .find_map(|unit| match unit {
+            CompilationUnit::Cairo(unit)
+                if unit.main_component().target_kind() == TargetKind::STARKNET_CONTRACT =>
+            {
+                Some(unit)
+            }
+            _ => None,
+        })
+        .unwrap();
+        let db = build_scarb_root_database(&unit, &ws, Default::default())
+            .unwrap()
+            .db;
+        let crate_ids = collect_main_crate_ids(&unit, &db);
+
+        let resolution = resolve_contract_selectors(&db, &crate_ids, selectors);
+        let contracts = resolution
+            .contracts
+            .iter()
+            .map(|decl| decl.module_id().full_path(&db))
+            .collect_vec();
+        let unmatched = resolution
+            .unmatched_selectors
+            .iter()
+            .map(ContractSelector::full_path)
+            .collect_vec();
+        (contracts, unmatched)
+    }
+
+    fn selectors(selectors: &[&str]) -> Vec<ContractSelector> {
+        selectors
+            .iter()
+            .map(|selector| ContractSelector(selector.to_string()))
+            .collect()
+    }
+
+    #[test_case(&["hello::lorem::Balance"] => vec!["hello::lorem::Balance"]; "exact")]
+    #[test_case(&["hello::lorem::*"] => vec!["hello::lorem::Balance"]; "glob")]
+    #[test_case(&["hello::**"] => vec!["hello::ipsum::HelloContract", "hello::lorem::Balance"]; "recursive glob")]
+    fn matched(paths: &[&str]) -> Vec<String> {
+        let (contracts, unmatched) = resolve(&selectors(paths));
+        assert!(unmatched.is_empty());
+        contracts
+    }
+
+    #[test]
+    fn unmatched() {
+        let (contracts, unmatched) = resolve(&selectors(&[
+            "hello::lorem::Missing",
+            "hello::ipsum::HelloContract",
+        ]));
+        assert_eq!(contracts, vec!["hello::ipsum::HelloContract"]);
+        assert_eq!(unmatched, vec!["hello::lorem::Missing"]);
+    }
+
+    #[test]
+    fn crate_prefixed() {
+        let (contracts, unmatched) = resolve(&selectors(&["crate::lorem::Balance"]));
+        assert!(contracts.is_empty());
+        assert_eq!(unmatched, vec!["crate::lorem::Balance"]);
+
+        let resolved = selectors(&["crate::lorem::Balance"])
+            .iter()
+            .map(|selector| selector.resolve_crate(&PackageName::new("hello")))
+            .collect_vec();
+        let (contracts, unmatched) = resolve(&resolved);
+        assert_eq!(contracts, vec!["hello::lorem::Balance"]);
+        assert!(unmatched.is_empty());
+    }
+
+    #[test_case(1)]
+    #[test_case(2)]
+    #[test_case(3)]
+    #[test_case(8)]
+    fn results_are_in_input_order(workers: usize) {
+        let contracts = (0..7).collect_vec();
+        // Collect results worker by worker, the same way contracts are split between threads.
+        let results = (0..workers)
+            .flat_map(|worker| {
+                contracts
+                    .iter()
+                    .enumerate()
+                    .skip(worker)
+                    .step_by(workers)
+                    .map(|(index, contract)| (index, Ok(*contract)))
+                    .collect_vec()
+            })
+            .collect_vec();
+        assert_eq!(in_input_order(results).unwrap(), contracts);
+    }
+
+    #[test]
+    fn results_propagate_first_error() {
+        let results = vec![
+            (2, Err(anyhow!("third"))),
+            (0, Ok(())),
+            (1, Err(anyhow!("second"))),
+        ];
+        assert_eq!(in_input_order(results).unwrap_err().to_string(), "second");
     }
 }
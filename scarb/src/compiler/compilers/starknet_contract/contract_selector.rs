@@ -1,20 +1,87 @@
 use crate::core::PackageName;
+use anyhow::{Error, Result, ensure};
 use serde::{Deserialize, Serialize};
 use std::collections::HashSet;
 
 pub const CAIRO_PATH_SEPARATOR: &str = "::";
 pub const GLOB_PATH_SELECTOR: &str = "*";
+pub const RECURSIVE_GLOB_PATH_SELECTOR: &str = "**";
+pub const VERSION_SELECTOR: &str = "@";
+pub const CRATE_SELECTOR: &str = "crate";
 
+/// A path to a contract module, as used in the `build-external-contracts` target property.
+///
+/// The path may end with a `*` wildcard, matching contracts defined directly in the given module,
+/// or with a `**` wildcard, matching contracts defined in the module and all of its submodules.
+/// The package name may be qualified with a version, like `hello@0.2.0::Balance`, to pick
+/// a specific package when multiple versions of it are present in the compilation unit.
+/// Contracts of the main package can be selected with the `crate` prefix, like `crate::Balance`.
+///
+/// Selectors are validated when deserialized from target properties.
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String")]
 pub struct ContractSelector(pub String);
 
+impl TryFrom<String> for ContractSelector {
+    type Error = Error;
+
+    fn try_from(selector: String) -> Result<Self> {
+        let selector = Self(selector);
+        ensure!(
+            selector.has_package_and_path(),
+            "external contract path `{}` must consist of a package name and a path \
+            within the package, like `package_name::ContractName` or `package_name::*`",
+            selector.0
+        );
+        ensure!(
+            selector.is_valid(),
+            "external contract path `{}` has invalid global path selectors, only a single trailing '*' or '**' selector is allowed",
+            selector.0
+        );
+        Ok(selector)
+    }
+}
+
 impl ContractSelector {
     pub fn package(&self) -> PackageName {
-        let parts = self
-            .0
+        let (package, _) = self.split_package();
+        let name = package
+            .split_once(VERSION_SELECTOR)
+            .map_or(package, |(name, _)| name);
+        PackageName::new(name)
+    }
+
+    /// The package version this selector is qualified with, if any.
+    pub fn version(&self) -> Option<&str> {
+        let (package, _) = self.split_package();
+        package
+            .split_once(VERSION_SELECTOR)
+            .map(|(_, version)| version)
+    }
+
+    /// The contract path selected, without the package version qualifier.
+    pub fn path(&self) -> String {
+        match self.split_package() {
+            (_, "") => self.package().to_string(),
+            (_, rest) => format!("{}{CAIRO_PATH_SEPARATOR}{rest}", self.package()),
+        }
+    }
+
+    /// Replaces the `crate` prefix of a selector with the name of the `main_package`.
+    ///
+    /// Selectors referring to other packages are returned unchanged.
+    pub fn resolve_crate(&self, main_package: &PackageName) -> Self {
+        match self.split_package() {
+            (CRATE_SELECTOR, "") => Self(main_package.to_string()),
+            (CRATE_SELECTOR, rest) => Self(format!("{main_package}{CAIRO_PATH_SEPARATOR}{rest}")),
+            _ => self.clone(),
+        }
+    }
+
+    fn split_package(&self) -> (&str, &str) {
+        self.0
             .split_once(CAIRO_PATH_SEPARATOR)
-            .unwrap_or((self.0.as_str(), ""));
-        PackageName::new(parts.0)
+            .unwrap_or((self.0.as_str(), ""))
     }
 
     pub fn contract(&self) -> String {
@@ -29,17 +96,64 @@ impl ContractSelector {
         self.0.ends_with(GLOB_PATH_SELECTOR)
     }
 
+    pub fn is_recursive_wildcard(&self) -> bool {
+        self.0.ends_with(RECURSIVE_GLOB_PATH_SELECTOR)
+    }
+
+    /// Whether this selector names both a package and a path within it, like `hello::Balance`.
+    pub fn has_package_and_path(&self) -> bool {
+        let (package, rest) = self.split_package();
+        !package.is_empty() && !rest.is_empty()
+    }
+
+    /// Whether this selector contains at most a single wildcard, `*` or `**`, at its end.
+    pub fn is_valid(&self) -> bool {
+        let globs = self.0.matches(GLOB_PATH_SELECTOR).count();
+        match globs {
+            0 => true,
+            1 => self.is_wildcard(),
+            2 => self.is_recursive_wildcard(),
+            _ => false,
+        }
+    }
+
     pub fn partial_path(&self) -> String {
-        let parts = self
-            .0
+        let path = self.path();
+        let parts = path
             .split_once(GLOB_PATH_SELECTOR)
-            .unwrap_or((self.0.as_str(), ""));
+            .unwrap_or((path.as_str(), ""));
         parts.0.to_string()
     }
 
     pub fn full_path(&self) -> String {
         self.0.clone()
     }
+
+    /// The `**` counterpart of a non-recursive `*` selector, like `hello::**` for `hello::*`.
+    pub fn to_recursive(&self) -> Option<Self> {
+        (self.is_wildcard() && !self.is_recursive_wildcard())
+            .then(|| Self(format!("{}{GLOB_PATH_SELECTOR}", self.0)))
+    }
+
+    /// Check whether a fully qualified contract path is matched by this selector.
+    ///
+    /// A `*` wildcard is not recursive, it only matches contracts defined directly in the module,
+    /// while a `**` wildcard matches contracts defined in any of its submodules as well.
+    /// Invalid selectors (see [`ContractSelector::is_valid`]) never match anything.
+    /// The package version qualifier is not a part of the contract path, and thus it is ignored.
+    pub fn matches(&self, full_path: &str) -> bool {
+        if !self.is_valid() {
+            return false;
+        }
+        if self.is_wildcard() {
+            let Some(rest) = full_path.strip_prefix(&self.partial_path()) else {
+                return false;
+            };
+            self.is_recursive_wildcard() || !rest.contains(CAIRO_PATH_SEPARATOR)
+        } else {
+            full_path == self.path()
+        }
+    }
 }
 
 pub struct ContractFileStemCalculator(HashSet<String>);
@@ -71,3 +185,76 @@ impl ContractFileStemCalculator {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::ContractSelector;
+    use crate::core::PackageName;
+
+    #[test_case("hello::Balance" => true; "contract")]
+    #[test_case("hello::*" => true; "glob")]
+    #[test_case("hello@0.1.0::lorem::Balance" => true; "versioned")]
+    #[test_case("Balance" => false; "bare name")]
+    #[test_case("::Balance" => false; "empty package")]
+    #[test_case("hello::" => false; "empty path")]
+    fn has_package_and_path(selector: &str) -> bool {
+        ContractSelector(selector.to_string()).has_package_and_path()
+    }
+
+    #[test_case("hello::*" => Some("hello::**".to_string()); "glob")]
+    #[test_case("hello::lorem::*" => Some("hello::lorem::**".to_string()); "glob module")]
+    #[test_case("hello::**" => None; "recursive glob")]
+    #[test_case("hello::Balance" => None; "path")]
+    fn to_recursive(selector: &str) -> Option<String> {
+        ContractSelector(selector.to_string())
+            .to_recursive()
+            .map(|selector| selector.full_path())
+    }
+
+    #[test_case("hello::Balance", "hello::Balance" => true; "exact")]
+    #[test_case("hello::Balance", "hello::BalanceV2" => false; "exact prefix")]
+    #[test_case("hello::Balance", "hello::lorem::Balance" => false; "exact other module")]
+    #[test_case("hello::*", "hello::Balance" => true; "glob")]
+    #[test_case("hello::*", "hello::lorem::ipsum::Balance" => false; "glob subpath")]
+    #[test_case("hello::lorem::*", "hello::lorem::Balance" => true; "glob module")]
+    #[test_case("hello::lorem::*", "hello::Balance" => false; "glob other module")]
+    #[test_case("hello::*", "world::Balance" => false; "glob other package")]
+    #[test_case("hello::**", "hello::Balance" => true; "recursive glob")]
+    #[test_case("hello::**", "hello::lorem::ipsum::Balance" => true; "recursive glob subpath")]
+    #[test_case("hello::lorem::**", "hello::Balance" => false; "recursive glob other module")]
+    #[test_case("hello::***", "hello::Balance" => false; "triple glob")]
+    #[test_case("hello::*::*", "hello::lorem::Balance" => false; "double glob subpath")]
+    #[test_case("hello::*::**", "hello::lorem::Balance" => false; "glob and recursive glob")]
+    #[test_case("hello@0.2.0::Balance", "hello::Balance" => true; "versioned exact")]
+    #[test_case("hello@0.2.0::*", "hello::Balance" => true; "versioned glob")]
+    #[test_case("hello@0.2.0::**", "hello::lorem::Balance" => true; "versioned recursive glob")]
+    fn matches(selector: &str, full_path: &str) -> bool {
+        ContractSelector(selector.to_string()).matches(full_path)
+    }
+
+    #[test_case("hello::Balance" => ("hello".to_string(), None, "hello::Balance".to_string()); "unversioned")]
+    #[test_case("hello@0.2.0::Balance" => ("hello".to_string(), Some("0.2.0".to_string()), "hello::Balance".to_string()); "versioned")]
+    #[test_case("hello@0.2.0::lorem::*" => ("hello".to_string(), Some("0.2.0".to_string()), "hello::lorem::*".to_string()); "versioned glob")]
+    #[test_case("hello@0.2.0" => ("hello".to_string(), Some("0.2.0".to_string()), "hello".to_string()); "package only")]
+    fn parts(selector: &str) -> (String, Option<String>, String) {
+        let selector = ContractSelector(selector.to_string());
+        (
+            selector.package().to_string(),
+            selector.version().map(ToString::to_string),
+            selector.path(),
+        )
+    }
+
+    #[test_case("crate::Balance" => "hello::Balance"; "crate exact")]
+    #[test_case("crate::lorem::*" => "hello::lorem::*"; "crate glob")]
+    #[test_case("crate" => "hello"; "crate only")]
+    #[test_case("world::Balance" => "world::Balance"; "other package")]
+    #[test_case("crates::Balance" => "crates::Balance"; "crate prefix")]
+    fn resolve_crate(selector: &str) -> String {
+        ContractSelector(selector.to_string())
+            .resolve_crate(&PackageName::new("hello"))
+            .full_path()
+    }
+}
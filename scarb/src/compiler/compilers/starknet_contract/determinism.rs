@@ -0,0 +1,98 @@
+use std::iter::zip;
+
+use anyhow::{Result, bail};
+use cairo_lang_starknet_classes::casm_contract_class::CasmContractClass;
+use cairo_lang_starknet_classes::contract_class::ContractClass;
+use itertools::{EitherOrBoth, Itertools};
+use serde::Serialize;
+
+use super::artifacts_writer::ArtifactsWriter;
+
+/// Ensures that two compilations of the same contracts produced byte-identical classes.
+///
+/// Contract classes are compared in their serialized form, as produced by the artifacts `writer`
+/// (compression, if enabled, is deterministic for identical input and is skipped here).
+pub fn ensure_deterministic(
+    writer: &ArtifactsWriter,
+    contract_paths: &[String],
+    (classes, casm_classes): (&[ContractClass], &[Option<CasmContractClass>]),
+    (recompiled_classes, recompiled_casm_classes): (&[ContractClass], &[Option<CasmContractClass>]),
+) -> Result<()> {
+    for (path, (class, recompiled)) in zip(contract_paths, zip(classes, recompiled_classes)) {
+        ensure_identical(writer, path, "Sierra contract class", class, recompiled)?;
+    }
+    for (path, (class, recompiled)) in
+        zip(contract_paths, zip(casm_classes, recompiled_casm_classes))
+    {
+        ensure_identical(writer, path, "compiled contract class", class, recompiled)?;
+    }
+    Ok(())
+}
+
+fn ensure_identical(
+    writer: &ArtifactsWriter,
+    contract_path: &str,
+    kind: &str,
+    first: &impl Serialize,
+    second: &impl Serialize,
+) -> Result<()> {
+    if writer.serialize_class(contract_path, first)?
+        != writer.serialize_class(contract_path, second)?
+    {
+        // Written bytes may not be textual, so the difference is described on pretty JSON.
+        let first = serde_json::to_string_pretty(first)?;
+        let second = serde_json::to_string_pretty(second)?;
+        bail!(
+            "{kind} of contract `{contract_path}` differs between two compilations\n{}",
+            first_difference(&first, &second)
+        );
+    }
+    Ok(())
+}
+
+/// Describes the first differing line of two texts in the unified diff format.
+fn first_difference(first: &str, second: &str) -> String {
+    let Some((index, lines)) = first
+        .lines()
+        .zip_longest(second.lines())
+        .enumerate()
+        .find(|(_, lines)| !matches!(lines, EitherOrBoth::Both(a, b) if a == b))
+    else {
+        return String::new();
+    };
+    let mut diff = format!(
+        "--- first compilation\n+++ second compilation\n@@ line {} @@",
+        index + 1
+    );
+    if let Some(line) = lines.clone().left() {
+        diff.push_str(&format!("\n-{line}"));
+    }
+    if let Some(line) = lines.right() {
+        diff.push_str(&format!("\n+{line}"));
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+    use test_case::test_case;
+
+    use super::first_difference;
+
+    #[test_case("a\nb\nc", "a\nx\nc" => indoc! {"
+        --- first compilation
+        +++ second compilation
+        @@ line 2 @@
+        -b
+        +x"}; "changed line")]
+    #[test_case("a\nb", "a" => indoc! {"
+        --- first compilation
+        +++ second compilation
+        @@ line 2 @@
+        -b"}; "removed line")]
+    #[test_case("a", "a" => ""; "identical")]
+    fn difference(first: &str, second: &str) -> String {
+        first_difference(first, second)
+    }
+}
@@ -1,9 +1,13 @@
 pub use artifacts_writer::ArtifactsWriter;
 pub use compiler::*;
 pub use contract_selector::{ContractFileStemCalculator, ContractSelector};
-pub use validations::ensure_gas_enabled;
+pub use selectors_cache::ContractSelectorsCache;
+pub use validations::{ensure_contracts_selected, ensure_gas_enabled};
 
 mod artifacts_writer;
 mod compiler;
 mod contract_selector;
+mod determinism;
+mod native;
+mod selectors_cache;
 mod validations;
@@ -0,0 +1,46 @@
+use anyhow::Result;
+use cairo_lang_starknet_classes::contract_class::ContractClass;
+use camino::Utf8Path;
+
+pub const NATIVE_NOT_BUILT_ERROR: &str = "native support not built, \
+    rebuild Scarb with the `cairo-native` feature enabled to compile contracts to native libraries";
+
+/// Extension of shared libraries produced by native contract compilation on the current platform.
+pub const NATIVE_LIBRARY_EXTENSION: &str = std::env::consts::DLL_EXTENSION;
+
+/// Whether this Scarb build is able to compile contracts to native shared libraries.
+pub fn is_native_supported() -> bool {
+    cfg!(feature = "cairo-native")
+}
+
+/// Compiles a Sierra contract class into a shared library loadable by `cairo-native` executors.
+#[cfg(feature = "cairo-native")]
+pub fn compile_native(class: &ContractClass, path: &Utf8Path) -> Result<()> {
+    use anyhow::Context;
+    use cairo_lang_starknet_classes::contract_class::version_id_from_serialized_sierra_program;
+    use cairo_native::OptLevel;
+    use cairo_native::executor::AotContractExecutor;
+
+    let program = class
+        .extract_sierra_program()
+        .context("failed to extract Sierra program from contract class")?;
+    let (sierra_version, _) = version_id_from_serialized_sierra_program(&class.sierra_program)
+        .context("failed to read Sierra version from contract class")?;
+    let executor = AotContractExecutor::new(
+        &program,
+        &class.entry_points_by_type,
+        sierra_version,
+        OptLevel::Default,
+        None,
+    )
+    .context("failed to compile Sierra contract to native library")?;
+    executor
+        .save(path)
+        .with_context(|| format!("failed to write native library: {path}"))?;
+    Ok(())
+}
+
+#[cfg(not(feature = "cairo-native"))]
+pub fn compile_native(_class: &ContractClass, _path: &Utf8Path) -> Result<()> {
+    anyhow::bail!(NATIVE_NOT_BUILT_ERROR)
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use cairo_lang_filesystem::cfg::CfgSet;
+use cairo_lang_filesystem::ids::CrateLongId;
+use tracing::debug;
+
+use super::compiler::FeatureGatedModule;
+use super::contract_selector::ContractSelector;
+use crate::compiler::CompilationUnitComponentId;
+
+/// External contract selectors of a single package, expanded against its crate.
+#[derive(Debug)]
+pub(crate) struct ExpandedSelectors {
+    /// Crates and definition paths of matched contracts, along with the selector matching them.
+    ///
+    /// Compilation units do not share databases, so contracts are stored by path and resolved
+    /// again against the database of each compilation unit.
+    pub contracts: Vec<(CrateLongId, String, ContractSelector)>,
+    pub unmatched_selectors: Vec<ContractSelector>,
+    pub feature_gated_modules: Vec<FeatureGatedModule>,
+    /// Non-recursive `*` selectors, along with the number of contracts defined in submodules
+    /// which would additionally be matched by their recursive `**` counterparts.
+    pub recursive_hints: Vec<(ContractSelector, usize)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub(crate) struct ExpandedSelectorsKey {
+    pub selectors: Vec<ContractSelector>,
+    pub component_id: CompilationUnitComponentId,
+    /// The cfg set the component is compiled with, resolved against the compilation unit.
+    pub cfg_set: CfgSet,
+}
+
+/// Caches expansion of `build-external-contracts` selectors within a single Scarb invocation,
+/// so that targets selecting the same contracts (like `starknet-contract` and `test` targets of
+/// one package) do not search the crate graph repeatedly.
+#[derive(Debug, Default)]
+pub struct ContractSelectorsCache(Mutex<HashMap<ExpandedSelectorsKey, Arc<ExpandedSelectors>>>);
+
+impl ContractSelectorsCache {
+    pub(crate) fn get_or_expand(
+        &self,
+        key: ExpandedSelectorsKey,
+        expand: impl FnOnce() -> ExpandedSelectors,
+    ) -> Arc<ExpandedSelectors> {
+        if let Some(expanded) = self.0.lock().unwrap().get(&key) {
+            debug!(
+                "reusing expanded external contract selectors: {:?}",
+                key.selectors
+            );
+            return expanded.clone();
+        }
+
+        debug!("expanding external contract selectors: {:?}", key.selectors);
+        let expanded = Arc::new(expand());
+        self.0.lock().unwrap().insert(key, expanded.clone());
+        expanded
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Arc;
+
+    use cairo_lang_filesystem::cfg::{Cfg, CfgSet};
+    use semver::Version;
+
+    use super::{ContractSelectorsCache, ExpandedSelectors, ExpandedSelectorsKey};
+    use crate::compiler::CompilationUnitComponentId;
+    use crate::compiler::compilers::starknet_contract::contract_selector::ContractSelector;
+    use crate::core::{PackageId, PackageName, SourceId};
+
+    fn key(cfg_set: CfgSet) -> ExpandedSelectorsKey {
+        ExpandedSelectorsKey {
+            selectors: vec![ContractSelector("first::*".to_string())],
+            component_id: CompilationUnitComponentId {
+                package_id: PackageId::new(
+                    PackageName::new("first"),
+                    Version::new(0, 1, 0),
+                    SourceId::mock_path(),
+                ),
+            },
+            cfg_set,
+        }
+    }
+
+    fn expanded(unmatched: &str) -> ExpandedSelectors {
+        ExpandedSelectors {
+            contracts: Vec::new(),
+            unmatched_selectors: vec![ContractSelector(unmatched.to_string())],
+            feature_gated_modules: Vec::new(),
+            recursive_hints: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn reuses_expansion_for_same_key() {
+        let cache = ContractSelectorsCache::default();
+        let cfg_set = CfgSet::from_iter([Cfg::kv("target", "test")]);
+        let first = cache.get_or_expand(key(cfg_set.clone()), || expanded("first::A"));
+        let second = cache.get_or_expand(key(cfg_set), || panic!("must not expand again"));
+        assert!(Arc::ptr_eq(&first, &second));
+        assert_eq!(
+            second.unmatched_selectors,
+            vec![ContractSelector("first::A".to_string())]
+        );
+    }
+
+    #[test]
+    fn expands_separately_for_different_cfg_sets() {
+        let cache = ContractSelectorsCache::default();
+        let contract = CfgSet::from_iter([Cfg::kv("target", "starknet-contract")]);
+        let test = CfgSet::from_iter([Cfg::kv("target", "test")]);
+        let first = cache.get_or_expand(key(contract), || expanded("first::A"));
+        let second = cache.get_or_expand(key(test), || expanded("first::B"));
+        assert!(!Arc::ptr_eq(&first, &second));
+        assert_eq!(
+            second.unmatched_selectors,
+            vec![ContractSelector("first::B".to_string())]
+        );
+    }
+}
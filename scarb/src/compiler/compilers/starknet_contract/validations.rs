@@ -1,18 +1,26 @@
-use crate::compiler::compilers::{Props, SerdeListSelector};
+use crate::compiler::compilers::starknet_contract::contract_selector::CAIRO_PATH_SEPARATOR;
+use crate::compiler::compilers::{ContractSelector, Props, SerdeListSelector};
 use crate::compiler::{CairoCompilationUnit, CompilationUnitAttributes};
+use crate::core::errors::GasDisabledError;
 use crate::core::{Utf8PathWorkspaceExt, Workspace};
+use crate::internal::edit_distance::closest_match;
 use anyhow::{Context, bail, ensure};
 use cairo_lang_compiler::db::RootDatabase;
-use cairo_lang_defs::ids::NamedLanguageElementId;
+use cairo_lang_defs::db::DefsGroup;
+use cairo_lang_defs::ids::{NamedLanguageElementId, TopLevelLanguageElementId, TraitId};
 use cairo_lang_filesystem::db::FilesGroup;
 use cairo_lang_filesystem::flag::Flag;
 use cairo_lang_filesystem::ids::FlagId;
+use cairo_lang_semantic::db::SemanticGroup;
 use cairo_lang_starknet::contract::ContractDeclaration;
+use cairo_lang_starknet_classes::abi::Item;
 use cairo_lang_starknet_classes::allowed_libfuncs::{
     AllowedLibfuncsError, BUILTIN_EXPERIMENTAL_LIBFUNCS_LIST, ListSelector,
 };
 use cairo_lang_starknet_classes::contract_class::ContractClass;
 use indoc::{formatdoc, writedoc};
+use itertools::Itertools;
+use std::collections::HashSet;
 use std::fmt::Write;
 use std::iter::zip;
 use tracing::debug;
@@ -25,11 +33,57 @@ pub fn ensure_gas_enabled(db: &mut RootDatabase) -> anyhow::Result<()> {
     ensure!(
         flag.map(|f| matches!(*f, Flag::AddWithdrawGas(true)))
             .unwrap_or(false),
-        "the target starknet contract compilation requires gas to be enabled"
+        GasDisabledError
     );
     Ok(())
 }
 
+pub fn ensure_contracts_selected(
+    include_main_contracts: bool,
+    build_external_contracts: &Option<Vec<ContractSelector>>,
+) -> anyhow::Result<()> {
+    ensure!(
+        include_main_contracts
+            || build_external_contracts
+                .as_ref()
+                .is_some_and(|contracts| !contracts.is_empty()),
+        "`include-main-contracts` is disabled and no `build-external-contracts` are specified, \
+        Scarb would not compile any contracts"
+    );
+    Ok(())
+}
+
+pub fn ensure_external_contracts_dependencies(
+    unit: &CairoCompilationUnit,
+    build_external_contracts: &[ContractSelector],
+) -> anyhow::Result<()> {
+    let package_names = unit
+        .components()
+        .iter()
+        .map(|component| component.package.id.name.as_str())
+        .collect::<Vec<_>>();
+    for selector in build_external_contracts {
+        let package_name = selector.package();
+        if package_names.contains(&package_name.as_str()) {
+            continue;
+        }
+        let mut message = format!(
+            "external contract selector `{}` refers to package `{package_name}`, \
+            which is not a dependency of `{}`",
+            selector.full_path(),
+            unit.main_component().package.id.name,
+        );
+        if let Some(suggestion) = closest_match(package_name.as_str(), package_names.clone()) {
+            let _ = write!(
+                message,
+                "\nhelp: a dependency with a similar name exists: `{suggestion}`"
+            );
+        }
+        bail!(message);
+    }
+    Ok(())
+}
+
 pub fn check_allowed_libfuncs(
     props: &Props,
     contracts: &[ContractDeclaration],
@@ -116,3 +170,156 @@ pub fn check_allowed_libfuncs(
 
     Ok(())
 }
+
+pub fn check_required_interfaces(
+    props: &Props,
+    contracts: &[ContractDeclaration],
+    classes: &[ContractClass],
+    db: &RootDatabase,
+    unit: &CairoCompilationUnit,
+) -> anyhow::Result<()> {
+    if props.require_interfaces.is_empty() {
+        return Ok(());
+    }
+
+    let contract_paths = contracts
+        .iter()
+        .map(|decl| decl.module_id().full_path(db))
+        .collect_vec();
+
+    let mut violations = Vec::new();
+    for required in &props.require_interfaces {
+        let interface = find_interface(db, unit, required.interface())?;
+        let interface_path = interface.full_path(db);
+        let entrypoints = db
+            .trait_functions(interface)
+            .ok()
+            .with_context(|| format!("failed to find functions of interface `{interface_path}`"))?;
+
+        for selected in required.contracts().unwrap_or_default() {
+            ensure!(
+                contract_paths.contains(selected),
+                "contract `{selected}` required to implement interface `{interface_path}` \
+                is not built by this target"
+            );
+        }
+
+        for (contract_path, class) in zip(&contract_paths, classes) {
+            if required
+                .contracts()
+                .is_some_and(|selected| !selected.contains(contract_path))
+            {
+                continue;
+            }
+            let implemented = abi_function_names(class);
+            let missing = entrypoints
+                .keys()
+                .filter(|name| !implemented.contains(name.as_str()))
+                .map(|name| format!("`{name}`"))
+                .collect_vec();
+            if !missing.is_empty() {
+                violations.push(format!(
+                    "contract `{contract_path}` does not implement interface `{interface_path}`, \
+                    missing entrypoints: {}",
+                    missing.join(", ")
+                ));
+            }
+        }
+    }
+
+    ensure!(violations.is_empty(), "{}", violations.join("\n"));
+    Ok(())
+}
+
+/// Finds a trait by its full path, or by its name among traits defined in the main package.
+fn find_interface(
+    db: &RootDatabase,
+    unit: &CairoCompilationUnit,
+    path: &str,
+) -> anyhow::Result<TraitId> {
+    let main_component = unit.main_component();
+    let crate_name = match path.split_once(CAIRO_PATH_SEPARATOR) {
+        Some((crate_name, _)) => crate_name,
+        None => main_component.package.id.name.as_str(),
+    };
+    let component = unit
+        .components()
+        .iter()
+        .find(|component| component.cairo_package_name() == crate_name)
+        .with_context(|| {
+            format!(
+                "interface `{path}` refers to package `{crate_name}`, \
+                which is not a dependency of `{}`",
+                main_component.package.id.name
+            )
+        })?;
+
+    let traits = db
+        .crate_modules(component.crate_id(db))
+        .iter()
+        .filter_map(|module_id| DefsGroup::module_traits_ids(db, *module_id).ok())
+        .flat_map(|traits| traits.iter().copied().collect_vec())
+        .filter(|trait_id| {
+            if path.contains(CAIRO_PATH_SEPARATOR) {
+                trait_id.full_path(db) == path
+            } else {
+                trait_id.name(db) == path
+            }
+        })
+        .collect_vec();
+
+    match traits[..] {
+        [trait_id] => Ok(trait_id),
+        [] => bail!("interface `{path}` not found"),
+        _ => bail!(
+            "interface name `{path}` is ambiguous, use one of full paths: {}",
+            traits
+                .iter()
+                .map(|trait_id| format!("`{}`", trait_id.full_path(db)))
+                .join(", ")
+        ),
+    }
+}
+
+/// Names of all functions exposed in the ABI of a contract class, including interface functions.
+fn abi_function_names(class: &ContractClass) -> HashSet<&str> {
+    fn collect<'a>(items: &'a [Item], names: &mut HashSet<&'a str>) {
+        for item in items {
+            match item {
+                Item::Function(function) => {
+                    names.insert(function.name.as_str());
+                }
+                Item::Interface(interface) => collect(&interface.items, names),
+                _ => {}
+            }
+        }
+    }
+
+    let mut names = HashSet::new();
+    if let Some(abi) = &class.abi {
+        collect(&abi.items, &mut names);
+    }
+    names
+}
+
+#[cfg(test)]
+mod tests {
+    use cairo_lang_compiler::db::RootDatabase;
+
+    use super::ensure_gas_enabled;
+    use crate::core::errors::GasDisabledError;
+
+    #[test]
+    fn gas_disabled_error_is_typed() {
+        let mut db = RootDatabase::builder()
+            .skip_auto_withdraw_gas()
+            .build()
+            .unwrap();
+        let err = ensure_gas_enabled(&mut db).unwrap_err();
+        assert!(err.downcast_ref::<GasDisabledError>().is_some());
+        assert_eq!(
+            err.to_string(),
+            "the target starknet contract compilation requires gas to be enabled"
+        );
+    }
+}
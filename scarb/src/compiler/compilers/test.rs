@@ -1,4 +1,4 @@
-use anyhow::Result;
+use anyhow::{Result, ensure};
 use cairo_lang_compiler::db::RootDatabase;
 use cairo_lang_compiler::diagnostics::DiagnosticsReporter;
 use cairo_lang_filesystem::db::FilesGroup;
@@ -13,12 +13,15 @@ use tracing::trace_span;
 
 use crate::compiler::compilers::starknet_contract::Props as StarknetContractProps;
 use crate::compiler::compilers::{
-    ArtifactsWriter, CompiledContracts, ContractSelector, ensure_gas_enabled,
-    find_project_contracts, get_compiled_contracts,
+    ArtifactsWriter, CompiledContracts, ContractSelector, ensure_contracts_selected,
+    ensure_gas_enabled, find_project_contracts, get_compiled_contracts, select_component,
+};
+use crate::compiler::helpers::{
+    build_compiler_config, build_diagnostics_reporter, collect_main_crate_ids, write_json,
+    write_string,
 };
-use crate::compiler::helpers::{build_compiler_config, collect_main_crate_ids, write_json};
 use crate::compiler::{CairoCompilationUnit, CompilationUnitAttributes, Compiler};
-use crate::core::{PackageName, SourceId, TargetKind, TestTargetProps, Workspace};
+use crate::core::{PackageName, SourceId, TargetKind, TestEmit, TestTargetProps, Workspace};
 use crate::flock::Filesystem;
 
 pub struct TestCompiler;
@@ -36,12 +39,38 @@ impl Compiler for TestCompiler {
         ws: &Workspace<'_>,
     ) -> Result<()> {
         let target_dir = unit.target_dir(ws);
-        let build_external_contracts = external_contracts_selectors(unit)?;
+        let test_props: TestTargetProps = unit.main_component().targets.target_props()?;
+        ensure!(
+            test_props.emit.as_ref().is_none_or(|emit| !emit.is_empty()),
+            "`emit` property of test target `{}` must not be empty",
+            unit.main_component().target_name(),
+        );
+        let build_external_contracts = test_props
+            .build_external_contracts
+            .clone()
+            .filter(|_| unit.build_external_contracts);
 
         let test_crate_ids = collect_main_crate_ids(unit, db);
+        let mut contract_main_crate_ids = if test_props.include_main_contracts() {
+            test_crate_ids.clone()
+        } else {
+            Vec::new()
+        };
+        if test_props.build_external_contracts_transitive && unit.build_external_contracts {
+            ws.config().ui().warn(format!(
+                "searching for contracts in all dependencies of test target `{}`, \
+                this may significantly slow down compilation",
+                unit.main_component().target_name(),
+            ));
+            contract_main_crate_ids.extend(collect_dependency_crate_ids(unit, db));
+        }
         // Search for all contracts in deps specified with `build-external-contracts`.
-        let all_crate_ids =
-            get_contract_crate_ids(&build_external_contracts, test_crate_ids.clone(), unit, db);
+        let all_crate_ids = get_contract_crate_ids(
+            &build_external_contracts,
+            contract_main_crate_ids.clone(),
+            unit,
+            db,
+        )?;
 
         let starknet = unit.cairo_plugins.iter().any(|plugin| {
             plugin.package.id.name == PackageName::STARKNET
@@ -49,20 +78,41 @@ impl Compiler for TestCompiler {
         });
 
         let contracts = if starknet {
+            ensure_contracts_selected(
+                test_props.include_main_contracts(),
+                &build_external_contracts,
+            )?;
             find_project_contracts(
                 db,
-                ws.config().ui(),
+                ws,
                 unit,
-                test_crate_ids.clone(),
+                contract_main_crate_ids,
                 build_external_contracts.clone(),
+                false,
             )?
+            .contracts
         } else {
+            if build_external_contracts.is_some() {
+                ws.config().ui().warn(format!(
+                    "external contracts will not be built for test target `{}`, \
+                    because package `{}` does not depend on `starknet` package\n\
+                    help: add dependency on `starknet` to package manifest",
+                    unit.main_component().target_name(),
+                    unit.main_package_id().name,
+                ));
+            }
             Vec::new()
         };
 
         let diagnostics_reporter =
-            build_compiler_config(db, unit, &test_crate_ids, cached_crates, ws)
-                .diagnostics_reporter;
+            build_diagnostics_reporter(db, &test_crate_ids, cached_crates, ws);
+        // Test targets can deny warnings regardless of the `allow-warnings` compiler config.
+        let diagnostics_reporter =
+            if unit.compiler_config.allow_warnings && !test_props.deny_warnings {
+                diagnostics_reporter.allow_warnings()
+            } else {
+                diagnostics_reporter
+            };
 
         let span = trace_span!("compile_test");
         let test_compilation = {
@@ -86,22 +136,32 @@ impl Compiler for TestCompiler {
         {
             let _guard = span.enter();
             let sierra_program: VersionedProgram = test_compilation.sierra_program.clone().into();
-            let file_name = format!("{}.test.sierra.json", unit.main_component().target_name());
-            write_json(&file_name, "output file", &target_dir, ws, &sierra_program)?;
-
-            let file_name = format!("{}.test.json", unit.main_component().target_name());
-            write_json(
-                &file_name,
-                "output file",
-                &target_dir,
-                ws,
-                &test_compilation.metadata,
-            )?;
+            if test_props.emits(TestEmit::Sierra) {
+                let file_name = format!("{}.test.sierra.json", unit.main_component().target_name());
+                write_json(&file_name, "output file", &target_dir, ws, &sierra_program)?;
+            }
+
+            if test_props.emit_sierra_text {
+                let file_name = format!("{}.test.sierra", unit.main_component().target_name());
+                write_string(&file_name, "output file", &target_dir, ws, &sierra_program)?;
+            }
+
+            if test_props.emits(TestEmit::Metadata) {
+                let file_name = format!("{}.test.json", unit.main_component().target_name());
+                write_json(
+                    &file_name,
+                    "output file",
+                    &target_dir,
+                    ws,
+                    &test_compilation.metadata,
+                )?;
+            }
         }
 
         if starknet {
             // Note: this will only search for contracts in the main CU component and
-            // `build-external-contracts`. It will not collect contracts from all dependencies.
+            // `build-external-contracts`. It will not collect contracts from all dependencies,
+            // unless `build-external-contracts-transitive` is enabled.
             compile_contracts(
                 ContractsCompilationArgs {
                     main_crate_ids: test_crate_ids,
@@ -155,7 +215,7 @@ fn compile_contracts(
         contract_paths,
         contracts,
         classes,
-    } = get_compiled_contracts(contracts, compiler_config, db)?;
+    } = get_compiled_contracts(contracts, compiler_config, db, ws.config().ui())?;
     let writer = ArtifactsWriter::new(target_name.clone(), target_dir, props)
         .with_extension_prefix("test".to_string());
     let casm_classes: Vec<Option<CasmContractClass>> = classes.iter().map(|_| None).collect();
@@ -163,13 +223,18 @@ fn compile_contracts(
     Ok(())
 }
 
-fn external_contracts_selectors(
-    unit: &CairoCompilationUnit,
-) -> Result<Option<Vec<ContractSelector>>> {
-    let test_props: TestTargetProps = unit.main_component().targets.target_props()?;
-    Ok(test_props
-        .build_external_contracts
-        .map(|contracts| contracts.into_iter().map(ContractSelector).collect_vec()))
+/// Collects crates of all dependencies of the unit, including transitive ones,
+/// apart from the `core` crate.
+fn collect_dependency_crate_ids(unit: &CairoCompilationUnit, db: &RootDatabase) -> Vec<CrateId> {
+    let main_package_id = unit.main_package_id();
+    unit.components()
+        .iter()
+        .filter(|component| {
+            component.package.id != main_package_id && !component.package.id.is_core()
+        })
+        .map(|component| component.crate_id(db))
+        .unique()
+        .collect()
 }
 
 fn get_contract_crate_ids(
@@ -177,30 +242,33 @@ fn get_contract_crate_ids(
     test_crate_ids: Vec<CrateId>,
     unit: &CairoCompilationUnit,
     db: &mut RootDatabase,
-) -> Vec<CrateId> {
-    let mut all_crate_ids = build_external_contracts
-        .as_ref()
-        .map(|external_contracts| {
-            external_contracts
+) -> Result<Vec<CrateId>> {
+    let mut all_crate_ids = Vec::new();
+    if let Some(external_contracts) = build_external_contracts {
+        for (package_name, version) in external_contracts
+            .iter()
+            .map(|selector| (selector.package(), selector.version()))
+            .sorted()
+            .unique()
+        {
+            let selectors = external_contracts
                 .iter()
-                .map(|selector| selector.package())
-                .sorted()
-                .unique()
-                .map(|package_name| {
-                    let discriminator = unit
-                        .components()
-                        .iter()
-                        .find(|component| component.package.id.name == package_name)
-                        .and_then(|component| component.id.to_discriminator());
-                    let name = package_name.to_smolstr();
-                    db.intern_crate(CrateLongId::Real {
-                        name,
-                        discriminator,
-                    })
+                .filter(|selector| {
+                    selector.package() == package_name && selector.version() == version
                 })
-                .collect_vec()
-        })
-        .unwrap_or_default();
+                .cloned()
+                .collect_vec();
+            // Selectors not matching any package version are reported when searching contracts.
+            let Some(component) = select_component(unit, &package_name, version, &selectors)?
+            else {
+                continue;
+            };
+            all_crate_ids.push(db.intern_crate(CrateLongId::Real {
+                name: package_name.to_smolstr(),
+                discriminator: component.id.to_discriminator(),
+            }));
+        }
+    }
     all_crate_ids.extend(test_crate_ids);
-    all_crate_ids
+    Ok(all_crate_ids)
 }
@@ -31,6 +31,7 @@ pub struct ScarbDatabase {
     pub proc_macros: Vec<ProcMacroHostPlugin>,
 }
 
+#[tracing::instrument(skip_all, level = "debug")]
 pub(crate) fn build_scarb_root_database(
     unit: &CairoCompilationUnit,
     ws: &Workspace<'_>,
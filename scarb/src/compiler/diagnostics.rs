@@ -0,0 +1,97 @@
+use cairo_lang_diagnostics::Severity;
+use scarb_ui::{OutputFormat, Ui, Verbosity};
+
+/// Format of diagnostics reported while compiling or linting packages.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DiagnosticsFormat {
+    /// Multi-line diagnostics, including a snippet of the relevant source code.
+    #[default]
+    Human,
+    /// One line per diagnostic, in the `path:line:col: severity[code]: message` form.
+    Short,
+}
+
+/// Prints a diagnostic, already formatted by the Cairo compiler, in the requested format.
+///
+/// The short format only applies to text output, diagnostics are always reported in full
+/// in JSON mode.
+pub fn print_diagnostic(
+    ui: &Ui,
+    format: DiagnosticsFormat,
+    severity: Severity,
+    code: Option<&str>,
+    message: &str,
+) {
+    if format == DiagnosticsFormat::Short && matches!(ui.output_format(), OutputFormat::Text) {
+        if severity == Severity::Warning && ui.verbosity() <= Verbosity::NoWarnings {
+            return;
+        }
+        ui.print(short_diagnostic(severity, code, message));
+        return;
+    }
+
+    match (severity, code) {
+        (Severity::Error, Some(code)) => ui.error_with_code(code, message),
+        (Severity::Error, None) => ui.error(message),
+        (Severity::Warning, Some(code)) => ui.warn_with_code(code, message),
+        (Severity::Warning, None) => ui.warn(message),
+    }
+}
+
+/// Squashes a formatted diagnostic into a single line.
+///
+/// The location is taken from the ` --> path:line:col` line emitted by the Cairo compiler,
+/// and only the first line of the message is kept.
+fn short_diagnostic(severity: Severity, code: Option<&str>, message: &str) -> String {
+    let ty = match severity {
+        Severity::Error => "error",
+        Severity::Warning => "warn",
+    };
+    let code = code.map(|code| format!("[{code}]")).unwrap_or_default();
+    let summary = message.lines().next().unwrap_or_default();
+    let location = message
+        .lines()
+        .find_map(|line| line.trim_start().strip_prefix("--> "));
+    match location {
+        Some(location) => format!("{location}: {ty}{code}: {summary}"),
+        None => format!("{ty}{code}: {summary}"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use cairo_lang_diagnostics::Severity;
+    use indoc::indoc;
+    use test_case::test_case;
+
+    use super::short_diagnostic;
+
+    #[test_case(
+        Severity::Error,
+        Some("E0006"),
+        indoc! {"
+            Identifier not found.
+             --> /hello/src/lib.cairo:1:21
+            fn f() -> felt252 { x }
+                                ^
+        "}
+        => "/hello/src/lib.cairo:1:21: error[E0006]: Identifier not found.";
+        "error with code"
+    )]
+    #[test_case(
+        Severity::Warning,
+        None,
+        indoc! {"
+            Unused variable. Consider ignoring by prefixing with `_`.
+             --> /hello/src/lib.cairo:2:9
+                let x = 1;
+                    ^
+        "}
+        => "/hello/src/lib.cairo:2:9: warn: Unused variable. Consider ignoring by prefixing with `_`.";
+        "warning without code"
+    )]
+    #[test_case(Severity::Error, None, "Cycle detected." => "error: Cycle detected."; "no location")]
+    fn short(severity: Severity, code: Option<&str>, message: &str) -> String {
+        short_diagnostic(severity, code, message)
+    }
+}
@@ -1,18 +1,24 @@
 //! Various utility functions helpful for interacting with Cairo compiler.
 
+use crate::compiler::diagnostics::print_diagnostic;
 use crate::compiler::{CairoCompilationUnit, CompilationUnitAttributes};
 use crate::core::{InliningStrategy, Workspace};
 use crate::flock::Filesystem;
+use crate::internal::fsx;
 use anyhow::{Context, Result};
 use cairo_lang_compiler::CompilerConfig;
 use cairo_lang_compiler::db::RootDatabase;
 use cairo_lang_compiler::diagnostics::DiagnosticsReporter;
-use cairo_lang_diagnostics::{FormattedDiagnosticEntry, Severity};
+use cairo_lang_diagnostics::FormattedDiagnosticEntry;
 use cairo_lang_filesystem::db::FilesGroup;
 use cairo_lang_filesystem::ids::CrateId;
+use camino::Utf8Path;
+use flate2::Compression;
+use flate2::write::GzEncoder;
 use itertools::Itertools;
 use serde::Serialize;
 use std::collections::HashSet;
+use std::fs::File;
 use std::io::{BufWriter, Write};
 
 pub struct CountingWriter<W> {
@@ -48,6 +54,34 @@ pub fn build_compiler_config<'c>(
     cached_crates: &[CrateId],
     ws: &Workspace<'c>,
 ) -> CompilerConfig<'c> {
+    let diagnostics_reporter = build_diagnostics_reporter(db, main_crate_ids, cached_crates, ws);
+    CompilerConfig {
+        diagnostics_reporter: if unit.compiler_config.allow_warnings {
+            diagnostics_reporter.allow_warnings()
+        } else {
+            diagnostics_reporter
+        },
+        replace_ids: unit.compiler_config.sierra_replace_ids,
+        inlining_strategy: unit.compiler_config.inlining_strategy.clone().into(),
+        add_statements_functions: unit
+            .compiler_config
+            .unstable_add_statements_functions_debug_info,
+        add_statements_code_locations: unit
+            .compiler_config
+            .unstable_add_statements_code_locations_debug_info,
+        ..CompilerConfig::default()
+    }
+}
+
+/// Builds a reporter printing diagnostics of `main_crate_ids` and crates not in `cached_crates`.
+///
+/// Warnings are not allowed, unless [`DiagnosticsReporter::allow_warnings`] is called.
+pub fn build_diagnostics_reporter<'c>(
+    db: &RootDatabase,
+    main_crate_ids: &[CrateId],
+    cached_crates: &[CrateId],
+    ws: &Workspace<'c>,
+) -> DiagnosticsReporter<'c> {
     let ignore_warnings_crates = db
         .crates()
         .into_iter()
@@ -59,7 +93,7 @@ pub fn build_compiler_config<'c>(
         .filter(|crate_id| !cached_crates.contains(crate_id))
         .chain(main_crate_ids.iter().cloned())
         .collect();
-    let diagnostics_reporter = DiagnosticsReporter::callback({
+    DiagnosticsReporter::callback({
         let config = ws.config();
 
         |entry: FormattedDiagnosticEntry| {
@@ -67,22 +101,14 @@ pub fn build_compiler_config<'c>(
                 .message()
                 .strip_suffix('\n')
                 .unwrap_or(entry.message());
-            match entry.severity() {
-                Severity::Error => {
-                    if let Some(code) = entry.error_code() {
-                        config.ui().error_with_code(code.as_str(), msg)
-                    } else {
-                        config.ui().error(msg)
-                    }
-                }
-                Severity::Warning => {
-                    if let Some(code) = entry.error_code() {
-                        config.ui().warn_with_code(code.as_str(), msg)
-                    } else {
-                        config.ui().warn(msg)
-                    }
-                }
-            };
+            let code = entry.error_code();
+            print_diagnostic(
+                &config.ui(),
+                config.diagnostics_format(),
+                entry.severity(),
+                code.as_ref().map(|code| code.as_str()),
+                msg,
+            );
         }
     })
     .with_ignore_warnings_crates(&ignore_warnings_crates)
@@ -91,23 +117,7 @@ pub fn build_compiler_config<'c>(
     // So if there were any diagnotics here to show, it would mean that the cache is outdated - thus
     // we should not use it in the first place.
     // Note we still add the main crate, as we want it to be checked for warnings.
-    .with_crates(&crates_to_check.into_iter().collect_vec());
-    CompilerConfig {
-        diagnostics_reporter: if unit.compiler_config.allow_warnings {
-            diagnostics_reporter.allow_warnings()
-        } else {
-            diagnostics_reporter
-        },
-        replace_ids: unit.compiler_config.sierra_replace_ids,
-        inlining_strategy: unit.compiler_config.inlining_strategy.clone().into(),
-        add_statements_functions: unit
-            .compiler_config
-            .unstable_add_statements_functions_debug_info,
-        add_statements_code_locations: unit
-            .compiler_config
-            .unstable_add_statements_code_locations_debug_info,
-        ..CompilerConfig::default()
-    }
+    .with_crates(&crates_to_check.into_iter().collect_vec())
 }
 
 impl From<InliningStrategy> for cairo_lang_lowering::utils::InliningStrategy {
@@ -136,8 +146,11 @@ impl From<cairo_lang_lowering::utils::InliningStrategy> for InliningStrategy {
 }
 
 pub fn collect_main_crate_ids(unit: &CairoCompilationUnit, db: &RootDatabase) -> Vec<CrateId> {
-    let main_component = unit.main_component();
-    vec![main_component.crate_id(db)]
+    unit.main_components()
+        .into_iter()
+        .map(|component| component.crate_id(db))
+        .unique()
+        .collect()
 }
 
 pub fn write_json(
@@ -147,11 +160,10 @@ pub fn write_json(
     ws: &Workspace<'_>,
     value: impl Serialize,
 ) -> Result<()> {
-    let file = target_dir.create_rw(file_name, description, ws.config())?;
-    let file = BufWriter::new(&*file);
-    serde_json::to_writer(file, &value)
-        .with_context(|| format!("failed to serialize {file_name}"))?;
-    Ok(())
+    write_artifact(file_name, description, target_dir, ws, |writer| {
+        serde_json::to_writer(writer, &value)
+            .with_context(|| format!("failed to serialize {file_name}"))
+    })
 }
 
 pub fn write_json_with_byte_count(
@@ -161,10 +173,55 @@ pub fn write_json_with_byte_count(
     ws: &Workspace<'_>,
     value: impl Serialize,
 ) -> Result<usize> {
-    let file = target_dir.create_rw(file_name, description, ws.config())?;
-    let file = BufWriter::new(&*file);
-    let mut writer = CountingWriter::new(file);
-    serde_json::to_writer(&mut writer, &value)
+    write_artifact(file_name, description, target_dir, ws, |writer| {
+        serialize_json_with_byte_count(file_name, writer, &value)
+    })
+}
+
+/// Like [`write_json_with_byte_count`], but compresses the file with gzip.
+///
+/// Returns sizes of the serialized value and of the compressed file, in bytes.
+pub fn write_gzipped_json_with_byte_count(
+    file_name: &str,
+    description: &str,
+    target_dir: &Filesystem,
+    ws: &Workspace<'_>,
+    value: impl Serialize,
+) -> Result<(usize, usize)> {
+    write_gzipped_artifact(file_name, description, target_dir, ws, |writer| {
+        serialize_json_with_byte_count(file_name, writer, &value)
+    })
+}
+
+/// Like [`write_artifact`], but compresses everything `write` writes with gzip.
+///
+/// The `write` function returns the number of bytes it has written, which is returned along with
+/// the size of the compressed file.
+pub fn write_gzipped_artifact(
+    file_name: &str,
+    description: &str,
+    target_dir: &Filesystem,
+    ws: &Workspace<'_>,
+    write: impl FnOnce(&mut dyn Write) -> Result<usize>,
+) -> Result<(usize, usize)> {
+    write_artifact(file_name, description, target_dir, ws, |writer| {
+        let mut compressed = CountingWriter::new(writer);
+        let mut encoder = GzEncoder::new(&mut compressed, Compression::default());
+        let original_size = write(&mut encoder)?;
+        encoder
+            .finish()
+            .with_context(|| format!("failed to compress {file_name}"))?;
+        Ok((original_size, compressed.byte_count))
+    })
+}
+
+fn serialize_json_with_byte_count(
+    file_name: &str,
+    writer: impl Write,
+    value: &impl Serialize,
+) -> Result<usize> {
+    let mut writer = CountingWriter::new(writer);
+    serde_json::to_writer(&mut writer, value)
         .with_context(|| format!("failed to serialize {file_name}"))?;
     Ok(writer.byte_count)
 }
@@ -176,7 +233,197 @@ pub fn write_string(
     ws: &Workspace<'_>,
     value: impl ToString,
 ) -> Result<()> {
-    let mut file = target_dir.create_rw(file_name, description, ws.config())?;
-    file.write_all(value.to_string().as_bytes())?;
-    Ok(())
+    write_artifact(file_name, description, target_dir, ws, |writer| {
+        writer.write_all(value.to_string().as_bytes())?;
+        Ok(())
+    })
+}
+
+/// Writes an output file to the [`ArtifactSink`](crate::compiler::ArtifactSink) configured for the current run, if any,
+/// or to the target directory otherwise.
+pub fn write_artifact<T>(
+    file_name: &str,
+    description: &str,
+    target_dir: &Filesystem,
+    ws: &Workspace<'_>,
+    write: impl FnOnce(&mut dyn Write) -> Result<T>,
+) -> Result<T> {
+    let Some(sink) = ws.config().artifact_sink() else {
+        let value = write_atomically(file_name, description, target_dir, ws, |file| {
+            let mut writer = BufWriter::new(file);
+            let value = write(&mut writer)?;
+            writer.flush()?;
+            Ok(value)
+        })?;
+        ws.record_artifact_written(&target_dir.path_unchecked().join(file_name));
+        return Ok(value);
+    };
+
+    let mut contents = Vec::new();
+    let value = write(&mut contents)?;
+    let path = target_dir.path_unchecked().join(file_name);
+    sink.write(&path, &contents)
+        .with_context(|| format!("failed to write {file_name}"))?;
+    ws.record_artifact_written(&path);
+    Ok(value)
+}
+
+/// Splits `file_name`, relative to `dir`, into the directory containing the file and its name.
+///
+/// A [`Filesystem`] only creates its own root when opening files, so files in subdirectories,
+/// like the ones of the nested contracts layout, have to be opened through a child [`Filesystem`].
+pub fn split_file_name<'a>(dir: &Filesystem, file_name: &'a str) -> (Filesystem, &'a str) {
+    let path = Utf8Path::new(file_name);
+    match (path.parent(), path.file_name()) {
+        (Some(parent), Some(name)) if !parent.as_str().is_empty() => (dir.child(parent), name),
+        _ => (dir.clone(), file_name),
+    }
+}
+
+/// Writes an output file through a temporary file, which is renamed to `file_name` only once
+/// `write` succeeds, so that a failed write never leaves a truncated artifact in the target
+/// directory.
+///
+/// Unlike [`write_artifact`], this does not record the file as a written artifact, so it is also
+/// suitable for internal files, like fingerprints.
+pub fn write_atomically<T>(
+    file_name: &str,
+    description: &str,
+    target_dir: &Filesystem,
+    ws: &Workspace<'_>,
+    write: impl FnOnce(&File) -> Result<T>,
+) -> Result<T> {
+    let (target_dir, file_name) = split_file_name(target_dir, file_name);
+    let mut file = target_dir.create_rw(format!("{file_name}.tmp"), description, ws.config())?;
+    match write(&*file) {
+        Ok(value) => {
+            file.rename(target_dir.path_existent()?.join(file_name))?;
+            Ok(value)
+        }
+        Err(err) => {
+            let path = file.path().to_path_buf();
+            drop(file);
+            fsx::remove_file(path)?;
+            Err(err)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use anyhow::Result;
+    use assert_fs::TempDir;
+    use assert_fs::prelude::*;
+    use camino::{Utf8Path, Utf8PathBuf};
+    use serde::ser::{Error, SerializeSeq};
+    use serde::{Serialize, Serializer};
+
+    use scarb_test_support::project_builder::ProjectBuilder;
+
+    use super::{write_json, write_string};
+    use crate::compiler::ArtifactSink;
+    use crate::core::Config;
+    use crate::ops;
+
+    /// Starts serializing a sequence and fails in the middle of it.
+    struct FailingValue;
+
+    impl Serialize for FailingValue {
+        fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            let mut seq = serializer.serialize_seq(None)?;
+            seq.serialize_element(&1)?;
+            Err(S::Error::custom("serialization failed"))
+        }
+    }
+
+    #[test]
+    fn failed_write_leaves_no_partial_file() {
+        let t = TempDir::new().unwrap();
+        ProjectBuilder::start().name("hello").build(&t);
+        let cache_dir = TempDir::new().unwrap();
+        let config_dir = TempDir::new().unwrap();
+
+        let manifest_path = Utf8Path::from_path(t.child("Scarb.toml").path())
+            .unwrap()
+            .to_path_buf();
+        let config = Config::builder(manifest_path.clone())
+            .global_cache_dir_override(Some(Utf8Path::from_path(&cache_dir).unwrap().to_path_buf()))
+            .global_config_dir_override(Some(
+                Utf8Path::from_path(&config_dir).unwrap().to_path_buf(),
+            ))
+            .build()
+            .unwrap();
+        let ws = ops::read_workspace(&manifest_path, &config).unwrap();
+        let target_dir = ws.target_dir().child("dev");
+
+        let err =
+            write_json("hello.json", "output file", &target_dir, &ws, FailingValue).unwrap_err();
+        assert_eq!(err.to_string(), "failed to serialize hello.json");
+        assert!(
+            target_dir
+                .path_existent()
+                .unwrap()
+                .read_dir()
+                .unwrap()
+                .next()
+                .is_none()
+        );
+        assert_eq!(ws.artifacts_written(), 0);
+    }
+
+    /// Keeps written artifacts in memory.
+    #[derive(Clone, Default)]
+    struct MemorySink(Arc<Mutex<Vec<(Utf8PathBuf, Vec<u8>)>>>);
+
+    impl ArtifactSink for MemorySink {
+        fn write(&self, path: &Utf8Path, contents: &[u8]) -> Result<()> {
+            self.0
+                .lock()
+                .unwrap()
+                .push((path.to_path_buf(), contents.to_vec()));
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn artifacts_are_passed_to_custom_sink() {
+        let t = TempDir::new().unwrap();
+        ProjectBuilder::start().name("hello").build(&t);
+        let cache_dir = TempDir::new().unwrap();
+        let config_dir = TempDir::new().unwrap();
+        let sink = MemorySink::default();
+
+        let manifest_path = Utf8Path::from_path(t.child("Scarb.toml").path())
+            .unwrap()
+            .to_path_buf();
+        let config = Config::builder(manifest_path.clone())
+            .global_cache_dir_override(Some(Utf8Path::from_path(&cache_dir).unwrap().to_path_buf()))
+            .global_config_dir_override(Some(
+                Utf8Path::from_path(&config_dir).unwrap().to_path_buf(),
+            ))
+            .artifact_sink(sink.clone())
+            .build()
+            .unwrap();
+        let ws = ops::read_workspace(&manifest_path, &config).unwrap();
+        let target_dir = ws.target_dir().child("dev");
+
+        write_json("hello.json", "output file", &target_dir, &ws, vec![1, 2]).unwrap();
+        write_string("hello.txt", "output file", &target_dir, &ws, "hello").unwrap();
+
+        let written = sink.0.lock().unwrap();
+        assert_eq!(
+            written
+                .iter()
+                .map(|(path, contents)| (path.file_name().unwrap(), contents.as_slice()))
+                .collect::<Vec<_>>(),
+            vec![
+                ("hello.json", b"[1,2]".as_slice()),
+                ("hello.txt", b"hello".as_slice()),
+            ]
+        );
+        assert!(!target_dir.path_unchecked().join("hello.json").exists());
+        assert_eq!(ws.artifacts_written(), 2);
+    }
 }
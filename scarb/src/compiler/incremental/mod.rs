@@ -2,4 +2,6 @@ mod compilation;
 mod fingerprint;
 mod source;
 
+pub(crate) use compilation::incremental_allowed;
 pub use compilation::{load_incremental_artifacts, save_incremental_artifacts};
+pub(crate) use fingerprint::{UnitFingerprint, is_fresh};
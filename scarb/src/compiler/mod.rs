@@ -1,15 +1,20 @@
 use anyhow::Result;
+pub use artifact_sink::ArtifactSink;
 use cairo_lang_compiler::db::RootDatabase;
 use cairo_lang_filesystem::ids::CrateId;
 pub use compilation_unit::*;
+pub use compilers::{ContractSelector, ContractSelectorsResolution, resolve_contract_selectors};
+pub(crate) use compilers::{ContractSelectorsCache, ProjectContracts, find_unit_contracts};
 pub use profile::*;
 pub use repository::*;
 
 use crate::core::{TargetKind, Workspace};
 
+mod artifact_sink;
 mod compilation_unit;
 mod compilers;
 pub mod db;
+pub mod diagnostics;
 pub mod helpers;
 pub mod incremental;
 pub mod plugin;
@@ -181,7 +181,7 @@ impl<'c> From<CargoCommand<'c>> for Command {
             CargoAction::Check => cmd.arg("check"),
             CargoAction::Package(_) => cmd.arg("package"),
         };
-        if args.config.offline() {
+        if !args.config.network_allowed() {
             cmd.arg("--offline");
         }
         match args.action {
@@ -2,7 +2,7 @@ use std::collections::HashMap;
 use std::collections::hash_map::Entry;
 use std::fmt;
 
-use anyhow::{Result, bail};
+use anyhow::{Result, bail, ensure};
 use cairo_lang_compiler::db::RootDatabase;
 use itertools::Itertools;
 use smol_str::SmolStr;
@@ -12,8 +12,20 @@ use crate::compiler::compilers::{
 };
 use crate::compiler::incremental::{load_incremental_artifacts, save_incremental_artifacts};
 use crate::compiler::{CairoCompilationUnit, CompilationUnitAttributes, Compiler};
-use crate::core::Workspace;
+use crate::core::{TargetKind, Workspace};
 
+/// Target kinds with compilers, or other meaning, built into Scarb.
+///
+/// Compilers registered with [`CompilerRepository::add_custom`] cannot take over these kinds.
+pub const BUILTIN_TARGET_KINDS: [TargetKind; 5] = [
+    TargetKind::LIB,
+    TargetKind::TEST,
+    TargetKind::STARKNET_CONTRACT,
+    TargetKind::EXECUTABLE,
+    TargetKind::CAIRO_PLUGIN,
+];
+
+/// Compilers dispatched to by the target kind of the compilation unit main component.
 pub struct CompilerRepository {
     compilers: HashMap<SmolStr, Box<dyn Compiler>>,
 }
@@ -45,6 +57,25 @@ impl CompilerRepository {
         }
     }
 
+    /// Registers a compiler for a target kind not known to Scarb, like one added by a third-party
+    /// Scarb extension.
+    ///
+    /// Fails if the kind is one of [`BUILTIN_TARGET_KINDS`] or already has a compiler.
+    pub fn add_custom(&mut self, compiler: Box<dyn Compiler>) -> Result<()> {
+        let target_kind = compiler.target_kind();
+        ensure!(
+            !BUILTIN_TARGET_KINDS.contains(&target_kind),
+            "cannot register custom compiler for built-in target `{target_kind}`"
+        );
+        self.add(compiler)
+    }
+
+    /// Like [`CompilerRepository::add_custom`], but consumes and returns the repository.
+    pub fn with_custom(mut self, compiler: Box<dyn Compiler>) -> Result<Self> {
+        self.add_custom(compiler)?;
+        Ok(self)
+    }
+
     pub fn compile(
         &self,
         unit: CairoCompilationUnit,
@@ -70,3 +101,70 @@ impl fmt::Debug for CompilerRepository {
             .finish()
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use anyhow::Result;
+    use cairo_lang_compiler::db::RootDatabase;
+    use cairo_lang_filesystem::ids::CrateId;
+    use test_case::test_case;
+
+    use super::CompilerRepository;
+    use crate::compiler::{CairoCompilationUnit, Compiler};
+    use crate::core::{TargetKind, Workspace};
+
+    struct CustomCompiler(TargetKind);
+
+    impl Compiler for CustomCompiler {
+        fn target_kind(&self) -> TargetKind {
+            self.0.clone()
+        }
+
+        fn compile(
+            &self,
+            _unit: &CairoCompilationUnit,
+            _cached_crates: &[CrateId],
+            _db: &mut RootDatabase,
+            _ws: &Workspace<'_>,
+        ) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    fn add_custom(kind: &str) -> Result<()> {
+        CompilerRepository::std().add_custom(Box::new(CustomCompiler(TargetKind::try_new(kind)?)))
+    }
+
+    #[test]
+    fn registers_custom_target_kind() {
+        let repo = CompilerRepository::std()
+            .with_custom(Box::new(CustomCompiler(TargetKind::new("prover"))))
+            .unwrap();
+        assert!(repo.compilers.contains_key("prover"));
+    }
+
+    #[test_case("lib")]
+    #[test_case("test")]
+    #[test_case("starknet-contract")]
+    #[test_case("executable")]
+    #[test_case("cairo-plugin")]
+    fn rejects_builtin_target_kinds(kind: &str) {
+        assert_eq!(
+            add_custom(kind).unwrap_err().to_string(),
+            format!("cannot register custom compiler for built-in target `{kind}`")
+        );
+    }
+
+    #[test]
+    fn rejects_duplicate_custom_target_kinds() {
+        let mut repo = CompilerRepository::std();
+        repo.add_custom(Box::new(CustomCompiler(TargetKind::new("prover"))))
+            .unwrap();
+        assert_eq!(
+            repo.add_custom(Box::new(CustomCompiler(TargetKind::new("prover"))))
+                .unwrap_err()
+                .to_string(),
+            "found duplicate compiler for target `prover`"
+        );
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::ffi::{OsStr, OsString};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -9,21 +10,26 @@ use camino::{Utf8Path, Utf8PathBuf};
 use once_cell::sync::OnceCell;
 use tokio::runtime::{Builder, Handle, Runtime};
 use tracing::trace;
+use url::Url;
 use which::which_in;
 
 use scarb_ui::{OutputFormat, Ui, Verbosity};
 
 use crate::SCARB_ENV;
+use crate::compiler::diagnostics::DiagnosticsFormat;
 use crate::compiler::plugin::CairoPluginRepository;
 use crate::compiler::plugin::proc_macro::ProcMacroRepository;
-use crate::compiler::{CompilerRepository, Profile};
+use crate::compiler::{ArtifactSink, CompilerRepository, Profile};
 use crate::core::AppDirs;
 #[cfg(doc)]
 use crate::core::Workspace;
+use crate::core::registry::DEFAULT_REGISTRY_INDEX_ENV;
+use crate::core::source::SourceCache;
 use crate::flock::AdvisoryLock;
 use crate::internal::fsx;
+use crate::internal::fsx::PathBufUtf8Ext;
 
-use super::ManifestDependency;
+use super::{ManifestDependency, PackageName};
 
 const USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
 
@@ -37,8 +43,11 @@ pub struct Config {
     // HACK: This should be the lifetime of Config itself, but we cannot express that, so we
     //   put static lifetime here and transmute in getter function.
     package_cache_lock: OnceCell<AdvisoryLock<'static>>,
+    // HACK: Same as above, sources loaded with this config borrow it.
+    source_cache: SourceCache<'static>,
     log_filter_directive: OsString,
     offline: bool,
+    frozen: bool,
     compilers: CompilerRepository,
     cairo_plugins: CairoPluginRepository,
     proc_macro_repository: ProcMacroRepository,
@@ -49,6 +58,11 @@ pub struct Config {
     profile: Profile,
     http_client: OnceCell<reqwest::Client>,
     load_prebuilt_proc_macros: bool,
+    diagnostics_format: DiagnosticsFormat,
+    allowed_path_dep_roots: Option<Vec<Utf8PathBuf>>,
+    artifact_sink: Option<Arc<dyn ArtifactSink>>,
+    precise_overrides: BTreeMap<PackageName, String>,
+    default_registry_index: Option<Url>,
 }
 
 impl Config {
@@ -73,6 +87,51 @@ impl Config {
             }
         }
 
+        // Relative target directory overrides are resolved against the current directory,
+        // so that artifact paths stay valid for subprocesses running in other directories.
+        let target_dir_override = b
+            .target_dir_override
+            .map(|dir| -> Result<Utf8PathBuf> {
+                if dir.is_relative() {
+                    Ok(env::current_dir()?.try_into_utf8()?.join(dir))
+                } else {
+                    Ok(dir)
+                }
+            })
+            .transpose()?;
+
+        // Roots are canonicalized, just like paths of path dependencies are.
+        let allowed_path_dep_roots = b
+            .allowed_path_dep_roots
+            .map(|roots| {
+                roots
+                    .into_iter()
+                    .map(|root| -> Result<Utf8PathBuf> {
+                        let root = if root.is_relative() {
+                            env::current_dir()?.try_into_utf8()?.join(root)
+                        } else {
+                            root
+                        };
+                        Ok(fsx::canonicalize_utf8(&root).unwrap_or(root))
+                    })
+                    .collect::<Result<Vec<_>>>()
+            })
+            .transpose()?;
+
+        let default_registry_index = match b.default_registry_index {
+            Some(index) => Some(index),
+            None => env::var_os(DEFAULT_REGISTRY_INDEX_ENV)
+                .map(|index| -> Result<Url> {
+                    let index = index.to_string_lossy();
+                    Url::parse(&index).with_context(|| {
+                        format!(
+                            "invalid URL in `{DEFAULT_REGISTRY_INDEX_ENV}` environment variable: {index}"
+                        )
+                    })
+                })
+                .transpose()?,
+        };
+
         let compilers = b.compilers.unwrap_or_else(CompilerRepository::std);
         let compiler_plugins = b.cairo_plugins.unwrap_or_else(CairoPluginRepository::std);
         let profile: Profile = b.profile.unwrap_or_default();
@@ -84,17 +143,24 @@ impl Config {
         Ok(Self {
             manifest_path: b.manifest_path,
             dirs,
-            target_dir_override: b.target_dir_override,
+            target_dir_override,
             app_exe: OnceCell::new(),
             ui,
             creation_time,
             package_cache_lock: OnceCell::new(),
+            source_cache: SourceCache::default(),
             log_filter_directive: b.log_filter_directive.unwrap_or_default(),
             offline: b.offline,
+            frozen: b.frozen,
             compilers,
             cairo_plugins: compiler_plugins,
             proc_macro_repository: ProcMacroRepository::new(b.load_proc_macros),
             load_prebuilt_proc_macros: b.load_prebuilt_proc_macros,
+            diagnostics_format: DiagnosticsFormat::default(),
+            allowed_path_dep_roots,
+            artifact_sink: b.artifact_sink,
+            precise_overrides: b.precise_overrides,
+            default_registry_index,
             custom_source_patches: b.custom_source_patches,
             tokio_runtime: OnceCell::new(),
             tokio_handle,
@@ -183,6 +249,15 @@ impl Config {
         self.ui = ui;
     }
 
+    /// Format in which compiler and linter diagnostics are printed.
+    pub fn diagnostics_format(&self) -> DiagnosticsFormat {
+        self.diagnostics_format
+    }
+
+    pub fn set_diagnostics_format(&mut self, diagnostics_format: DiagnosticsFormat) {
+        self.diagnostics_format = diagnostics_format;
+    }
+
     pub fn elapsed_time(&self) -> Duration {
         self.creation_time.elapsed()
     }
@@ -200,6 +275,12 @@ impl Config {
         not_static_al
     }
 
+    /// Sources loaded with this config, shared by all registries created during a single run.
+    pub(crate) fn source_cache<'a>(&'a self) -> &'a SourceCache<'a> {
+        // UNSAFE: This mem::transmute call only changes generic lifetime parameters.
+        unsafe { mem::transmute(&self.source_cache) }
+    }
+
     pub fn tokio_handle(&self) -> &Handle {
         self.tokio_handle.get_or_init(|| {
             // No tokio runtime handle stored yet.
@@ -225,10 +306,26 @@ impl Config {
         self.offline
     }
 
+    pub fn set_offline(&mut self, offline: bool) {
+        self.offline = offline;
+    }
+
+    /// States whether the _Frozen Mode_ is turned on.
+    ///
+    /// In this mode, Scarb neither accesses the network nor modifies the lockfile.
+    pub const fn frozen(&self) -> bool {
+        self.frozen
+    }
+
     /// If `false`, Scarb should never access the network, but otherwise it should continue
     /// operating if possible.
     pub const fn network_allowed(&self) -> bool {
-        !self.offline()
+        !self.offline() && !self.frozen()
+    }
+
+    /// Name of the mode forbidding network access, for use in error messages.
+    pub const fn offline_mode_name(&self) -> &'static str {
+        if self.frozen { "frozen" } else { "offline" }
     }
 
     pub fn compilers(&self) -> &CompilerRepository {
@@ -251,6 +348,10 @@ impl Config {
         self.profile.clone()
     }
 
+    pub fn set_profile(&mut self, profile: Profile) {
+        self.profile = profile;
+    }
+
     /// Returns handle to the global HTTP client.
     ///
     /// The global client maintains an internal connection pool, and is preconfigured with known
@@ -294,7 +395,8 @@ impl Config {
     pub fn online_http(&self) -> Result<reqwest::Client> {
         ensure!(
             self.network_allowed(),
-            "cannot access the network in offline mode"
+            "cannot access the network in {} mode",
+            self.offline_mode_name()
         );
         self.http()
     }
@@ -303,6 +405,29 @@ impl Config {
     pub fn load_prebuilt_proc_macros(&self) -> bool {
         self.load_prebuilt_proc_macros
     }
+
+    /// Directories which path dependencies must be located in, if restricted.
+    pub fn allowed_path_dep_roots(&self) -> Option<&[Utf8PathBuf]> {
+        self.allowed_path_dep_roots.as_deref()
+    }
+
+    /// Git revisions which named packages are resolved at, overriding the lockfile.
+    pub fn precise_overrides(&self) -> &BTreeMap<PackageName, String> {
+        &self.precise_overrides
+    }
+
+    /// Index which packages of the default registry are read from, if overridden.
+    ///
+    /// Workspaces may override it further in their root manifest,
+    /// see [`Workspace::default_registry_index`].
+    pub fn default_registry_index(&self) -> Option<&Url> {
+        self.default_registry_index.as_ref()
+    }
+
+    /// Custom destination of emitted artifacts, `None` if artifacts are written to files.
+    pub fn artifact_sink(&self) -> Option<&dyn ArtifactSink> {
+        self.artifact_sink.as_deref()
+    }
 }
 
 #[derive(Debug)]
@@ -315,6 +440,7 @@ pub struct ConfigBuilder {
     ui_verbosity: Verbosity,
     ui_output_format: OutputFormat,
     offline: bool,
+    frozen: bool,
     log_filter_directive: Option<OsString>,
     compilers: Option<CompilerRepository>,
     cairo_plugins: Option<CairoPluginRepository>,
@@ -323,6 +449,10 @@ pub struct ConfigBuilder {
     profile: Option<Profile>,
     load_proc_macros: bool,
     load_prebuilt_proc_macros: bool,
+    allowed_path_dep_roots: Option<Vec<Utf8PathBuf>>,
+    artifact_sink: Option<Arc<dyn ArtifactSink>>,
+    precise_overrides: BTreeMap<PackageName, String>,
+    default_registry_index: Option<Url>,
 }
 
 impl ConfigBuilder {
@@ -336,6 +466,7 @@ impl ConfigBuilder {
             ui_verbosity: Verbosity::Normal,
             ui_output_format: OutputFormat::Text,
             offline: false,
+            frozen: false,
             log_filter_directive: None,
             compilers: None,
             cairo_plugins: None,
@@ -344,6 +475,10 @@ impl ConfigBuilder {
             profile: None,
             load_proc_macros: true,
             load_prebuilt_proc_macros: true,
+            allowed_path_dep_roots: None,
+            artifact_sink: None,
+            precise_overrides: BTreeMap::new(),
+            default_registry_index: None,
         }
     }
 
@@ -395,6 +530,11 @@ impl ConfigBuilder {
         self
     }
 
+    pub fn frozen(mut self, frozen: bool) -> Self {
+        self.frozen = frozen;
+        self
+    }
+
     pub fn log_filter_directive(
         mut self,
         log_filter_directive: Option<impl Into<OsString>>,
@@ -437,4 +577,32 @@ impl ConfigBuilder {
         self.load_prebuilt_proc_macros = load_prebuilt_proc_macros;
         self
     }
+
+    pub fn allowed_path_dep_roots(
+        mut self,
+        allowed_path_dep_roots: Option<Vec<Utf8PathBuf>>,
+    ) -> Self {
+        self.allowed_path_dep_roots = allowed_path_dep_roots;
+        self
+    }
+
+    /// Resolve the named Git packages at the given `precise` revisions, overriding the lockfile.
+    pub fn precise_overrides(mut self, precise_overrides: BTreeMap<PackageName, String>) -> Self {
+        self.precise_overrides = precise_overrides;
+        self
+    }
+
+    /// Read packages of the default registry from `default_registry_index`.
+    ///
+    /// If not set, the `SCARB_DEFAULT_REGISTRY_INDEX` environment variable is used, if present.
+    pub fn default_registry_index(mut self, default_registry_index: Option<Url>) -> Self {
+        self.default_registry_index = default_registry_index;
+        self
+    }
+
+    /// Pass emitted artifacts to the `artifact_sink` instead of writing them to the target directory.
+    pub fn artifact_sink(mut self, artifact_sink: impl ArtifactSink + 'static) -> Self {
+        self.artifact_sink = Some(Arc::new(artifact_sink));
+        self
+    }
 }
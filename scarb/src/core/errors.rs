@@ -13,3 +13,40 @@ impl ScriptExecutionError {
         Self { exit_code }
     }
 }
+
+/// Starknet contracts were requested to be compiled, while gas is disabled for the compilation unit.
+///
+/// Contract compilation requires the `add_withdraw_gas` compiler flag to be enabled.
+#[derive(Debug, Error)]
+#[error("the target starknet contract compilation requires gas to be enabled")]
+pub struct GasDisabledError;
+
+/// A [pretty URL](crate::core::SourceId::to_pretty_url) of a source could not be parsed.
+#[derive(Debug, Error)]
+pub enum SourceIdParseError {
+    /// The source protocol is not followed by a `+` and the source URL.
+    #[error("invalid source: {pretty_url}")]
+    MissingUrl { pretty_url: String },
+    /// The source URL is malformed.
+    #[error("cannot parse source URL: {pretty_url}")]
+    BadUrl {
+        pretty_url: String,
+        #[source]
+        source: url::ParseError,
+    },
+    /// The source protocol is none of the protocols supported by Scarb.
+    #[error("unsupported source protocol: {protocol}")]
+    UnknownProtocol { protocol: String },
+    /// The source URL was parsed, but does not describe a valid source,
+    /// like a Git source with a malformed precise revision.
+    #[error(transparent)]
+    InvalidSource(anyhow::Error),
+}
+
+/// The queried package is not present in the index of a registry.
+#[derive(Debug, Error)]
+#[error("package not found in registry: {package}")]
+pub struct PackageNotFoundError {
+    /// Description of the queried package.
+    pub package: String,
+}
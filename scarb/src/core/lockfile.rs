@@ -1,7 +1,7 @@
 use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::str::FromStr;
 
-use anyhow::{Context, Result, anyhow};
+use anyhow::{Context, Result, anyhow, bail};
 use itertools::Itertools;
 use semver::Version;
 use serde::{Deserialize, Serialize};
@@ -33,10 +33,11 @@ pub struct Lockfile {
     // and being more defensive against lock files tinkered with by hand. Thus, we use `SmallVec`
     // to optimise for the common case of a single entry only.
     packages: BTreeMap<PackageName, SmallVec<[PackageLock; 1]>>,
+    std: Option<StdLock>,
 }
 
 mod serdex {
-    use crate::core::lockfile::{LockVersion, PackageLock};
+    use crate::core::lockfile::{LockVersion, PackageLock, StdLock};
     use serde::{Deserialize, Serialize};
     use std::collections::BTreeSet;
 
@@ -47,11 +48,16 @@ mod serdex {
         #[serde(default = "BTreeSet::new")]
         #[serde(skip_serializing_if = "BTreeSet::is_empty")]
         packages: BTreeSet<PackageLock>,
+        #[serde(default)]
+        #[serde(skip_serializing_if = "Option::is_none")]
+        std: Option<StdLock>,
     }
 
     impl From<Lockfile> for super::Lockfile {
         fn from(value: Lockfile) -> Self {
-            Self::new(value.packages).with_version(value.version)
+            Self::new(value.packages)
+                .with_version(value.version)
+                .with_std(value.std)
         }
     }
 
@@ -60,6 +66,7 @@ mod serdex {
             Self {
                 version: value.version,
                 packages: value.packages.into_values().flatten().collect(),
+                std: value.std,
             }
         }
     }
@@ -85,6 +92,18 @@ pub struct PackageLock {
     pub dependencies: BTreeSet<PackageName>,
 }
 
+/// Identity of the standard library used to build the locked packages.
+///
+/// Std packages are not listed among locked packages, as they are always provided by the running
+/// Scarb. This entry makes it auditable which std (and thus Cairo compiler) version was in use,
+/// for example, when `cairo-version` requirements have been ignored.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct StdLock {
+    pub version: Version,
+    pub source: SourceId,
+}
+
 fn skip_path_source_id(sid: &Option<SourceId>) -> bool {
     sid.map(|sid| sid.is_path()).unwrap_or(true)
 }
@@ -102,6 +121,7 @@ impl Lockfile {
         Self {
             version: Default::default(),
             packages,
+            std: None,
         }
     }
 
@@ -109,6 +129,10 @@ impl Lockfile {
         Self { version, ..self }
     }
 
+    pub fn with_std(self, std: Option<StdLock>) -> Self {
+        Self { std, ..self }
+    }
+
     pub fn from_resolve(resolve: &Resolve) -> Self {
         let include_package = |package_id: &PackageId| !package_id.source_id.is_std();
         let packages = resolve
@@ -129,13 +153,24 @@ impl Lockfile {
                     .checksum(summary.checksum.clone())
                     .build()
             });
-        Self::new(packages)
+        let std = resolve
+            .package_ids()
+            .find(PackageId::is_core)
+            .map(|core| StdLock {
+                version: core.version.clone(),
+                source: core.source_id,
+            });
+        Self::new(packages).with_std(std)
     }
 
     pub fn packages(&self) -> impl Iterator<Item = &PackageLock> {
         self.packages.values().flatten()
     }
 
+    pub fn std(&self) -> Option<&StdLock> {
+        self.std.as_ref()
+    }
+
     pub fn packages_by_name<'a>(
         &'a self,
         name: &PackageName,
@@ -148,6 +183,12 @@ impl Lockfile {
     }
 
     pub fn package_matching(&self, dependency: ManifestDependency) -> Option<Result<PackageId>> {
+        self.lock_matching(&dependency)
+            .cloned()
+            .map(|p| p.try_into())
+    }
+
+    fn lock_matching(&self, dependency: &ManifestDependency) -> Option<&PackageLock> {
         self.packages_by_name(&dependency.name)
             .find(|p| dependency.matches_name_and_version(&p.name, &p.version))
             .filter(|p| {
@@ -156,8 +197,42 @@ impl Lockfile {
                     // No locking occurs on path sources.
                     .unwrap_or(false)
             })
+    }
+
+    /// Creates a lockfile with only the locks of given dependencies, dropping all other entries.
+    pub fn retain_locked<'a>(
+        &self,
+        dependencies: impl IntoIterator<Item = &'a ManifestDependency>,
+    ) -> Self {
+        let packages = dependencies
+            .into_iter()
+            .filter_map(|dependency| self.lock_matching(dependency))
             .cloned()
-            .map(|p| p.try_into())
+            .sorted()
+            .dedup();
+        Self::new(packages)
+    }
+
+    /// Pins locks of the named Git packages to the given `precise` revisions.
+    pub fn with_precise_overrides(
+        mut self,
+        overrides: &BTreeMap<PackageName, String>,
+    ) -> Result<Self> {
+        for (name, precise) in overrides {
+            let Some(locks) = self.packages.get_mut(name) else {
+                bail!(
+                    "cannot override precise revision of package `{name}`, \
+                    it is not present in the lockfile"
+                );
+            };
+            for lock in locks.iter_mut() {
+                let Some(source) = lock.source else {
+                    bail!("cannot set precise version for non-git source of package `{name}`");
+                };
+                lock.source = Some(source.with_precise(precise.clone())?);
+            }
+        }
+        Ok(self)
     }
 
     fn body(&self) -> Result<DocumentMut> {
@@ -244,7 +319,7 @@ mod tests {
     use expect_test::expect;
     use semver::Version;
 
-    use crate::core::lockfile::{Lockfile, PackageLock};
+    use crate::core::lockfile::{Lockfile, PackageLock, StdLock};
     use crate::core::{Checksum, PackageName, SourceId};
 
     #[test]
@@ -322,6 +397,36 @@ mod tests {
         assert_eq!(lock, deserialized);
     }
 
+    #[test]
+    fn with_std() {
+        let pkg = PackageLock::builder()
+            .name(PackageName::new("first"))
+            .version(Version::parse("1.0.0").unwrap())
+            .build();
+
+        let lock = Lockfile::new(vec![pkg]).with_std(Some(StdLock {
+            version: Version::parse("2.11.4").unwrap(),
+            source: SourceId::for_std(),
+        }));
+
+        let serialized = expect![[r#"
+            # Code generated by scarb DO NOT EDIT.
+            version = 1
+
+            [[package]]
+            name = "first"
+            version = "1.0.0"
+
+            [std]
+            version = "2.11.4"
+            source = "std"
+        "#]];
+
+        serialized.assert_eq(&lock.render().unwrap());
+        let deserialized = Lockfile::from_str(serialized.data()).unwrap();
+        assert_eq!(lock, deserialized);
+    }
+
     #[test]
     fn empty() {
         let lock = Lockfile::new([]);
@@ -127,12 +127,15 @@ impl From<ManifestCompilerConfig> for TomlCairo {
             sierra_replace_ids: Some(config.sierra_replace_ids),
             allow_warnings: Some(config.allow_warnings),
             enable_gas: Some(config.enable_gas),
-            unstable_add_statements_functions_debug_info: Some(
-                config.unstable_add_statements_functions_debug_info,
-            ),
-            unstable_add_statements_code_locations_debug_info: Some(
-                config.unstable_add_statements_code_locations_debug_info,
-            ),
+            // NOTE: Disabled debug info flags are left unset, so that they do not take precedence
+            //   over the `debug` shorthand when merged with profile definitions.
+            unstable_add_statements_functions_debug_info: config
+                .unstable_add_statements_functions_debug_info
+                .then_some(true),
+            unstable_add_statements_code_locations_debug_info: config
+                .unstable_add_statements_code_locations_debug_info
+                .then_some(true),
+            debug: None,
             panic_backtrace: Some(config.panic_backtrace),
             unsafe_panic: Some(config.unsafe_panic),
             inlining_strategy: Some(config.inlining_strategy),
@@ -21,6 +21,12 @@ pub struct ManifestDependencyInner {
     pub version_req: DependencyVersionReq,
     #[builder(default)]
     pub source_id: SourceId,
+    /// Whether updating dependencies re-resolves the Git branch of [`Self::source_id`].
+    ///
+    /// Non-tracking branch dependencies stay at the revision captured in the lockfile
+    /// (the `precise` revision of the locked source) until it is removed from the lockfile.
+    #[builder(default = true)]
+    pub track: bool,
     #[builder(default)]
     pub kind: DepKind,
     #[builder(default)]
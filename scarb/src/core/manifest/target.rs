@@ -7,6 +7,7 @@ use camino::{Utf8Path, Utf8PathBuf};
 use serde::{Deserialize, Serialize};
 use smol_str::SmolStr;
 
+use crate::compiler::ContractSelector;
 use crate::core::{TargetKind, TomlExternalTargetParams};
 use crate::internal::serdex::toml_merge;
 
@@ -114,7 +115,16 @@ impl Hash for TargetInner {
 #[serde(rename_all = "kebab-case")]
 pub struct TestTargetProps {
     pub test_type: TestTargetType,
-    pub build_external_contracts: Option<Vec<String>>,
+    pub build_external_contracts: Option<Vec<ContractSelector>>,
+    pub include_main_contracts: Option<bool>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub build_external_contracts_transitive: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub emit_sierra_text: bool,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub deny_warnings: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub emit: Option<Vec<TestEmit>>,
 }
 
 impl TestTargetProps {
@@ -122,17 +132,41 @@ impl TestTargetProps {
         Self {
             test_type,
             build_external_contracts: Default::default(),
+            include_main_contracts: Default::default(),
+            build_external_contracts_transitive: Default::default(),
+            emit_sierra_text: Default::default(),
+            deny_warnings: Default::default(),
+            emit: Default::default(),
         }
     }
 
+    pub fn include_main_contracts(&self) -> bool {
+        self.include_main_contracts.unwrap_or(true)
+    }
+
+    /// Whether the test compiler should write the `kind` output file, all are written by default.
+    pub fn emits(&self, kind: TestEmit) -> bool {
+        self.emit.as_ref().is_none_or(|emit| emit.contains(&kind))
+    }
+
     pub fn with_build_external_contracts(self, external: Vec<String>) -> Self {
         Self {
-            build_external_contracts: Some(external),
+            build_external_contracts: Some(external.into_iter().map(ContractSelector).collect()),
             ..self
         }
     }
 }
 
+/// Output files of the test compiler, selectable with the `emit` test target property.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum TestEmit {
+    /// The `[target name].test.sierra.json` file with the Sierra program of tests.
+    Sierra,
+    /// The `[target name].test.json` file with tests metadata, like their names.
+    Metadata,
+}
+
 #[derive(Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum TestTargetType {
@@ -24,7 +24,10 @@ use crate::core::manifest::maybe_workspace::{MaybeWorkspace, WorkspaceInherit};
 use crate::core::manifest::scripts::ScriptDefinition;
 use crate::core::manifest::{ManifestDependency, ManifestMetadata, Summary, Target};
 use crate::core::package::PackageId;
-use crate::core::registry::{DEFAULT_REGISTRY_INDEX, DEFAULT_REGISTRY_INDEX_PATCH_SOURCE};
+use crate::core::registry::{
+    DEFAULT_REGISTRY_INDEX, DEFAULT_REGISTRY_INDEX_PATCH_SOURCE, DEFAULT_REGISTRY_NAME,
+    RegistryMirrors,
+};
 use crate::core::source::{GitReference, SourceId};
 use crate::core::{
     Config, DepKind, DependencyVersionReq, EnabledFeature, InliningStrategy, ManifestBuilder,
@@ -58,6 +61,30 @@ pub struct TomlManifest {
     pub tool: Option<BTreeMap<SmolStr, MaybeWorkspaceTomlTool>>,
     pub features: Option<BTreeMap<FeatureName, Vec<TomlFeatureToEnable>>>,
     pub patch: Option<BTreeMap<SmolStr, BTreeMap<PackageName, TomlDependency>>>,
+    pub registries: Option<BTreeMap<SmolStr, TomlRegistry>>,
+    pub source: Option<BTreeMap<SmolStr, TomlSource>>,
+}
+
+/// Represents a registry definition in the `[registries]` section of a `Scarb.toml`.
+#[derive(Debug, Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TomlRegistry {
+    pub index: Option<Url>,
+    pub mirrors: Option<Vec<Url>>,
+    pub pure_mirrors: Option<bool>,
+}
+
+/// Represents a source definition in the `[source]` section of a `Scarb.toml`.
+///
+/// A source either points to the original location of packages (`git` or `registry`) and names
+/// the source it should be replaced with, or defines a local `directory` with vendored packages.
+#[derive(Debug, Clone, Default, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub struct TomlSource {
+    pub replace_with: Option<SmolStr>,
+    pub git: Option<Url>,
+    pub registry: Option<Url>,
+    pub directory: Option<RelativeUtf8PathBuf>,
 }
 
 type MaybeWorkspaceScriptDefinition = MaybeWorkspace<ScriptDefinition, WorkspaceScriptDefinition>;
@@ -203,6 +230,9 @@ pub struct TomlPackage {
     pub repository: Option<MaybeWorkspaceField<String>>,
     pub include: Option<Vec<Utf8PathBuf>>,
     /// **UNSTABLE** This package does not depend on Cairo's `core`.
+    ///
+    /// Can also be spelled as `no-std`.
+    #[serde(alias = "no-std")]
     pub no_core: Option<bool>,
     pub cairo_version: Option<MaybeWorkspaceField<VersionReq>>,
     pub experimental_features: Option<Vec<SmolStr>>,
@@ -344,6 +374,10 @@ mod serdex {
                             detailed.detailed.rev.is_none(),
                             "field `rev` is not allowed when inheriting workspace dependency"
                         );
+                        ensure!(
+                            detailed.detailed.track.is_none(),
+                            "field `track` is not allowed when inheriting workspace dependency"
+                        );
                         ensure!(
                             detailed.detailed.registry.is_none(),
                             "field `registry` is not allowed when inheriting workspace dependency"
@@ -368,7 +402,7 @@ mod serdex {
 }
 
 /// When { workspace = true } you cannot define other keys that configure the source of
-/// the dependency such as `version`, `registry`, `path`, `git`, `branch`, `tag`, `rev`.
+/// the dependency such as `version`, `registry`, `path`, `git`, `branch`, `tag`, `rev`, `track`.
 /// You can also not define `default-features`.
 /// Only `features` is allowed.
 #[derive(Debug, Default, Clone, Serialize)]
@@ -408,6 +442,8 @@ pub struct DetailedTomlDependency {
     pub branch: Option<String>,
     pub tag: Option<String>,
     pub rev: Option<String>,
+    /// Whether `scarb update` should move a `branch` dependency to the latest commit.
+    pub track: Option<bool>,
 
     pub registry: Option<Url>,
 
@@ -482,6 +518,10 @@ pub struct TomlCairo {
     /// Used by [cairo-coverage](https://github.com/software-mansion/cairo-coverage).
     /// This feature is unstable and is subject to change.
     pub unstable_add_statements_code_locations_debug_info: Option<bool>,
+    /// Shorthand for enabling both `unstable-add-statements-functions-debug-info` and
+    /// `unstable-add-statements-code-locations-debug-info`.
+    /// The individual flags take precedence over this one, if specified.
+    pub debug: Option<bool>,
     /// Whether to add panic backtrace handling to the generated code.
     pub panic_backtrace: Option<bool>,
     /// Do not generate panic handling code. This might be useful for client side proving.
@@ -498,6 +538,8 @@ pub struct TomlProfile {
     pub inherits: Option<SmolStr>,
     pub cairo: Option<TomlCairo>,
     pub tool: Option<TomlToolsDefinition>,
+    /// Whether to enable incremental compilation, taking precedence over `cairo.incremental`.
+    pub incremental: Option<bool>,
 }
 
 impl DefaultForProfile for TomlProfile {
@@ -513,6 +555,7 @@ impl DefaultForProfile for TomlProfile {
 #[serde(rename_all = "kebab-case")]
 pub struct TomlToolScarbMetadata {
     pub allow_prebuilt_plugins: Option<Vec<String>>,
+    pub allow_duplicate_package_names: Option<bool>,
 }
 
 const DEPENDENCY_FEATURE_SEPARATOR: &str = "/";
@@ -617,6 +660,7 @@ impl TomlManifest {
         source_id: SourceId,
         profile: Profile,
         workspace_manifest: Option<&TomlManifest>,
+        is_dependency: bool,
         config: &Config,
     ) -> Result<Manifest> {
         let root = manifest_path
@@ -707,6 +751,11 @@ impl TomlManifest {
                 .clone()
                 .map(|dep| dep.to_dependency(name.clone(), manifest_path, kind.clone()))?
                 .resolve(name.as_str(), inherit_ws)?;
+            // Manifests of dependencies are not written by the user, and may be located
+            // anywhere, like in the global cache.
+            if let Some(roots) = config.allowed_path_dep_roots().filter(|_| !is_dependency) {
+                ensure_path_dep_allowed(&toml_dep, roots)?;
+            }
             dependencies.push(toml_dep);
         }
 
@@ -721,7 +770,14 @@ impl TomlManifest {
 
         let no_core = package.no_core.unwrap_or(false);
 
-        let targets = self.collect_targets(package.name.to_smol_str(), root)?;
+        let mut targets = self.collect_targets(package.name.to_smol_str(), root)?;
+        // Dependencies must not break consumers which do not set variables they reference.
+        if !is_dependency {
+            targets = targets
+                .into_iter()
+                .map(interpolate_external_contracts)
+                .try_collect()?;
+        }
 
         let publish = package.publish.unwrap_or(true);
 
@@ -1150,6 +1206,10 @@ impl TomlManifest {
             if let Some(enable_gas) = cairo.enable_gas {
                 compiler_config.enable_gas = enable_gas;
             }
+            if let Some(debug) = cairo.debug {
+                compiler_config.unstable_add_statements_functions_debug_info = debug;
+                compiler_config.unstable_add_statements_code_locations_debug_info = debug;
+            }
             if let Some(unstable_add_statements_functions_debug_info) =
                 cairo.unstable_add_statements_functions_debug_info
             {
@@ -1172,6 +1232,9 @@ impl TomlManifest {
                 compiler_config.incremental = incremental;
             }
         }
+        if let Some(incremental) = profile_definition.incremental {
+            compiler_config.incremental = incremental;
+        }
         Ok(compiler_config)
     }
 
@@ -1209,6 +1272,103 @@ impl TomlManifest {
             .transpose()
     }
 
+    /// Returns the default registry index URL configured in the `[registries.default]` section.
+    pub fn collect_default_registry_index(&self) -> Result<Option<Url>> {
+        let Some(registries) = self.registries.as_ref() else {
+            return Ok(None);
+        };
+        if let Some(name) = registries
+            .keys()
+            .find(|name| name.as_str() != DEFAULT_REGISTRY_NAME)
+        {
+            bail!(
+                "unsupported registry `{name}` in the `[registries]` section, \
+                only the `{DEFAULT_REGISTRY_NAME}` registry can be configured"
+            );
+        }
+        Ok(registries
+            .get(DEFAULT_REGISTRY_NAME)
+            .and_then(|registry| registry.index.clone()))
+    }
+
+    /// Returns the mirrors of the default registry configured in the `[registries.default]`
+    /// section, keyed by the URL of the default registry.
+    pub fn collect_registry_mirrors(&self) -> Result<BTreeMap<CanonicalUrl, RegistryMirrors>> {
+        let Some(registry) = self
+            .registries
+            .as_ref()
+            .and_then(|registries| registries.get(DEFAULT_REGISTRY_NAME))
+        else {
+            return Ok(BTreeMap::new());
+        };
+        let Some(mirrors) = registry.mirrors.as_ref() else {
+            ensure!(
+                registry.pure_mirrors.is_none(),
+                "`pure-mirrors` cannot be set without `mirrors` in the `[registries.{DEFAULT_REGISTRY_NAME}]` section"
+            );
+            return Ok(BTreeMap::new());
+        };
+        let mirrors = RegistryMirrors {
+            mirrors: mirrors
+                .iter()
+                .map(SourceId::for_registry)
+                .collect::<Result<_>>()?,
+            pure: registry.pure_mirrors.unwrap_or_default(),
+        };
+        Ok(BTreeMap::from([(
+            SourceId::default_registry().canonical_url.clone(),
+            mirrors,
+        )]))
+    }
+
+    /// Returns the mapping of replaced sources to directories with vendored packages,
+    /// as configured in the `[source]` section.
+    pub fn collect_source_replacements(
+        &self,
+        manifest_path: &Utf8Path,
+    ) -> Result<BTreeMap<CanonicalUrl, Utf8PathBuf>> {
+        let Some(sources) = self.source.as_ref() else {
+            return Ok(BTreeMap::new());
+        };
+        sources
+            .iter()
+            .filter_map(|(name, source)| source.replace_with.as_ref().map(|r| (name, source, r)))
+            .map(|(name, source, replace_with)| {
+                ensure!(
+                    source.directory.is_none(),
+                    "source `{name}` cannot define both `replace-with` and `directory`"
+                );
+                let url = match (name.as_str(), &source.git, &source.registry) {
+                    (DEFAULT_REGISTRY_NAME, None, None) => {
+                        SourceId::default_registry().canonical_url.clone()
+                    }
+                    (_, Some(url), None) | (_, None, Some(url)) => CanonicalUrl::new(url)?,
+                    (_, None, None) => bail!(
+                        "source `{name}` must specify either `git` or `registry` to be replaced"
+                    ),
+                    (_, Some(_), Some(_)) => {
+                        bail!("source `{name}` cannot specify both `git` and `registry`")
+                    }
+                };
+                let replacement = sources.get(replace_with).ok_or_else(|| {
+                    anyhow!(
+                        "source `{name}` is replaced with `{replace_with}`, which is not defined"
+                    )
+                })?;
+                let directory = replacement.directory.as_ref().ok_or_else(|| {
+                    anyhow!(
+                        "source `{replace_with}` used as a replacement for `{name}` \
+                        must define a `directory`"
+                    )
+                })?;
+                let directory = directory.relative_to_file(manifest_path).with_context(|| {
+                    format!("failed to find directory of source `{replace_with}`")
+                })?;
+                Ok((url, directory))
+            })
+            .collect()
+    }
+
     pub fn collect_patch(
         &self,
         manifest_path: &Utf8Path,
@@ -1251,6 +1411,71 @@ impl TomlManifest {
     }
 }
 
+/// Ensures that a path dependency is located within one of the allowed `roots`.
+fn ensure_path_dep_allowed(dep: &ManifestDependency, roots: &[Utf8PathBuf]) -> Result<()> {
+    let Some(manifest_path) = dep.source_id.to_path() else {
+        return Ok(());
+    };
+    let path = manifest_path.parent().unwrap_or(&manifest_path);
+    ensure!(
+        roots.iter().any(|root| path.starts_with(root)),
+        "path dependency `{}` at `{path}` is outside of allowed path dependency roots: {}",
+        dep.name,
+        roots.iter().map(|root| format!("`{root}`")).join(", ")
+    );
+    Ok(())
+}
+
+/// Expands `${VAR}` references to environment variables in `build-external-contracts` selectors
+/// of the `target`.
+///
+/// Interpolation is limited to selectors, so that they can vary between CI builds, without turning
+/// the whole manifest into a template.
+fn interpolate_external_contracts(target: Target) -> Result<Target> {
+    let mut params = target.params.clone();
+    let Some(selectors) = params
+        .get_mut("build-external-contracts")
+        .and_then(toml::Value::as_array_mut)
+    else {
+        return Ok(target);
+    };
+    for selector in selectors {
+        if let toml::Value::String(selector) = selector {
+            *selector = interpolate_env(selector)?;
+        }
+    }
+    Ok(Target::new(
+        target.kind.clone(),
+        target.name.clone(),
+        target.source_path.clone(),
+        target.group_id.clone(),
+        params,
+    ))
+}
+
+fn interpolate_env(selector: &str) -> Result<String> {
+    let mut result = String::with_capacity(selector.len());
+    let mut rest = selector;
+    while let Some(start) = rest.find("${") {
+        result.push_str(&rest[..start]);
+        let Some(end) = rest[start..].find('}') else {
+            bail!("unterminated environment variable reference in selector `{selector}`");
+        };
+        let name = &rest[start + 2..start + end];
+        ensure!(
+            !name.is_empty(),
+            "empty environment variable reference in selector `{selector}`"
+        );
+        let value = std::env::var(name).map_err(|_| {
+            anyhow!("environment variable `{name}` referenced in selector `{selector}` is not set")
+        })?;
+        result.push_str(&value);
+        rest = &rest[start + end + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
 fn merge_profile(target: &TomlProfile, source: &TomlProfile) -> Result<TomlProfile> {
     let inherits = source.inherits.clone().or(target.inherits.clone());
     let cairo = if let (Some(target), Some(source)) = (&target.cairo, &source.cairo) {
@@ -1263,10 +1488,12 @@ fn merge_profile(target: &TomlProfile, source: &TomlProfile) -> Result<TomlProfi
     } else {
         source.tool.clone().or(target.tool.clone())
     };
+    let incremental = source.incremental.or(target.incremental);
     Ok(TomlProfile {
         inherits,
         cairo,
         tool,
+        incremental,
     })
 }
 
@@ -1351,6 +1578,10 @@ impl DetailedTomlDependency {
                 only one of `branch`, `tag` or `rev` is allowed"
             );
         }
+        ensure!(
+            self.track.is_none() || self.branch.is_some(),
+            "dependency ({name}) provides `track`, which is only allowed along with `branch`"
+        );
         let source_id = match (
             self.version.as_ref(),
             self.git.as_ref(),
@@ -1403,10 +1634,12 @@ impl DetailedTomlDependency {
             .map(FeatureName::try_new)
             .collect::<Result<Vec<_>>>()?;
         let default_features = self.default_features.unwrap_or(true);
+        let track = self.track.unwrap_or(true);
 
         Ok(ManifestDependency::builder()
             .name(name)
             .source_id(source_id)
+            .track(track)
             .version_req(version_req)
             .kind(dep_kind)
             .features(features)
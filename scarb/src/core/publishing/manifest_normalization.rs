@@ -74,6 +74,8 @@ pub fn prepare_manifest_for_publish(pkg: &Package) -> Result<TomlManifest> {
         tool,
         features,
         patch: None,
+        registries: None,
+        source: None,
     })
 }
 
@@ -177,6 +179,7 @@ fn generate_dependency(dep: &ManifestDependency) -> Result<TomlDependency> {
         branch: None,
         tag: None,
         rev: None,
+        track: None,
 
         // Unless it is the default registry, expand the registry specification to registry URL.
         //
@@ -12,6 +12,7 @@ use scarb_ui::Ui;
 
 #[allow(unused_imports)]
 use crate::core::PackageName;
+use crate::core::errors::PackageNotFoundError;
 use crate::core::registry::client::{
     CreateScratchFileCallback, RegistryClient, RegistryDownload, RegistryResource,
 };
@@ -117,7 +118,10 @@ impl<'c> RegistryClientCache<'c> {
         {
             RegistryResource::NotFound => {
                 db.prune_records(package_name).await?;
-                bail!("package not found in registry: {dependency}")
+                Err(PackageNotFoundError {
+                    package: dependency.to_string(),
+                }
+                .into())
             }
 
             RegistryResource::InCache => db.get_records(dependency).await,
@@ -143,12 +147,23 @@ impl<'c> RegistryClientCache<'c> {
     ) -> Result<(FileLockGuard, Checksum)> {
         // Skip downloading if the package already has been.
         if self.is_package_downloaded(package).await {
-            trace!("found cached archive which is not empty, skipping download");
+            trace!("found cached archive which is not empty, verifying checksum");
             let tarball_name = package.tarball_name();
             let file = self
                 .dl_fs
                 .open_ro(&tarball_name, &tarball_name, self.config)?;
             let checksum = self.get_record_maybe_uncached(package).await?.checksum;
+            let file = self
+                .verify_checksum(package, &checksum, file)
+                .await
+                .with_context(|| {
+                    format!(
+                        "cached archive is corrupted: {}\n\
+                        help: run the following to wipe entire cache: scarb cache clean",
+                        self.dl_fs.path_unchecked().join(&tarball_name)
+                    )
+                })?;
+            trace!("cached package archive file has valid checksum: {checksum}");
             return Ok((file, checksum));
         }
 
@@ -194,7 +209,7 @@ impl<'c> RegistryClientCache<'c> {
     }
 
     /// Check whether the tarball has already been downloaded in the _dl_ directory.
-    /// If user modifies downloaded archive, it's their problem.
+    /// The archive contents are not verified here, this is done by the caller.
     /// In case of IO errors, we treat the file as non-existent, and will attempt re-downloading.
     async fn is_package_downloaded(&self, package: PackageId) -> bool {
         let fs = &self.dl_fs;
@@ -1,7 +1,7 @@
 use anyhow::Result;
 use async_trait::async_trait;
 
-use crate::core::{ManifestDependency, Package, PackageId, Summary};
+use crate::core::{ManifestDependency, Package, PackageId, SourceId, Summary};
 
 pub mod cache;
 pub mod client;
@@ -13,6 +13,17 @@ pub mod source_map;
 
 pub const DEFAULT_REGISTRY_INDEX: &str = "https://scarbs.xyz/";
 pub const DEFAULT_REGISTRY_INDEX_PATCH_SOURCE: &str = "scarbs-xyz";
+pub const DEFAULT_REGISTRY_INDEX_ENV: &str = "SCARB_DEFAULT_REGISTRY_INDEX";
+pub const DEFAULT_REGISTRY_NAME: &str = "default";
+
+/// Registries consulted, in order, for packages which are not found in a registry.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct RegistryMirrors {
+    pub mirrors: Vec<SourceId>,
+    /// Whether mirrors only replicate the registry, in which case packages found in a mirror
+    /// keep the identity of the mirrored registry.
+    pub pure: bool,
+}
 
 #[async_trait(?Send)]
 pub trait Registry {
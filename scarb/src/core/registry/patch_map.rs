@@ -1,5 +1,7 @@
-use crate::core::{ManifestDependency, PackageName, SourceId};
+use crate::core::{ManifestDependency, PackageName, Resolve, SourceId};
 use crate::sources::canonical_url::CanonicalUrl;
+use anyhow::{Result, ensure};
+use itertools::Itertools;
 use scarb_ui::Ui;
 use std::cell::RefCell;
 use std::collections::{HashMap, HashSet};
@@ -32,6 +34,13 @@ impl PatchMap {
         result
     }
 
+    /// Find the patch replacing `dependency`, without marking it as used.
+    pub fn get(&self, dependency: &ManifestDependency) -> Option<&ManifestDependency> {
+        self.map
+            .get(&dependency.source_id.canonical_url)
+            .and_then(|patches| patches.get(&dependency.name))
+    }
+
     pub fn insert(
         &mut self,
         source_pattern: CanonicalUrl,
@@ -59,4 +68,34 @@ impl PatchMap {
             }
         }
     }
+
+    /// Ensure that packages resolved for patched dependencies match version requirements of
+    /// the original dependencies they replace.
+    pub fn ensure_compatible_versions(&self, resolve: &Resolve) -> Result<()> {
+        for (package_id, summary) in resolve.summaries.iter().sorted_by_key(|(id, _)| **id) {
+            for dependency in summary.full_dependencies() {
+                if self.get(dependency).is_none() {
+                    continue;
+                }
+                let Some(patched) = resolve
+                    .package_dependencies(*package_id)
+                    .find(|id| id.name == dependency.name)
+                else {
+                    continue;
+                };
+                ensure!(
+                    dependency.version_req.matches(&patched.version),
+                    "patch `{patched}` is not compatible with dependency `{name}` of package \
+                    `{package_id}`\n\
+                    version `{version}` does not match requirement `{req}` of the original \
+                    dependency from `{source_id}`",
+                    name = dependency.name,
+                    version = patched.version,
+                    req = dependency.version_req,
+                    source_id = dependency.source_id,
+                );
+            }
+        }
+        Ok(())
+    }
 }
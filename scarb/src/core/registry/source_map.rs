@@ -1,24 +1,31 @@
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 use std::sync::Arc;
 
 use anyhow::{Context, Result};
 use async_trait::async_trait;
+use camino::Utf8PathBuf;
 use itertools::Itertools;
 use tokio::sync::RwLock;
 use tracing::trace;
+use url::Url;
 
 #[cfg(doc)]
 use crate::core::Workspace;
-use crate::core::registry::Registry;
-use crate::core::source::Source;
+use crate::core::registry::{Registry, RegistryMirrors};
+use crate::core::source::{Source, SourceCache};
 use crate::core::{Config, ManifestDependency, Package, PackageId, SourceId, Summary};
 use crate::sources::PathSource;
+use crate::sources::canonical_url::CanonicalUrl;
 
 /// Source of information about a group of packages.
 pub struct SourceMap<'c> {
     config: &'c Config,
     sources: RwLock<HashMap<SourceId, Arc<dyn Source + 'c>>>,
     yanked_whitelist: HashSet<PackageId>,
+    source_replacements: BTreeMap<CanonicalUrl, Utf8PathBuf>,
+    registry_mirrors: BTreeMap<CanonicalUrl, RegistryMirrors>,
+    default_registry_index: Option<Url>,
+    source_cache: SourceCache<'c>,
 }
 
 impl<'c> SourceMap<'c> {
@@ -47,6 +54,48 @@ impl<'c> SourceMap<'c> {
             config,
             sources,
             yanked_whitelist,
+            source_replacements: BTreeMap::new(),
+            registry_mirrors: BTreeMap::new(),
+            default_registry_index: None,
+            source_cache: SourceCache::default(),
+        }
+    }
+
+    /// Redirect sources with matching URLs to directories with vendored packages.
+    pub fn with_source_replacements(
+        self,
+        source_replacements: BTreeMap<CanonicalUrl, Utf8PathBuf>,
+    ) -> Self {
+        Self {
+            source_replacements,
+            ..self
+        }
+    }
+
+    /// Consult mirrors for packages missing in registries with matching URLs.
+    pub fn with_registry_mirrors(
+        self,
+        registry_mirrors: BTreeMap<CanonicalUrl, RegistryMirrors>,
+    ) -> Self {
+        Self {
+            registry_mirrors,
+            ..self
+        }
+    }
+
+    /// Read packages of the default registry from `default_registry_index`, if given.
+    pub fn with_default_registry_index(self, default_registry_index: Option<Url>) -> Self {
+        Self {
+            default_registry_index,
+            ..self
+        }
+    }
+
+    /// Reuse sources already loaded by other registries sharing the `source_cache`.
+    pub fn with_source_cache(self, source_cache: SourceCache<'c>) -> Self {
+        Self {
+            source_cache,
+            ..self
         }
     }
 
@@ -56,8 +105,16 @@ impl<'c> SourceMap<'c> {
             Ok(source)
         } else {
             trace!("loading source: {source_id}");
-            let source = source_id
-                .load(self.config, &self.yanked_whitelist)
+            let source: Arc<dyn Source + 'c> = self
+                .source_cache
+                .load(
+                    source_id,
+                    self.config,
+                    &self.yanked_whitelist,
+                    &self.source_replacements,
+                    &self.registry_mirrors,
+                    self.default_registry_index.as_ref(),
+                )
                 .with_context(|| format!("failed to load source: {source_id}"))?;
             self.sources.write().await.insert(source_id, source.clone());
             Ok(source)
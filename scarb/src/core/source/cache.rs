@@ -0,0 +1,120 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+
+use anyhow::Result;
+use camino::Utf8PathBuf;
+use url::Url;
+
+use crate::core::registry::RegistryMirrors;
+use crate::core::source::{Source, SourceId};
+use crate::core::{Config, PackageId};
+use crate::sources::canonical_url::CanonicalUrl;
+
+/// Sources loaded during a single Scarb run, shared by all registries built with a [`Config`].
+///
+/// Loading git and registry sources involves setup work, like opening repositories or reading
+/// indices, which this cache allows to perform only once per [`SourceId`].
+/// The cache is shared between threads, so only sources which are [`Send`] and [`Sync`] are kept.
+#[derive(Clone, Default)]
+pub struct SourceCache<'c> {
+    sources: Arc<Mutex<HashMap<SourceId, CachedSource<'c>>>>,
+}
+
+struct CachedSource<'c> {
+    source: Arc<dyn Source + Send + Sync + 'c>,
+    yanked_whitelist: HashSet<PackageId>,
+}
+
+impl<'c> SourceCache<'c> {
+    /// Like [`SourceId::load`], but reuses the source loaded previously for the same ID,
+    /// unless it has been loaded with a different `yanked_whitelist`.
+    pub fn load(
+        &self,
+        source_id: SourceId,
+        config: &'c Config,
+        yanked_whitelist: &HashSet<PackageId>,
+        source_replacements: &BTreeMap<CanonicalUrl, Utf8PathBuf>,
+        registry_mirrors: &BTreeMap<CanonicalUrl, RegistryMirrors>,
+        default_registry_index: Option<&Url>,
+    ) -> Result<Arc<dyn Source + Send + Sync + 'c>> {
+        let mut sources = self.sources.lock().unwrap();
+        if let Some(cached) = sources.get(&source_id) {
+            if cached.yanked_whitelist == *yanked_whitelist {
+                return Ok(cached.source.clone());
+            }
+        }
+
+        let source = source_id.load(
+            config,
+            yanked_whitelist,
+            source_replacements,
+            registry_mirrors,
+            default_registry_index,
+        )?;
+        sources.insert(
+            source_id,
+            CachedSource {
+                source: source.clone(),
+                yanked_whitelist: yanked_whitelist.clone(),
+            },
+        );
+        Ok(source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{BTreeMap, HashSet};
+    use std::sync::Arc;
+
+    use assert_fs::TempDir;
+    use assert_fs::prelude::*;
+    use camino::Utf8Path;
+
+    use scarb_test_support::project_builder::ProjectBuilder;
+
+    use super::SourceCache;
+    use crate::core::{Config, PackageId, SourceId};
+
+    #[test]
+    fn reuses_loaded_sources() {
+        let t = TempDir::new().unwrap();
+        ProjectBuilder::start().name("hello").build(&t);
+        let cache_dir = TempDir::new().unwrap();
+        let config_dir = TempDir::new().unwrap();
+
+        let manifest_path = Utf8Path::from_path(t.child("Scarb.toml").path())
+            .unwrap()
+            .to_path_buf();
+        let config = Config::builder(manifest_path)
+            .global_cache_dir_override(Some(Utf8Path::from_path(&cache_dir).unwrap().to_path_buf()))
+            .global_config_dir_override(Some(
+                Utf8Path::from_path(&config_dir).unwrap().to_path_buf(),
+            ))
+            .build()
+            .unwrap();
+
+        let cache = SourceCache::default();
+        let source_id = SourceId::mock_git();
+        let load = |yanked_whitelist: &HashSet<PackageId>| {
+            cache
+                .load(
+                    source_id,
+                    &config,
+                    yanked_whitelist,
+                    &BTreeMap::new(),
+                    &BTreeMap::new(),
+                    None,
+                )
+                .unwrap()
+        };
+
+        let first = load(&HashSet::new());
+        let second = load(&HashSet::new());
+        assert!(Arc::ptr_eq(&first, &second));
+
+        let yanked_whitelist = HashSet::from([PackageId::from_display_str("foo v1.0.0").unwrap()]);
+        let third = load(&yanked_whitelist);
+        assert!(!Arc::ptr_eq(&first, &third));
+    }
+}
@@ -1,18 +1,20 @@
-use std::collections::HashSet;
+use std::collections::{BTreeMap, HashSet};
 use std::fmt;
 use std::hash::{Hash, Hasher};
 use std::ops::Deref;
 use std::sync::{Arc, LazyLock};
 
-use anyhow::{Context, Result, anyhow, bail};
+use anyhow::{Context, Result, anyhow, bail, ensure};
 use camino::{Utf8Path, Utf8PathBuf};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use smol_str::SmolStr;
 use url::Url;
 
-use crate::core::registry::DEFAULT_REGISTRY_INDEX;
+use crate::core::errors::SourceIdParseError;
+use crate::core::registry::{DEFAULT_REGISTRY_INDEX, RegistryMirrors};
 use crate::core::source::Source;
 use crate::core::{Config, PackageId};
+use crate::internal::fsx;
 use crate::internal::fsx::PathBufUtf8Ext;
 use crate::internal::static_hash_cache::StaticHashCache;
 use crate::sources::canonical_url::CanonicalUrl;
@@ -120,6 +122,11 @@ const GIT_SOURCE_PROTOCOL: &str = "git";
 const REGISTRY_SOURCE_PROTOCOL: &str = "registry";
 const STD_SOURCE_PROTOCOL: &str = "std";
 
+const PATH_SOURCE_TAG: u8 = 0;
+const GIT_SOURCE_TAG: u8 = 1;
+const REGISTRY_SOURCE_TAG: u8 = 2;
+const STD_SOURCE_TAG: u8 = 3;
+
 #[derive(Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash)]
 pub struct GitSourceSpec {
     pub reference: GitReference,
@@ -166,7 +173,19 @@ impl SourceId {
     }
 
     /// Creates a new `SourceId` from this source with the given `precise`.
+    ///
+    /// The `precise` revision must be a full, 40 characters long hexadecimal Git commit hash.
     pub fn with_precise(self, v: String) -> Result<SourceId> {
+        ensure!(
+            v.len() == 40 && v.chars().all(|c| c.is_ascii_hexdigit()),
+            "invalid precise revision `{v}` for source: {self}\n\
+            note: expected a full, 40 characters long hexadecimal Git commit hash"
+        );
+        self.with_precise_unchecked(v)
+    }
+
+    /// Like [`SourceId::with_precise`], but does not validate the format of `precise`.
+    fn with_precise_unchecked(self, v: String) -> Result<SourceId> {
         let kind = self
             .kind
             .as_git_source_spec()
@@ -191,6 +210,18 @@ impl SourceId {
         can_lock && self.equals_ignoring_kind(other)
     }
 
+    /// Checks whether both source IDs point to the same repository, registry or path.
+    ///
+    /// Unlike equality, this ignores the Git reference and the `precise` revision, so it can be
+    /// used to group sources for display purposes, regardless of which revision each one uses.
+    pub fn same_repository(self, other: Self) -> bool {
+        let same_kind = match (&self.kind, &other.kind) {
+            (SourceKind::Git(_), SourceKind::Git(_)) => true,
+            (kind, other_kind) => kind == other_kind,
+        };
+        same_kind && self.canonical_url == other.canonical_url
+    }
+
     fn equals_ignoring_kind(self, other: Self) -> bool {
         let first = SourceIdInner {
             kind: SourceKind::Std,
@@ -208,7 +239,15 @@ impl SourceId {
         Self(CACHE.intern(inner))
     }
 
+    /// Creates a source ID for packages located at `path`, which must be absolute.
+    ///
+    /// Symlinks in `path` are resolved here, so that the same on-disk location always interns
+    /// to a single [`SourceId`], regardless of which path it has been reached through.
+    /// Paths that do not exist (yet) are used verbatim.
     pub fn for_path(path: &Utf8Path) -> Result<Self> {
+        ensure!(path.is_absolute(), "path ({}) is not absolute", path);
+        let path = fsx::canonicalize_utf8(path).unwrap_or_else(|_| path.to_path_buf());
+        let path = path.as_path();
         let url = if path.is_dir() {
             Url::from_directory_path(path)
         } else {
@@ -325,7 +364,10 @@ impl SourceId {
         }
     }
 
-    pub fn from_pretty_url(pretty_url: &str) -> Result<Self> {
+    /// Parses a source ID from its [pretty URL][Self::to_pretty_url].
+    ///
+    /// Errors are typed, so that callers can tell apart the reasons of parsing failures.
+    pub fn from_pretty_url(pretty_url: &str) -> Result<Self, SourceIdParseError> {
         if pretty_url == STD_SOURCE_PROTOCOL {
             return Ok(Self::for_std());
         }
@@ -334,14 +376,17 @@ impl SourceId {
             let mut parts = pretty_url.splitn(2, '+');
             (
                 parts.next().expect("at least one part must be here"),
-                parts
-                    .next()
-                    .ok_or_else(|| anyhow!("invalid source: {pretty_url}"))?,
+                parts.next().ok_or_else(|| SourceIdParseError::MissingUrl {
+                    pretty_url: pretty_url.to_string(),
+                })?,
             )
         };
 
         let parse_url = |value: &str| {
-            Url::parse(value).with_context(|| format!("cannot parse source URL: {pretty_url}"))
+            Url::parse(value).map_err(|source| SourceIdParseError::BadUrl {
+                pretty_url: pretty_url.to_string(),
+                source,
+            })
         };
 
         let url = || parse_url(url_part);
@@ -350,7 +395,7 @@ impl SourceId {
             GIT_SOURCE_PROTOCOL => {
                 let (mut url, precise) = url_part
                     .rsplit_once('#')
-                    .map(|(url, precise)| -> Result<(_, _)> {
+                    .map(|(url, precise)| -> Result<(_, _), SourceIdParseError> {
                         Ok((parse_url(url)?, Some(precise.to_string())))
                     })
                     .unwrap_or_else(|| Ok((url()?, None)))?;
@@ -367,38 +412,192 @@ impl SourceId {
 
                 url.set_query(None);
 
-                let sid = SourceId::for_git(&url, &reference)?;
-                precise.map(|p| sid.with_precise(p)).unwrap_or(Ok(sid))
+                let sid = SourceId::for_git(&url, &reference)
+                    .map_err(SourceIdParseError::InvalidSource)?;
+                // Precise revisions only appear in pretty URLs written to lockfiles by Scarb,
+                // so a malformed one means that the lockfile has to be regenerated.
+                precise
+                    .map(|p| sid.with_precise(p))
+                    .unwrap_or(Ok(sid))
+                    .map_err(|err| {
+                        SourceIdParseError::InvalidSource(anyhow!(
+                            "{err}\n\
+                            help: remove `Scarb.lock` and run Scarb again to regenerate the lockfile"
+                        ))
+                    })
             }
 
-            PATH_SOURCE_PROTOCOL => SourceId::new(url()?, SourceKind::Path),
+            PATH_SOURCE_PROTOCOL => {
+                SourceId::new(url()?, SourceKind::Path).map_err(SourceIdParseError::InvalidSource)
+            }
 
-            REGISTRY_SOURCE_PROTOCOL => SourceId::for_registry(&(url()?)),
+            REGISTRY_SOURCE_PROTOCOL => {
+                SourceId::for_registry(&(url()?)).map_err(SourceIdParseError::InvalidSource)
+            }
 
-            kind => bail!("unsupported source protocol: {kind}"),
+            kind => Err(SourceIdParseError::UnknownProtocol {
+                protocol: kind.to_string(),
+            }),
         }
     }
 
+    /// Encodes this source ID in a compact binary form, suitable for caches where parsing
+    /// [pretty URLs][Self::to_pretty_url] would be too costly.
+    ///
+    /// The encoding consists of a source kind tag, the source URL and, for Git sources,
+    /// the Git reference and the optional precise revision.
+    /// The pretty URL stays the canonical, serde representation of source IDs.
+    pub fn to_bytes(self) -> Vec<u8> {
+        fn put_str(bytes: &mut Vec<u8>, value: &str) {
+            bytes.extend((value.len() as u32).to_le_bytes());
+            bytes.extend(value.as_bytes());
+        }
+
+        let mut bytes = Vec::new();
+        match &self.kind {
+            SourceKind::Path => {
+                bytes.push(PATH_SOURCE_TAG);
+                put_str(&mut bytes, self.url.as_str());
+            }
+            SourceKind::Git(GitSourceSpec { reference, precise }) => {
+                bytes.push(GIT_SOURCE_TAG);
+                put_str(&mut bytes, self.url.as_str());
+                match reference {
+                    GitReference::DefaultBranch => bytes.push(0),
+                    GitReference::Tag(tag) => {
+                        bytes.push(1);
+                        put_str(&mut bytes, tag);
+                    }
+                    GitReference::Branch(branch) => {
+                        bytes.push(2);
+                        put_str(&mut bytes, branch);
+                    }
+                    GitReference::Rev(rev) => {
+                        bytes.push(3);
+                        put_str(&mut bytes, rev);
+                    }
+                }
+                match precise {
+                    None => bytes.push(0),
+                    Some(precise) => {
+                        bytes.push(1);
+                        put_str(&mut bytes, precise);
+                    }
+                }
+            }
+            SourceKind::Registry => {
+                bytes.push(REGISTRY_SOURCE_TAG);
+                put_str(&mut bytes, self.url.as_str());
+            }
+            SourceKind::Std => bytes.push(STD_SOURCE_TAG),
+        }
+        bytes
+    }
+
+    /// Decodes a source ID from the binary form produced by [`SourceId::to_bytes`].
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        struct Reader<'a>(&'a [u8]);
+
+        impl<'a> Reader<'a> {
+            fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+                ensure!(self.0.len() >= len, "unexpected end of binary source ID");
+                let (head, tail) = self.0.split_at(len);
+                self.0 = tail;
+                Ok(head)
+            }
+
+            fn tag(&mut self) -> Result<u8> {
+                Ok(self.take(1)?[0])
+            }
+
+            fn str(&mut self) -> Result<&'a str> {
+                let len = u32::from_le_bytes(self.take(4)?.try_into().unwrap());
+                Ok(std::str::from_utf8(self.take(len as usize)?)?)
+            }
+
+            fn url(&mut self) -> Result<Url> {
+                let url = self.str()?;
+                Url::parse(url).with_context(|| format!("cannot parse source URL: {url}"))
+            }
+        }
+
+        let mut reader = Reader(bytes);
+        let source_id = match reader.tag()? {
+            PATH_SOURCE_TAG => SourceId::new(reader.url()?, SourceKind::Path)?,
+            GIT_SOURCE_TAG => {
+                let url = reader.url()?;
+                let reference = match reader.tag()? {
+                    0 => GitReference::DefaultBranch,
+                    1 => GitReference::Tag(reader.str()?.into()),
+                    2 => GitReference::Branch(reader.str()?.into()),
+                    3 => GitReference::Rev(reader.str()?.into()),
+                    tag => bail!("invalid git reference tag in binary source ID: {tag}"),
+                };
+                let source_id = SourceId::for_git(&url, &reference)?;
+                match reader.tag()? {
+                    0 => source_id,
+                    1 => source_id.with_precise_unchecked(reader.str()?.to_string())?,
+                    tag => bail!("invalid precise revision tag in binary source ID: {tag}"),
+                }
+            }
+            REGISTRY_SOURCE_TAG => SourceId::for_registry(&reader.url()?)?,
+            STD_SOURCE_TAG => SourceId::for_std(),
+            tag => bail!("invalid source kind tag in binary source ID: {tag}"),
+        };
+        ensure!(reader.0.is_empty(), "trailing bytes in binary source ID");
+        Ok(source_id)
+    }
+
     #[cfg(test)]
     pub(crate) fn from_display_str(string: &str) -> Result<Self> {
-        Self::for_path(&Utf8PathBuf::from(string)).or_else(|_| Self::from_pretty_url(string))
+        Self::for_path(&Utf8PathBuf::from(string))
+            .or_else(|_| Self::from_pretty_url(string).map_err(Into::into))
     }
 
     /// Creates an implementation of `Source` corresponding to this ID.
+    ///
+    /// Git and registry sources listed in `source_replacements` are loaded from a directory with
+    /// vendored packages instead, while preserving this ID as the identity of loaded packages.
+    /// Registries listed in `registry_mirrors` consult their mirrors for packages they do not have.
+    /// The default registry is read from `default_registry_index` instead, if one is given.
     pub fn load<'c>(
         self,
         config: &'c Config,
         yanked_whitelist: &HashSet<PackageId>,
-    ) -> Result<Arc<dyn Source + 'c>> {
+        source_replacements: &BTreeMap<CanonicalUrl, Utf8PathBuf>,
+        registry_mirrors: &BTreeMap<CanonicalUrl, RegistryMirrors>,
+        default_registry_index: Option<&Url>,
+    ) -> Result<Arc<dyn Source + Send + Sync + 'c>> {
         use crate::sources::*;
+        if self.is_git() || self.is_registry() {
+            if let Some(directory) = source_replacements.get(&self.canonical_url) {
+                return Ok(Arc::new(VendoredSource::new(directory, self, config)));
+            }
+        }
         match self.kind {
             SourceKind::Path => Ok(Arc::new(PathSource::new(self, config))),
             SourceKind::Git(_) => Ok(Arc::new(GitSource::new(self, config)?)),
-            SourceKind::Registry => Ok(Arc::new(RegistrySource::new(
-                self,
-                config,
-                yanked_whitelist,
-            )?)),
+            SourceKind::Registry => {
+                let primary = match default_registry_index.filter(|_| self.is_default_registry()) {
+                    Some(index) => RegistrySource::new(
+                        SourceId::for_registry(index)?,
+                        config,
+                        yanked_whitelist,
+                    )?
+                    .with_package_source_id(self),
+                    None => RegistrySource::new(self, config, yanked_whitelist)?,
+                };
+                match registry_mirrors.get(&self.canonical_url) {
+                    Some(mirrors) => Ok(Arc::new(MirroredRegistrySource::new(
+                        self,
+                        primary,
+                        mirrors,
+                        config,
+                        yanked_whitelist,
+                    )?)),
+                    None => Ok(Arc::new(primary)),
+                }
+            }
             SourceKind::Std => Ok(Arc::new(StandardLibSource::new(config))),
         }
     }
@@ -484,8 +683,11 @@ mod tests {
     use test_case::test_case;
     use url::Url;
 
+    use crate::core::errors::SourceIdParseError;
     use crate::core::{GitReference, source::SourceId};
 
+    const PRECISE: &str = "3356bf0c5c1a089167d7d3c28d543e195325e596";
+
     #[test_case(SourceId::mock_git())]
     #[test_case(SourceId::mock_path())]
     #[test_case(SourceId::default_registry())]
@@ -497,6 +699,25 @@ mod tests {
         );
     }
 
+    #[test_case(SourceId::mock_git())]
+    #[test_case(SourceId::mock_git().with_precise(PRECISE.to_string()).unwrap())]
+    #[test_case(SourceId::mock_path())]
+    #[test_case(SourceId::default_registry())]
+    #[test_case(SourceId::for_std())]
+    fn equality_after_bytes_conversion(source_id: SourceId) {
+        let decoded = SourceId::from_bytes(&source_id.to_bytes()).unwrap();
+        assert_eq!(decoded, source_id);
+        assert_eq!(decoded.to_pretty_url(), source_id.to_pretty_url());
+    }
+
+    #[test]
+    fn invalid_bytes() {
+        let bytes = SourceId::mock_git().to_bytes();
+        assert!(SourceId::from_bytes(&bytes[..bytes.len() - 1]).is_err());
+        assert!(SourceId::from_bytes(&[bytes.as_slice(), &[0]].concat()).is_err());
+        assert!(SourceId::from_bytes(&[42]).is_err());
+    }
+
     #[test]
     fn ignores_git_suffix() {
         fn mock_git(input: &str) -> SourceId {
@@ -517,20 +738,92 @@ mod tests {
         let original = sid.to_pretty_url();
         assert!(!original.contains("some_rev"));
         assert!(!original.contains('#'));
-        let sid = sid.with_precise("some_rev".into()).unwrap();
+        let sid = sid.with_precise_unchecked("some_rev".into()).unwrap();
         assert_eq!(sid.to_pretty_url(), format!("{original}#some_rev"));
     }
 
     #[test]
     fn parses_precise() {
         let sid = SourceId::mock_git();
-        let sid = sid.with_precise("some_rev".into()).unwrap();
+        let sid = sid.with_precise(PRECISE.into()).unwrap();
         assert_eq!(
             SourceId::from_pretty_url(&sid.to_pretty_url()).unwrap(),
             sid
         );
     }
 
+    #[test_case("some_rev")]
+    #[test_case("1f06df93")]
+    #[test_case("zzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzzz")]
+    fn rejects_invalid_precise(precise: &str) {
+        let sid = SourceId::mock_git();
+        assert!(sid.with_precise(precise.into()).is_err());
+        let url = format!("{}#{precise}", sid.to_pretty_url());
+        let err = SourceId::from_pretty_url(&url).unwrap_err();
+        assert!(matches!(err, SourceIdParseError::InvalidSource(_)));
+        assert!(
+            err.to_string().ends_with(
+                "help: remove `Scarb.lock` and run Scarb again to regenerate the lockfile"
+            )
+        );
+    }
+
+    #[test]
+    fn pretty_url_missing_url() {
+        let err = SourceId::from_pretty_url("git").unwrap_err();
+        assert!(matches!(err, SourceIdParseError::MissingUrl { .. }));
+        assert_eq!(err.to_string(), "invalid source: git");
+    }
+
+    #[test_case("git+not a url")]
+    #[test_case("path+")]
+    #[test_case("registry+://example.com")]
+    fn pretty_url_bad_url(pretty_url: &str) {
+        let err = SourceId::from_pretty_url(pretty_url).unwrap_err();
+        assert!(matches!(err, SourceIdParseError::BadUrl { .. }));
+        assert_eq!(
+            err.to_string(),
+            format!("cannot parse source URL: {pretty_url}")
+        );
+    }
+
+    #[test]
+    fn pretty_url_unknown_protocol() {
+        let err = SourceId::from_pretty_url("svn+https://example.com/repo").unwrap_err();
+        assert!(matches!(
+            &err,
+            SourceIdParseError::UnknownProtocol { protocol } if protocol == "svn"
+        ));
+        assert_eq!(err.to_string(), "unsupported source protocol: svn");
+    }
+
+    #[test]
+    fn pretty_url_invalid_source() {
+        let url = format!("{}#some_rev", SourceId::mock_git().to_pretty_url());
+        let err = SourceId::from_pretty_url(&url).unwrap_err();
+        assert!(matches!(err, SourceIdParseError::InvalidSource(_)));
+    }
+
+    #[test]
+    fn same_repository() {
+        let url = Url::parse("https://github.com/starkware-libs/cairo.git").unwrap();
+        let tag = SourceId::for_git(&url, &GitReference::Tag("v2.0.0".into())).unwrap();
+        let branch = SourceId::for_git(&url, &GitReference::Branch("main".into()))
+            .unwrap()
+            .with_precise(PRECISE.into())
+            .unwrap();
+        assert_ne!(tag, branch);
+        assert!(tag.same_repository(branch));
+        assert!(branch.same_repository(tag));
+
+        let other_url = Url::parse("https://github.com/software-mansion/scarb.git").unwrap();
+        let other = SourceId::for_git(&other_url, &GitReference::Tag("v2.0.0".into())).unwrap();
+        assert!(!tag.same_repository(other));
+
+        let registry = SourceId::for_registry(&url).unwrap();
+        assert!(!tag.same_repository(registry));
+    }
+
     // NOTE: Path sources are deliberately not tested here, because paths have different form
     //   depending on running OS. We simply trust that this code works in that case.
     #[test_case(SourceId::mock_git() => "github.com-192sksn8g7p8c")]
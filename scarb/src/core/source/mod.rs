@@ -3,8 +3,10 @@ use async_trait::async_trait;
 
 use crate::core::manifest::{ManifestDependency, Summary};
 use crate::core::package::{Package, PackageId};
+pub use cache::SourceCache;
 pub use id::*;
 
+mod cache;
 mod id;
 
 /// Something that finds and downloads remote packages based on names and versions.
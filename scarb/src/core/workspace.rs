@@ -1,15 +1,18 @@
 use std::collections::{BTreeMap, HashSet};
 use std::fmt;
+use std::sync::Mutex;
 
 use anyhow::{Result, anyhow, bail};
 use camino::{Utf8Path, Utf8PathBuf};
 use itertools::Itertools;
 use scarb_ui::args::PackagesSource;
 use smol_str::SmolStr;
+use url::Url;
 
-use crate::compiler::Profile;
+use crate::compiler::{ContractSelectorsCache, Profile};
 use crate::core::config::Config;
 use crate::core::package::Package;
+use crate::core::registry::RegistryMirrors;
 use crate::core::{ManifestDependency, PackageId, ScriptDefinition, Target};
 use crate::flock::Filesystem;
 use crate::sources::canonical_url::CanonicalUrl;
@@ -28,6 +31,12 @@ pub struct Workspace<'c> {
     root_package: Option<PackageId>,
     target_dir: Filesystem,
     patch: BTreeMap<CanonicalUrl, Vec<ManifestDependency>>,
+    source_replacements: BTreeMap<CanonicalUrl, Utf8PathBuf>,
+    registry_mirrors: BTreeMap<CanonicalUrl, RegistryMirrors>,
+    default_registry_index: Option<Url>,
+    tool_metadata: BTreeMap<SmolStr, toml::Value>,
+    artifacts_written: Mutex<Vec<Utf8PathBuf>>,
+    contract_selectors_cache: ContractSelectorsCache,
 }
 
 impl<'c> Workspace<'c> {
@@ -66,9 +75,49 @@ impl<'c> Workspace<'c> {
             members: packages,
             scripts,
             patch,
+            source_replacements: BTreeMap::new(),
+            registry_mirrors: BTreeMap::new(),
+            default_registry_index: None,
+            tool_metadata: BTreeMap::new(),
+            artifacts_written: Mutex::new(Vec::new()),
+            contract_selectors_cache: ContractSelectorsCache::default(),
         })
     }
 
+    pub(crate) fn with_source_replacements(
+        self,
+        source_replacements: BTreeMap<CanonicalUrl, Utf8PathBuf>,
+    ) -> Self {
+        Self {
+            source_replacements,
+            ..self
+        }
+    }
+
+    pub(crate) fn with_registry_mirrors(
+        self,
+        registry_mirrors: BTreeMap<CanonicalUrl, RegistryMirrors>,
+    ) -> Self {
+        Self {
+            registry_mirrors,
+            ..self
+        }
+    }
+
+    pub(crate) fn with_default_registry_index(self, default_registry_index: Option<Url>) -> Self {
+        Self {
+            default_registry_index,
+            ..self
+        }
+    }
+
+    pub(crate) fn with_tool_metadata(self, tool_metadata: BTreeMap<SmolStr, toml::Value>) -> Self {
+        Self {
+            tool_metadata,
+            ..self
+        }
+    }
+
     pub(crate) fn from_single_package(
         package: Package,
         config: &'c Config,
@@ -94,6 +143,28 @@ impl<'c> Workspace<'c> {
         self.config
     }
 
+    /// Returns the number of compilation artifacts written to the target directory so far.
+    pub fn artifacts_written(&self) -> usize {
+        self.artifacts_written.lock().unwrap().len()
+    }
+
+    /// Returns paths of compilation artifacts written to the target directory so far.
+    pub fn written_artifact_paths(&self) -> Vec<Utf8PathBuf> {
+        self.artifacts_written.lock().unwrap().clone()
+    }
+
+    pub(crate) fn record_artifact_written(&self, path: &Utf8Path) {
+        self.artifacts_written
+            .lock()
+            .unwrap()
+            .push(path.to_path_buf());
+    }
+
+    /// Returns the cache of external contract selectors expanded during this invocation.
+    pub(crate) fn contract_selectors_cache(&self) -> &ContractSelectorsCache {
+        &self.contract_selectors_cache
+    }
+
     pub fn root(&self) -> &Utf8Path {
         self.manifest_path
             .parent()
@@ -197,6 +268,30 @@ impl<'c> Workspace<'c> {
     pub fn patch(&self) -> &BTreeMap<CanonicalUrl, Vec<ManifestDependency>> {
         &self.patch
     }
+
+    /// Returns directories with vendored packages, keyed by the URL of the source they replace.
+    pub fn source_replacements(&self) -> &BTreeMap<CanonicalUrl, Utf8PathBuf> {
+        &self.source_replacements
+    }
+
+    /// Returns mirrors of registries, keyed by the URL of the mirrored registry.
+    pub fn registry_mirrors(&self) -> &BTreeMap<CanonicalUrl, RegistryMirrors> {
+        &self.registry_mirrors
+    }
+
+    /// Returns the index which packages of the default registry are read from, if overridden.
+    ///
+    /// The workspace manifest takes precedence over the [`Config`].
+    pub fn default_registry_index(&self) -> Option<&Url> {
+        self.default_registry_index
+            .as_ref()
+            .or_else(|| self.config.default_registry_index())
+    }
+
+    /// Returns the metadata of a tool defined in the `[workspace.tool]` table.
+    pub fn tool_metadata(&self, tool_name: &str) -> Option<&toml::Value> {
+        self.tool_metadata.get(tool_name)
+    }
 }
 
 fn check_unique_targets(targets: &Vec<&Target>) -> Result<()> {
@@ -1,5 +1,6 @@
 pub mod async_cache;
 pub mod cloneable_error;
+pub mod edit_distance;
 pub mod fsx;
 pub mod lazy_directory_creator;
 pub mod restricted_names;
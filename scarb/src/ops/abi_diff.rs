@@ -0,0 +1,225 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use anyhow::{Context, Result};
+use cairo_lang_starknet_classes::abi::{Contract, Input, Item, Output, StateMutability};
+use cairo_lang_starknet_classes::contract_class::ContractClass;
+use camino::Utf8Path;
+use itertools::Itertools;
+use scarb_ui::Message;
+use serde::{Serialize, Serializer};
+
+use crate::internal::fsx;
+
+/// Differences between entrypoints of two versions of a Starknet contract class.
+#[derive(Debug, Default, Serialize)]
+pub struct AbiDiff {
+    pub added: Vec<AbiEntrypoint>,
+    pub removed: Vec<AbiEntrypoint>,
+    pub changed: Vec<ChangedAbiEntrypoint>,
+}
+
+impl AbiDiff {
+    /// Whether the new contract class breaks callers of the old one,
+    /// i.e. any entrypoint has been removed or has changed its signature.
+    pub fn is_breaking(&self) -> bool {
+        !self.removed.is_empty() || !self.changed.is_empty()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && !self.is_breaking()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct AbiEntrypoint {
+    pub kind: AbiEntrypointKind,
+    pub name: String,
+    pub signature: String,
+    /// Signature without parameter names, which callers do not depend on.
+    #[serde(skip)]
+    types: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ChangedAbiEntrypoint {
+    pub kind: AbiEntrypointKind,
+    pub name: String,
+    pub old_signature: String,
+    pub new_signature: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AbiEntrypointKind {
+    Constructor,
+    Function,
+    L1Handler,
+}
+
+impl AbiEntrypointKind {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Constructor => "constructor",
+            Self::Function => "function",
+            Self::L1Handler => "l1_handler",
+        }
+    }
+}
+
+/// Compares entrypoints declared in ABIs of two contract class files.
+#[tracing::instrument(level = "debug")]
+pub fn abi_diff(old: &Utf8Path, new: &Utf8Path) -> Result<AbiDiff> {
+    let old = collect_entrypoints(&read_abi(old)?);
+    let mut new = collect_entrypoints(&read_abi(new)?);
+
+    let mut diff = AbiDiff::default();
+    for (key, old) in old {
+        match new.remove(&key) {
+            None => diff.removed.push(old),
+            Some(new) if new.types != old.types => diff.changed.push(ChangedAbiEntrypoint {
+                kind: old.kind,
+                name: old.name,
+                old_signature: old.signature,
+                new_signature: new.signature,
+            }),
+            Some(_) => {}
+        }
+    }
+    diff.added = new.into_values().collect();
+    Ok(diff)
+}
+
+fn read_abi(path: &Utf8Path) -> Result<Contract> {
+    let contents = fsx::read_to_string(path)?;
+    let class: ContractClass = serde_json::from_str(&contents)
+        .with_context(|| format!("failed to parse contract class: {path}"))?;
+    class
+        .abi
+        .with_context(|| format!("contract class does not contain ABI: {path}"))
+}
+
+fn collect_entrypoints(abi: &Contract) -> BTreeMap<(AbiEntrypointKind, String), AbiEntrypoint> {
+    let mut entrypoints = BTreeMap::new();
+    collect_items(abi.items.iter(), &mut entrypoints);
+    entrypoints
+}
+
+fn collect_items<'a>(
+    items: impl Iterator<Item = &'a Item>,
+    entrypoints: &mut BTreeMap<(AbiEntrypointKind, String), AbiEntrypoint>,
+) {
+    for item in items {
+        let entrypoint = match item {
+            Item::Function(f) => AbiEntrypoint::new(
+                AbiEntrypointKind::Function,
+                &f.name,
+                &f.inputs,
+                &f.outputs,
+                Some(&f.state_mutability),
+            ),
+            Item::Constructor(c) => AbiEntrypoint::new(
+                AbiEntrypointKind::Constructor,
+                &c.name,
+                &c.inputs,
+                &[],
+                None,
+            ),
+            Item::L1Handler(h) => AbiEntrypoint::new(
+                AbiEntrypointKind::L1Handler,
+                &h.name,
+                &h.inputs,
+                &h.outputs,
+                Some(&h.state_mutability),
+            ),
+            Item::Interface(interface) => {
+                collect_items(interface.items.iter(), entrypoints);
+                continue;
+            }
+            Item::Event(_) | Item::Struct(_) | Item::Enum(_) | Item::Impl(_) => continue,
+        };
+        entrypoints.insert((entrypoint.kind, entrypoint.name.clone()), entrypoint);
+    }
+}
+
+impl AbiEntrypoint {
+    fn new(
+        kind: AbiEntrypointKind,
+        name: &str,
+        inputs: &[Input],
+        outputs: &[Output],
+        state_mutability: Option<&StateMutability>,
+    ) -> Self {
+        let params = inputs
+            .iter()
+            .map(|input| format!("{}: {}", input.name, input.ty))
+            .join(", ");
+        let types = inputs.iter().map(|input| input.ty.as_str()).join(", ");
+        Self {
+            kind,
+            name: name.to_string(),
+            signature: signature(params, outputs, state_mutability),
+            types: signature(types, outputs, state_mutability),
+        }
+    }
+}
+
+fn signature(
+    params: String,
+    outputs: &[Output],
+    state_mutability: Option<&StateMutability>,
+) -> String {
+    let mut signature = format!("({params})");
+    if !outputs.is_empty() {
+        let outputs = outputs.iter().map(|output| output.ty.as_str()).join(", ");
+        write!(signature, " -> {outputs}").unwrap();
+    }
+    match state_mutability {
+        Some(StateMutability::External) => signature.push_str(" [external]"),
+        Some(StateMutability::View) => signature.push_str(" [view]"),
+        None => {}
+    }
+    signature
+}
+
+impl Message for AbiDiff {
+    fn text(self) -> String {
+        if self.is_empty() {
+            return "no entrypoint changes".to_string();
+        }
+        let mut text = String::new();
+        for entrypoint in &self.removed {
+            let kind = entrypoint.kind.as_str();
+            let AbiEntrypoint {
+                name, signature, ..
+            } = entrypoint;
+            writeln!(text, "removed {kind} `{name}{signature}`").unwrap();
+        }
+        for entrypoint in &self.changed {
+            let kind = entrypoint.kind.as_str();
+            let ChangedAbiEntrypoint {
+                name,
+                old_signature,
+                new_signature,
+                ..
+            } = entrypoint;
+            writeln!(
+                text,
+                "changed {kind} `{name}{old_signature}` to `{name}{new_signature}`"
+            )
+            .unwrap();
+        }
+        for entrypoint in &self.added {
+            let kind = entrypoint.kind.as_str();
+            let AbiEntrypoint {
+                name, signature, ..
+            } = entrypoint;
+            writeln!(text, "added {kind} `{name}{signature}`").unwrap();
+        }
+        text.trim_end().to_string()
+    }
+
+    fn structured<S: Serializer>(self, ser: S) -> Result<S::Ok, S::Error> {
+        self.serialize(ser)
+    }
+}
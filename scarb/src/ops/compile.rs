@@ -5,18 +5,25 @@ use crate::compiler::helpers::{build_compiler_config, collect_main_crate_ids};
 use crate::compiler::plugin::proc_macro;
 use crate::compiler::{CairoCompilationUnit, CompilationUnit, CompilationUnitAttributes};
 use crate::core::{
-    FeatureName, PackageId, PackageName, TargetKind, Utf8PathWorkspaceExt, Workspace,
+    FeatureName, InliningStrategy, PackageId, PackageName, TargetKind, Utf8PathWorkspaceExt,
+    Workspace,
 };
 use crate::ops;
 use crate::ops::{CompilationUnitsOpts, get_test_package_ids, validate_features};
 use anyhow::{Context, Error, Result, anyhow};
 use cairo_lang_compiler::db::RootDatabase;
 use cairo_lang_compiler::diagnostics::DiagnosticsError;
+use cairo_lang_defs::db::DefsGroup;
+use cairo_lang_filesystem::ids::CrateId;
+use cairo_lang_semantic::db::SemanticGroup;
+use cairo_lang_semantic::diagnostic::SemanticDiagnosticKind;
+use camino::Utf8PathBuf;
 use indoc::formatdoc;
 use itertools::Itertools;
-use scarb_ui::HumanDuration;
 use scarb_ui::args::FeaturesSpec;
 use scarb_ui::components::Status;
+use scarb_ui::{HumanDuration, Message};
+use serde::{Serialize, Serializer};
 use smol_str::{SmolStr, ToSmolStr};
 use std::collections::HashSet;
 use std::thread;
@@ -61,6 +68,11 @@ pub struct CompileOpts {
     pub include_target_names: Vec<SmolStr>,
     pub features: FeaturesOpts,
     pub ignore_cairo_version: bool,
+    pub no_external_contracts: bool,
+    pub report_ignore: bool,
+    pub verify_deterministic: bool,
+    /// Inlining strategy overriding the one configured for each compiled unit.
+    pub inlining_strategy: Option<InliningStrategy>,
 }
 
 impl CompileOpts {
@@ -93,8 +105,40 @@ impl CompileOpts {
                 .collect_vec(),
             features: features.try_into()?,
             ignore_cairo_version,
+            no_external_contracts: false,
+            report_ignore: false,
+            verify_deterministic: false,
+            inlining_strategy: None,
         })
     }
+
+    pub fn with_no_external_contracts(self, no_external_contracts: bool) -> Self {
+        Self {
+            no_external_contracts,
+            ..self
+        }
+    }
+
+    pub fn with_report_ignore(self, report_ignore: bool) -> Self {
+        Self {
+            report_ignore,
+            ..self
+        }
+    }
+
+    pub fn with_verify_deterministic(self, verify_deterministic: bool) -> Self {
+        Self {
+            verify_deterministic,
+            ..self
+        }
+    }
+
+    pub fn with_inlining_strategy(self, inlining_strategy: Option<InliningStrategy>) -> Self {
+        Self {
+            inlining_strategy,
+            ..self
+        }
+    }
 }
 
 #[tracing::instrument(skip_all, level = "debug")]
@@ -116,7 +160,7 @@ fn process<F>(
     operation_type: Option<&str>,
 ) -> Result<()>
 where
-    F: FnMut(Vec<CompilationUnit>, &[PackageId], &Workspace<'_>) -> Result<()>,
+    F: FnMut(Vec<CompilationUnit>, &[PackageId], &Workspace<'_>) -> Result<usize>,
 {
     let resolve = ops::resolve_workspace(ws)?;
     let packages_to_process = ws
@@ -156,6 +200,17 @@ where
         let is_cairo_plugin = matches!(cu, CompilationUnit::ProcMacro(_));
         is_cairo_plugin || (is_selected && is_included && !is_excluded)
     })
+    .map(|cu| match cu {
+        CompilationUnit::Cairo(mut unit) => {
+            unit.build_external_contracts = !opts.no_external_contracts;
+            unit.verify_deterministic = opts.verify_deterministic;
+            if let Some(inlining_strategy) = &opts.inlining_strategy {
+                unit.compiler_config.inlining_strategy = inlining_strategy.clone();
+            }
+            CompilationUnit::Cairo(unit)
+        }
+        cu => cu,
+    })
     // Proc macro compilations are processed first, as Cairo compilation units may depend on them.
     .sorted_by_key(|cu| {
         if matches!(cu, CompilationUnit::ProcMacro(_)) {
@@ -166,28 +221,169 @@ where
     })
     .collect::<Vec<_>>();
 
-    operation(compilation_units, &packages, ws)?;
+    let compilation_units = operation(compilation_units, &packages, ws)?;
 
-    let elapsed_time = HumanDuration(ws.config().elapsed_time());
+    let elapsed = ws.config().elapsed_time();
+    let elapsed_time = HumanDuration(elapsed);
     let profile = ws.current_profile()?;
     let formatted_message = match operation_type {
         Some(op) => format!("{op} `{profile}` profile target(s) in {elapsed_time}"),
         None => format!("`{profile}` profile target(s) in {elapsed_time}"),
     };
-    ws.config()
-        .ui()
-        .print(Status::new("Finished", &formatted_message));
+    ws.config().ui().print(FinishedMessage {
+        message: &formatted_message,
+        profile: profile.as_str(),
+        elapsed_ms: elapsed.as_millis() as u64,
+        compilation_units,
+        artifacts: ws.artifacts_written(),
+    });
+
+    if opts.report_ignore {
+        let paths = ws
+            .written_artifact_paths()
+            .into_iter()
+            .map(|path| match path.strip_prefix(ws.root()) {
+                Ok(relative) => relative.to_path_buf(),
+                Err(_) => path,
+            })
+            .collect_vec();
+        ws.config().ui().print(IgnoreReport {
+            patterns: ignore_patterns(&paths),
+        });
+    }
 
     Ok(())
 }
 
+/// File patterns matching artifacts written by the build, suitable for a `.gitignore` file.
+struct IgnoreReport {
+    patterns: Vec<String>,
+}
+
+impl Message for IgnoreReport {
+    fn text(self) -> String {
+        if self.patterns.is_empty() {
+            return "no artifacts were written to the target directory".to_string();
+        }
+        let mut text = "add the following patterns to `.gitignore` to avoid committing build \
+            artifacts:"
+            .to_string();
+        for pattern in self.patterns {
+            text.push('\n');
+            text.push_str(&pattern);
+        }
+        text
+    }
+
+    fn structured<S: Serializer>(self, ser: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct IgnoreReport {
+            patterns: Vec<String>,
+        }
+
+        IgnoreReport {
+            patterns: self.patterns,
+        }
+        .serialize(ser)
+    }
+}
+
+/// Collapse artifact paths into `.gitignore` patterns.
+///
+/// Files in the same directory sharing an extension (everything after the first `.`) are
+/// represented by a single glob pattern built from the common prefix of their names,
+/// like `target/dev/hello_*.contract_class.json`.
+fn ignore_patterns(paths: &[Utf8PathBuf]) -> Vec<String> {
+    let groups = paths
+        .iter()
+        .unique()
+        .filter_map(|path| {
+            let dir = path.parent()?.as_str().replace('\\', "/");
+            let (stem, ext) = path.file_name()?.split_once('.')?;
+            Some(((dir, ext.to_string()), stem.to_string()))
+        })
+        .into_group_map();
+
+    groups
+        .into_iter()
+        .map(|((dir, ext), stems)| {
+            let name = if let [stem] = stems.as_slice() {
+                stem.clone()
+            } else {
+                let prefix = stems
+                    .iter()
+                    .map(String::as_str)
+                    .reduce(|prefix, stem| {
+                        let len = prefix
+                            .chars()
+                            .zip(stem.chars())
+                            .take_while(|(a, b)| a == b)
+                            .map(|(a, _)| a.len_utf8())
+                            .sum();
+                        &prefix[..len]
+                    })
+                    .unwrap_or_default();
+                let prefix = prefix.rfind('_').map_or(prefix, |idx| &prefix[..=idx]);
+                format!("{prefix}*")
+            };
+            if dir.is_empty() {
+                format!("{name}.{ext}")
+            } else {
+                format!("{dir}/{name}.{ext}")
+            }
+        })
+        .sorted()
+        .collect()
+}
+
+/// Summary of a finished build, printed as a `Finished` status line in text mode.
+///
+/// In JSON mode, it additionally carries machine-readable build statistics.
+struct FinishedMessage<'a> {
+    message: &'a str,
+    profile: &'a str,
+    elapsed_ms: u64,
+    compilation_units: usize,
+    artifacts: usize,
+}
+
+impl Message for FinishedMessage<'_> {
+    fn text(self) -> String {
+        Status::new("Finished", self.message).text()
+    }
+
+    fn structured<S: Serializer>(self, ser: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct Finished<'a> {
+            status: &'a str,
+            message: &'a str,
+            profile: &'a str,
+            elapsed_ms: u64,
+            compilation_units: usize,
+            artifacts: usize,
+        }
+
+        Finished {
+            status: "finished",
+            message: self.message,
+            profile: self.profile,
+            elapsed_ms: self.elapsed_ms,
+            compilation_units: self.compilation_units,
+            artifacts: self.artifacts,
+        }
+        .serialize(ser)
+    }
+}
+
+/// Compile given units, returning the number of units that were actually compiled.
 pub fn compile_units(
     units: Vec<CompilationUnit>,
     required_packages: &[PackageId],
     ws: &Workspace<'_>,
-) -> Result<()> {
+) -> Result<usize> {
     let required_plugins = plugins_required_for_units(&units);
 
+    let mut compiled = 0;
     for unit in units {
         // We can skip compiling proc macros that are not used by Cairo compilation units.
         if matches!(&unit, &CompilationUnit::ProcMacro(_))
@@ -198,8 +394,9 @@ pub fn compile_units(
             continue;
         }
         compile_unit(unit, ws)?;
+        compiled += 1;
     }
-    Ok(())
+    Ok(compiled)
 }
 
 /// Run compiler in a new thread.
@@ -218,6 +415,8 @@ pub fn compile_unit(unit: CompilationUnit, ws: &Workspace<'_>) -> Result<()> {
 #[tracing::instrument(skip_all, level = "trace")]
 fn compile_unit_inner(unit: CompilationUnit, ws: &Workspace<'_>) -> Result<()> {
     let package_name = unit.main_package_id().name.clone();
+    let no_std = is_no_std_unit(&unit);
+    let mut missing_std_items = false;
 
     let result = match unit {
         CompilationUnit::ProcMacro(unit) => {
@@ -239,7 +438,10 @@ fn compile_unit_inner(unit: CompilationUnit, ws: &Workspace<'_>) -> Result<()> {
                 proc_macros,
             } = build_scarb_root_database(&unit, ws, Default::default())?;
             check_starknet_dependency(&unit, ws, &db, &package_name);
+            let main_crate_ids = collect_main_crate_ids(&unit, &db);
             let result = ws.config().compilers().compile(unit, &mut db, ws);
+            missing_std_items =
+                no_std && result.is_err() && has_unresolved_paths(&db, &main_crate_ids);
 
             for plugin in proc_macros {
                 plugin
@@ -262,17 +464,23 @@ fn compile_unit_inner(unit: CompilationUnit, ws: &Workspace<'_>) -> Result<()> {
             ws.config().ui().anyhow(&err);
         }
 
-        anyhow!("could not compile `{package_name}` due to previous error")
+        let note = no_std_note(&err, missing_std_items, &package_name);
+        anyhow!("could not compile `{package_name}` due to previous error{note}")
     })
 }
 
+/// Check given units, returning the number of units that were actually processed.
+///
+/// Proc macros required by checked Cairo units are compiled instead, and are counted
+/// the same way as in [`compile_units`].
 fn check_units(
     units: Vec<CompilationUnit>,
     _required_packages: &[PackageId],
     ws: &Workspace<'_>,
-) -> Result<()> {
+) -> Result<usize> {
     let required_plugins = plugins_required_for_units(&units);
 
+    let mut processed = 0;
     for unit in units {
         if matches!(unit, CompilationUnit::ProcMacro(_))
             && required_plugins.contains(&unit.main_package_id())
@@ -283,12 +491,15 @@ fn check_units(
         } else {
             check_unit(unit, ws)?;
         }
+        processed += 1;
     }
-    Ok(())
+    Ok(processed)
 }
 
 fn check_unit(unit: CompilationUnit, ws: &Workspace<'_>) -> Result<()> {
     let package_name = unit.main_package_id().name.clone();
+    let no_std = is_no_std_unit(&unit);
+    let mut missing_std_items = false;
 
     ws.config()
         .ui()
@@ -306,6 +517,8 @@ fn check_unit(unit: CompilationUnit, ws: &Workspace<'_>) -> Result<()> {
                 .diagnostics_reporter
                 .ensure(&db)
                 .map_err(|err| err.into());
+            missing_std_items =
+                no_std && result.is_err() && has_unresolved_paths(&db, &main_crate_ids);
             let span = trace_span!("drop_db");
             {
                 let _guard = span.enter();
@@ -320,7 +533,8 @@ fn check_unit(unit: CompilationUnit, ws: &Workspace<'_>) -> Result<()> {
             ws.config().ui().anyhow(&err);
         }
 
-        anyhow!("could not check `{package_name}` due to previous error")
+        let note = no_std_note(&err, missing_std_items, &package_name);
+        anyhow!("could not check `{package_name}` due to previous error{note}")
     })?;
 
     Ok(())
@@ -354,14 +568,51 @@ fn check_starknet_dependency(
     }
 }
 
+/// Whether the unit is built without `core`, because its main package opted out of it.
+fn is_no_std_unit(unit: &CompilationUnit) -> bool {
+    match unit {
+        CompilationUnit::Cairo(unit) => {
+            !unit.main_package_id.is_core() && unit.core_package_component().is_none()
+        }
+        CompilationUnit::ProcMacro(_) => false,
+    }
+}
+
+// NOTE: Referencing any item from `core` in a no-std package fails with "not found" diagnostics,
+//   which do not hint at the actual cause, so we point it out explicitly.
+fn no_std_note(err: &Error, missing_std_items: bool, package_name: &PackageName) -> String {
+    if missing_std_items && suppress_error(err) {
+        format!("\nnote: std not available in no-std package `{package_name}`")
+    } else {
+        String::new()
+    }
+}
+
+/// Whether any of the main crates failed to compile due to a path that could not be resolved,
+/// which in a no-std package is likely a reference to an item from `core`.
+fn has_unresolved_paths(db: &RootDatabase, main_crate_ids: &[CrateId]) -> bool {
+    main_crate_ids
+        .iter()
+        .flat_map(|crate_id| db.crate_modules(*crate_id).iter().copied().collect_vec())
+        .filter_map(|module_id| db.module_semantic_diagnostics(module_id).ok())
+        .any(|diagnostics| {
+            diagnostics
+                .get_all()
+                .iter()
+                .any(|diag| matches!(diag.kind, SemanticDiagnosticKind::PathNotFound(_)))
+        })
+}
+
 fn suppress_error(err: &Error) -> bool {
     matches!(err.downcast_ref(), Some(&DiagnosticsError))
 }
 
 // Returns proc macro packages that need to be compiled for the provided Cairo compilation units.
-pub fn plugins_required_for_units(units: &[CompilationUnit]) -> HashSet<PackageId> {
+pub fn plugins_required_for_units<'a>(
+    units: impl IntoIterator<Item = &'a CompilationUnit>,
+) -> HashSet<PackageId> {
     units
-        .iter()
+        .into_iter()
         .flat_map(|unit| match unit {
             CompilationUnit::Cairo(unit) => unit
                 .cairo_plugins
@@ -372,3 +623,46 @@ pub fn plugins_required_for_units(units: &[CompilationUnit]) -> HashSet<PackageI
         })
         .collect::<HashSet<PackageId>>()
 }
+
+#[cfg(test)]
+mod tests {
+    use camino::Utf8PathBuf;
+    use test_case::test_case;
+
+    use super::ignore_patterns;
+
+    #[test_case(&["target/dev/hello.sierra.json"] => vec!["target/dev/hello.sierra.json"]; "single")]
+    #[test_case(
+        &[
+            "target/dev/world_Balance.contract_class.json",
+            "target/dev/world_FortyTwo.contract_class.json",
+            "target/dev/world.starknet_artifacts.json",
+        ] => vec![
+            "target/dev/world.starknet_artifacts.json",
+            "target/dev/world_*.contract_class.json",
+        ];
+        "contracts"
+    )]
+    #[test_case(
+        &[
+            "target/dev/world_Balance.contract_class.json",
+            "target/dev/world_Bar.contract_class.json",
+        ] => vec!["target/dev/world_*.contract_class.json"];
+        "prefix cut at underscore"
+    )]
+    #[test_case(
+        &[
+            "target/dev/a.sierra.json",
+            "target/dev/a.sierra.json",
+            "target/release/a.sierra.json",
+        ] => vec!["target/dev/a.sierra.json", "target/release/a.sierra.json"];
+        "deduplicated per directory"
+    )]
+    fn patterns(paths: &[&str]) -> Vec<String> {
+        let paths = paths
+            .iter()
+            .map(|p| Utf8PathBuf::from(*p))
+            .collect::<Vec<_>>();
+        ignore_patterns(&paths)
+    }
+}
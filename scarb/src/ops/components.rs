@@ -0,0 +1,124 @@
+use std::fmt::Write;
+
+use anyhow::{Result, bail};
+use scarb_ui::Message;
+use serde::{Serialize, Serializer};
+use smol_str::SmolStr;
+
+use crate::compiler::{CompilationUnit, CompilationUnitAttributes, CompilationUnitComponent};
+use crate::core::{Package, PackageId, TargetKind, Workspace};
+use crate::ops;
+use crate::ops::{CompilationUnitsOpts, FeaturesOpts, get_test_package_ids, validate_features};
+
+#[derive(Clone, Debug)]
+pub struct InspectComponentsOpts {
+    pub features: FeaturesOpts,
+    pub ignore_cairo_version: bool,
+    pub target_name: Option<SmolStr>,
+}
+
+/// Components of compilation units of a package, along with identities of crates they are
+/// interned as in the compiler database.
+#[derive(Debug, Serialize)]
+pub struct UnitsComponents {
+    units: Vec<UnitComponents>,
+}
+
+#[derive(Debug, Serialize)]
+struct UnitComponents {
+    target_kind: TargetKind,
+    target_name: SmolStr,
+    components: Vec<ComponentInfo>,
+}
+
+#[derive(Debug, Serialize)]
+struct ComponentInfo {
+    package: PackageId,
+    source: String,
+    discriminator: Option<SmolStr>,
+    crate_long_id: String,
+}
+
+impl ComponentInfo {
+    fn new(component: &CompilationUnitComponent) -> Self {
+        Self {
+            package: component.package.id,
+            source: component.package.id.source_id.to_pretty_url(),
+            discriminator: component.id.to_discriminator(),
+            crate_long_id: format!("{:?}", component.crate_long_id()),
+        }
+    }
+}
+
+/// Lists components of Cairo compilation units of `package`, optionally limited to the unit
+/// of a single target.
+pub fn inspect_components(
+    package: Package,
+    opts: InspectComponentsOpts,
+    ws: &Workspace<'_>,
+) -> Result<UnitsComponents> {
+    validate_features(&[package.clone()], &opts.features)?;
+
+    let resolve = ops::resolve_workspace(ws)?;
+    let compilation_units = ops::generate_compilation_units(
+        &resolve,
+        &opts.features,
+        ws,
+        CompilationUnitsOpts {
+            ignore_cairo_version: opts.ignore_cairo_version,
+            load_prebuilt_macros: false,
+        },
+    )?;
+
+    // Includes test package ids.
+    let package_ids = get_test_package_ids(vec![package.id], ws);
+    let units = compilation_units
+        .iter()
+        .filter_map(|unit| match unit {
+            CompilationUnit::Cairo(unit) => Some(unit),
+            _ => None,
+        })
+        .filter(|unit| package_ids.contains(&unit.main_package_id()))
+        .filter(|unit| {
+            opts.target_name
+                .as_ref()
+                .is_none_or(|name| unit.main_component().target_name() == *name)
+        })
+        .map(|unit| UnitComponents {
+            target_kind: unit.main_component().target_kind(),
+            target_name: unit.main_component().target_name(),
+            components: unit.components().iter().map(ComponentInfo::new).collect(),
+        })
+        .collect::<Vec<_>>();
+
+    if units.is_empty() {
+        bail!(
+            "no compilation units found for `{}`",
+            package.id.name.to_string()
+        );
+    }
+
+    Ok(UnitsComponents { units })
+}
+
+impl Message for UnitsComponents {
+    fn text(self) -> String {
+        let mut text = String::new();
+        for unit in self.units {
+            writeln!(text, "{} {}", unit.target_kind, unit.target_name).unwrap();
+            for component in unit.components {
+                writeln!(text, "    {}", component.package).unwrap();
+                writeln!(text, "        source: {}", component.source).unwrap();
+                if let Some(discriminator) = component.discriminator {
+                    writeln!(text, "        discriminator: {discriminator}").unwrap();
+                }
+                writeln!(text, "        crate: {}", component.crate_long_id).unwrap();
+            }
+        }
+        text.trim_end().to_string()
+    }
+
+    fn structured<S: Serializer>(self, ser: S) -> Result<S::Ok, S::Error> {
+        self.serialize(ser)
+    }
+}
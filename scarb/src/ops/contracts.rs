@@ -0,0 +1,246 @@
+use std::fmt::Write;
+
+use anyhow::Result;
+use cairo_lang_defs::ids::NamedLanguageElementId;
+use itertools::Itertools;
+use scarb_ui::Message;
+use serde::{Serialize, Serializer};
+use smol_str::SmolStr;
+
+use crate::compiler::db::{ScarbDatabase, build_scarb_root_database};
+use crate::compiler::{
+    CairoCompilationUnit, CompilationUnit, CompilationUnitAttributes, ProjectContracts,
+    find_unit_contracts,
+};
+use crate::core::{PackageId, PackageName, TargetKind, Workspace};
+use crate::ops;
+use crate::ops::{CompilationUnitsOpts, FeaturesOpts};
+
+#[derive(Debug)]
+pub struct ListContractsOpts {
+    pub features: FeaturesOpts,
+    pub ignore_cairo_version: bool,
+}
+
+/// A Starknet contract which would be built for a package.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub struct PackageContract {
+    /// Name of the package defining the contract, which is not the built package for
+    /// external contracts.
+    pub package_name: PackageName,
+    pub contract_name: String,
+    /// Full Cairo path of the contract module, e.g. `hello::balance::Balance`.
+    pub module_path: String,
+    /// The `build-external-contracts` selector which included the contract, if it is external.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub selector: Option<String>,
+}
+
+/// Lists Starknet contracts which would be built for `packages`.
+///
+/// Contracts are discovered the same way as when building the `starknet-contract` targets,
+/// including external contracts, but nothing is compiled to Sierra nor written to disk.
+#[tracing::instrument(skip_all, level = "debug")]
+pub fn list_contracts(
+    packages: Vec<PackageId>,
+    opts: ListContractsOpts,
+    ws: &Workspace<'_>,
+) -> Result<Vec<PackageContract>> {
+    let units = starknet_contract_units(&packages, &opts.features, opts.ignore_cairo_version, ws)?;
+
+    let mut contracts = Vec::new();
+    for unit in units.iter() {
+        let ScarbDatabase { db, .. } = build_scarb_root_database(unit, ws, Default::default())?;
+        let crate_ids = unit
+            .components()
+            .iter()
+            .map(|component| (component.crate_id(&db), component.package.id.name.clone()))
+            .collect_vec();
+
+        let ProjectContracts {
+            contracts: declarations,
+            selectors,
+            ..
+        } = find_unit_contracts(&db, ws, unit)?;
+        for decl in declarations {
+            let module_id = decl.module_id();
+            let owning_crate = module_id.owning_crate(&db);
+            let Some((_, package_name)) = crate_ids.iter().find(|(id, _)| *id == owning_crate)
+            else {
+                continue;
+            };
+            let module_path = module_id.full_path(&db);
+            contracts.push(PackageContract {
+                package_name: package_name.clone(),
+                contract_name: decl.submodule_id.name(&db).to_string(),
+                selector: selectors.get(&module_path).map(|s| s.full_path()),
+                module_path,
+            });
+        }
+    }
+
+    Ok(contracts.into_iter().sorted().dedup().collect())
+}
+
+#[derive(Debug)]
+pub struct CheckSelectorsOpts {
+    pub features: FeaturesOpts,
+    pub ignore_cairo_version: bool,
+}
+
+/// Result of matching `build-external-contracts` selectors of `starknet-contract` targets against
+/// contracts defined in their dependencies.
+#[derive(Debug, Default, Serialize)]
+pub struct SelectorsCheck {
+    pub targets: Vec<TargetSelectorsCheck>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TargetSelectorsCheck {
+    pub package_name: PackageName,
+    pub target_name: SmolStr,
+    pub matched: Vec<MatchedSelector>,
+    pub unmatched: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MatchedSelector {
+    pub selector: String,
+    /// Number of contracts included by this selector, not counting ones already included by
+    /// a preceding selector.
+    pub contracts: usize,
+}
+
+impl SelectorsCheck {
+    /// Number of selectors which did not match any contract, across all targets.
+    pub fn unmatched_count(&self) -> usize {
+        self.targets
+            .iter()
+            .map(|target| target.unmatched.len())
+            .sum()
+    }
+}
+
+/// Checks whether every `build-external-contracts` selector of `starknet-contract` targets of
+/// `packages` matches at least one contract.
+///
+/// Contracts are discovered the same way as when building, but nothing is compiled to Sierra nor
+/// written to disk.
+#[tracing::instrument(skip_all, level = "debug")]
+pub fn check_selectors(
+    packages: Vec<PackageId>,
+    opts: CheckSelectorsOpts,
+    ws: &Workspace<'_>,
+) -> Result<SelectorsCheck> {
+    let units = starknet_contract_units(&packages, &opts.features, opts.ignore_cairo_version, ws)?;
+
+    let mut check = SelectorsCheck::default();
+    for unit in units.iter() {
+        let ScarbDatabase { db, .. } = build_scarb_root_database(unit, ws, Default::default())?;
+        let ProjectContracts {
+            selectors,
+            external_selectors,
+            unmatched_selectors,
+            ..
+        } = find_unit_contracts(&db, ws, unit)?;
+        if external_selectors.is_empty() {
+            continue;
+        }
+
+        let counts = selectors.values().counts();
+        let matched = external_selectors
+            .iter()
+            .filter(|selector| !unmatched_selectors.contains(selector))
+            .map(|selector| MatchedSelector {
+                selector: selector.full_path(),
+                contracts: counts.get(selector).copied().unwrap_or_default(),
+            })
+            .collect();
+        check.targets.push(TargetSelectorsCheck {
+            package_name: unit.main_package_id().name.clone(),
+            target_name: unit.main_component().target_name(),
+            matched,
+            unmatched: unmatched_selectors
+                .iter()
+                .map(|selector| selector.full_path())
+                .collect(),
+        });
+    }
+    Ok(check)
+}
+
+impl Message for SelectorsCheck {
+    fn text(self) -> String {
+        if self.targets.is_empty() {
+            return "no external contract selectors found".to_string();
+        }
+        let mut text = String::new();
+        for target in &self.targets {
+            writeln!(
+                text,
+                "{} (starknet-contract target `{}`)",
+                target.package_name, target.target_name
+            )
+            .unwrap();
+            for MatchedSelector {
+                selector,
+                contracts,
+            } in &target.matched
+            {
+                let noun = if *contracts == 1 {
+                    "contract"
+                } else {
+                    "contracts"
+                };
+                writeln!(text, "    matched   `{selector}` ({contracts} {noun})").unwrap();
+            }
+            for selector in &target.unmatched {
+                writeln!(text, "    unmatched `{selector}`").unwrap();
+            }
+        }
+        text.trim_end().to_string()
+    }
+
+    fn structured<S: Serializer>(self, ser: S) -> Result<S::Ok, S::Error> {
+        self.serialize(ser)
+    }
+}
+
+/// Generates compilation units of `starknet-contract` targets of `packages`, compiling procedural
+/// macros beforehand, as they may define contracts.
+fn starknet_contract_units(
+    packages: &[PackageId],
+    features: &FeaturesOpts,
+    ignore_cairo_version: bool,
+    ws: &Workspace<'_>,
+) -> Result<Vec<CairoCompilationUnit>> {
+    let resolve = ops::resolve_workspace(ws)?;
+    let compilation_units = ops::generate_compilation_units(
+        &resolve,
+        features,
+        ws,
+        CompilationUnitsOpts {
+            ignore_cairo_version,
+            load_prebuilt_macros: ws.config().load_prebuilt_proc_macros(),
+        },
+    )?;
+
+    compilation_units
+        .iter()
+        .filter(|unit| matches!(unit, CompilationUnit::ProcMacro(_)))
+        .map(|unit| ops::compile::compile_unit(unit.clone(), ws))
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(compilation_units
+        .into_iter()
+        .filter_map(|unit| match unit {
+            CompilationUnit::Cairo(unit)
+                if packages.contains(&unit.main_package_id())
+                    && unit.main_component().target_kind() == TargetKind::STARKNET_CONTRACT =>
+            {
+                Some(unit)
+            }
+            _ => None,
+        })
+        .collect())
+}
@@ -0,0 +1,142 @@
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+use anyhow::Result;
+use async_trait::async_trait;
+use futures::TryFutureExt;
+
+use crate::core::registry::Registry;
+use crate::core::registry::cache::RegistryCache;
+use crate::core::registry::patcher::RegistryPatcher;
+use crate::core::{ManifestDependency, Package, PackageId, SourceId, Summary, Workspace};
+use crate::ops::lockfile::read_lockfile_if_exists;
+use crate::ops::resolve::{workspace_patch_maps, workspace_source_map};
+use crate::resolver;
+
+/// A single source that would have to be loaded by `scarb fetch`.
+#[derive(Clone, Debug)]
+pub struct FetchPlanEntry {
+    pub source_id: SourceId,
+    /// Whether everything needed from this source is already present in the global cache.
+    pub cached: bool,
+}
+
+/// Sources the workspace dependency graph refers to, which would be fetched from the network.
+#[derive(Clone, Debug, Default)]
+pub struct FetchPlan {
+    pub entries: Vec<FetchPlanEntry>,
+}
+
+impl FetchPlan {
+    pub fn missing(&self) -> impl Iterator<Item = &FetchPlanEntry> {
+        self.entries.iter().filter(|entry| !entry.cached)
+    }
+}
+
+/// Compute which sources would need to be fetched in order to resolve the workspace.
+///
+/// The workspace is resolved with the regular resolver, honouring patches, source replacements
+/// and registry mirrors, but the lockfile is neither created nor updated.
+/// The network must be disabled in `ws.config()` for the plan to reflect the contents of
+/// the global cache.
+///
+/// Path and standard library sources are never fetched, and thus are not a part of the plan.
+/// Dependencies of packages coming from missing sources cannot be discovered, so these are
+/// not a part of the plan either.
+#[tracing::instrument(skip_all, level = "debug")]
+pub fn fetch_plan(ws: &Workspace<'_>) -> Result<FetchPlan> {
+    ws.config()
+        .tokio_handle()
+        .block_on(TryFutureExt::into_future(async {
+            let (_, patch_map) = workspace_patch_maps(ws);
+
+            let members_summaries = ws
+                .members()
+                .map(|pkg| pkg.manifest.summary.clone())
+                .collect::<Vec<_>>();
+
+            let lockfile = read_lockfile_if_exists(ws)?
+                .with_precise_overrides(ws.config().precise_overrides())?;
+            let yanked_whitelist = lockfile.create_yanked_whitelist();
+
+            let source_map = workspace_source_map(ws, yanked_whitelist);
+            let recording = RecordingRegistry::new(&source_map);
+            let cached = RegistryCache::new(&recording);
+            let patched = RegistryPatcher::new(&cached, &patch_map);
+
+            match resolver::resolve(&members_summaries, &patched, &patch_map, lockfile).await {
+                Ok(resolve) => {
+                    for package_id in resolve.package_ids() {
+                        // Failures are recorded as missing sources.
+                        let _ = patched.download(package_id).await;
+                    }
+                }
+                // The resolver fails if packages from missing sources are unavailable.
+                Err(err) if !recording.any_missing() => return Err(err),
+                Err(_) => {}
+            }
+
+            let entries = recording
+                .sources
+                .borrow()
+                .iter()
+                .map(|(&source_id, &cached)| FetchPlanEntry { source_id, cached })
+                .collect();
+
+            Ok(FetchPlan { entries })
+        }))
+}
+
+/// Registry wrapper recording which remote sources have been consulted,
+/// and whether they were available.
+struct RecordingRegistry<'a> {
+    registry: &'a dyn Registry,
+    sources: RefCell<BTreeMap<SourceId, bool>>,
+}
+
+impl<'a> RecordingRegistry<'a> {
+    fn new(registry: &'a dyn Registry) -> Self {
+        Self {
+            registry,
+            sources: Default::default(),
+        }
+    }
+
+    fn record(&self, source_id: SourceId, available: bool) {
+        let mut sources = self.sources.borrow_mut();
+        let cached = sources.entry(source_id).or_insert(true);
+        *cached &= available;
+    }
+
+    fn any_missing(&self) -> bool {
+        self.sources.borrow().values().any(|cached| !cached)
+    }
+}
+
+#[async_trait(?Send)]
+impl Registry for RecordingRegistry<'_> {
+    async fn query(&self, dependency: &ManifestDependency) -> Result<Vec<Summary>> {
+        if !is_fetched(dependency.source_id) {
+            return self.registry.query(dependency).await;
+        }
+        let result = self.registry.query(dependency).await;
+        self.record(dependency.source_id, result.is_ok());
+        // Let the resolver proceed with other dependencies, a missing source is reported
+        // in the plan instead.
+        Ok(result.unwrap_or_default())
+    }
+
+    async fn download(&self, package_id: PackageId) -> Result<Package> {
+        if !is_fetched(package_id.source_id) {
+            return self.registry.download(package_id).await;
+        }
+        let result = self.registry.download(package_id).await;
+        self.record(package_id.source_id, result.is_ok());
+        result
+    }
+}
+
+/// Path and standard library sources are never fetched.
+fn is_fetched(source_id: SourceId) -> bool {
+    source_id.is_git() || source_id.is_registry()
+}
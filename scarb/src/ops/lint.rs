@@ -1,38 +1,60 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+use std::io::Write as _;
+use std::ops::Deref;
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::vec;
 
 use crate::{
     compiler::{
-        CompilationUnit, CompilationUnitAttributes,
+        CairoCompilationUnit, CompilationUnit, CompilationUnitAttributes,
         db::{ScarbDatabase, build_scarb_root_database},
+        diagnostics::print_diagnostic,
+        helpers::write_atomically,
+        incremental::{UnitFingerprint, incremental_allowed, is_fresh},
     },
     core::{PackageId, PackageName, TargetKind},
+    internal::serdex::toml_merge,
     ops,
 };
 
-use anyhow::anyhow;
 use anyhow::{Context, Result};
+use anyhow::{anyhow, bail, ensure};
 use cairo_lang_compiler::db::RootDatabase;
 use cairo_lang_defs::db::DefsGroup;
 use cairo_lang_diagnostics::{DiagnosticEntry, Severity};
 use cairo_lang_formatter::FormatterConfig;
+use cairo_lang_semantic::plugin::PluginSuite;
 use cairo_lang_semantic::{SemanticDiagnostic, db::SemanticGroup};
 use cairo_lint::CAIRO_LINT_TOOL_NAME;
+use cairo_lint::context::{Lint, get_all_lints, get_unique_allowed_names};
 use cairo_lint::{
     CairoLintToolMetadata, apply_file_fixes, diagnostics::format_diagnostic, get_fixes,
-    plugin::cairo_lint_plugin_suite,
+    plugin::CairoLint,
 };
-use camino::Utf8PathBuf;
+use camino::{Utf8Path, Utf8PathBuf};
 use itertools::Itertools;
-use scarb_ui::components::Status;
+use scarb_stable_hash::short_hash;
+use scarb_ui::components::{Status, TypedMessage};
+use scarb_ui::{Message, OutputFormat};
+use serde::{Serialize, Serializer};
 
 use crate::core::{Package, Workspace};
+use crate::flock::Filesystem;
+use crate::internal::edit_distance::closest_match;
 use crate::internal::fsx::canonicalize;
+use crate::sources::client::PackageRepository;
 
+use super::manifest_lint::lint_manifest;
 use super::{
     CompilationUnitsOpts, FeaturesOpts, compile_unit, plugins_required_for_units, validate_features,
 };
 
+const LINT_FINGERPRINT_DIR_NAME: &str = "lint";
+
 struct CompilationUnitDiagnostics {
+    pub package_name: PackageName,
     pub db: RootDatabase,
     pub diagnostics: Vec<SemanticDiagnostic>,
     pub formatter_config: FormatterConfig,
@@ -46,7 +68,49 @@ pub struct LintOptions {
     pub ignore_cairo_version: bool,
     pub features: FeaturesOpts,
     pub deny_warnings: bool,
+    /// Fail if more warnings than this are reported across all linted packages.
+    pub max_warnings: Option<usize>,
     pub path: Option<Utf8PathBuf>,
+    pub staged: bool,
+    /// Only report diagnostics in files changed since this Git revision.
+    pub since: Option<String>,
+    pub output_file: Option<Utf8PathBuf>,
+    /// Lint package manifests instead of Cairo code.
+    pub manifest: bool,
+    /// Also report diagnostics in code generated by the compiler and procedural macros.
+    pub include_generated: bool,
+}
+
+/// Builds the `cairo-lint` plugin suite, which skips code generated by the compiler and
+/// procedural macros unless `include_generated` is set.
+fn lint_plugin_suite(
+    tool_metadata: CairoLintToolMetadata,
+    include_generated: bool,
+) -> Result<PluginSuite> {
+    validate_tool_metadata(&tool_metadata)?;
+    let mut suite = PluginSuite::default();
+    suite.add_analyzer_plugin_ex(Arc::new(CairoLint::new(include_generated, tool_metadata)));
+    Ok(suite)
+}
+
+/// Checks that `[tool.cairo-lint]` only switches lints known to `cairo-lint`.
+fn validate_tool_metadata(tool_metadata: &CairoLintToolMetadata) -> Result<()> {
+    let allowed_names = get_unique_allowed_names();
+    for (name, _) in tool_metadata.iter() {
+        if allowed_names.contains(&name.as_str()) {
+            continue;
+        }
+        let mut msg = format!("unknown lint in `[tool.{CAIRO_LINT_TOOL_NAME}]`: `{name}`");
+        if let Some(suggestion) = closest_match(name, allowed_names.iter().copied()) {
+            write!(
+                msg,
+                "\nhelp: a lint with a similar name exists: `{suggestion}`"
+            )
+            .unwrap();
+        }
+        bail!(msg);
+    }
+    Ok(())
 }
 
 #[tracing::instrument(skip_all, level = "debug")]
@@ -65,28 +129,18 @@ pub fn lint(opts: LintOptions, ws: &Workspace<'_>) -> Result<()> {
         },
     )?;
 
-    let absolute_path = opts.path.map(canonicalize).transpose()?;
-
-    // Select proc macro units that need to be compiled for Cairo compilation units.
-    let required_plugins = plugins_required_for_units(&compilation_units);
-
-    // We process all proc-macro units that are required by Cairo compilation units beforehand.
-    for compilation_unit in compilation_units.iter() {
-        if let CompilationUnit::ProcMacro(_) = compilation_unit {
-            if required_plugins.contains(&compilation_unit.main_package_id()) {
-                compile_unit(compilation_unit.clone(), ws)?;
-            }
-        }
+    if opts.manifest {
+        return lint_manifests(&opts, &compilation_units, ws);
     }
 
-    // We store the state of the workspace diagnostics, so we can decide upon throwing an error later on.
-    // Also we want to apply fixes only if there were no previous errors.
-    let mut packages_with_error: Vec<PackageName> = Default::default();
-    let mut diagnostics_per_cu: Vec<CompilationUnitDiagnostics> = Default::default();
+    let absolute_path = opts.path.map(canonicalize).transpose()?;
+
+    // Select compilation units to lint for each package.
+    let mut packages_to_lint: Vec<(Package, Vec<&CompilationUnit>)> = Default::default();
+    let mut skipped_packages: Vec<SkippedPackage> = Default::default();
 
     for package in opts.packages {
         let package_name = &package.id.name;
-        let formatter_config = package.fmt_config()?;
         let package_compilation_units = if opts.test {
             let mut result = vec![];
             let integration_test_compilation_unit =
@@ -121,6 +175,7 @@ pub fn lint(opts: LintOptions, ws: &Workspace<'_>) -> Result<()> {
                 ws.config()
                     .ui()
                     .print(Status::new("Skipping package", package_name.as_str()));
+                skipped_packages.push(SkippedPackage::new(&package, SkipReason::NoCu));
                 continue;
             }
 
@@ -145,6 +200,7 @@ pub fn lint(opts: LintOptions, ws: &Workspace<'_>) -> Result<()> {
                     ws.config()
                         .ui()
                         .print(Status::new("Skipping package", package_name.as_str()));
+                    skipped_packages.push(SkippedPackage::new(&package, SkipReason::NoCu));
                     continue;
                 }
             }
@@ -166,18 +222,84 @@ pub fn lint(opts: LintOptions, ws: &Workspace<'_>) -> Result<()> {
                 .collect::<Vec<_>>()
         };
 
-        for compilation_unit in filtered_by_target_names_package_compilation_units {
+        if filtered_by_target_names_package_compilation_units.is_empty() {
+            let has_matching_target = package
+                .manifest
+                .targets
+                .iter()
+                .any(|t| opts.target_names.contains(&t.name.to_string()));
+            let reason = if has_matching_target {
+                SkipReason::EmptyAfterFilter
+            } else {
+                SkipReason::NoMatchingTargetName
+            };
+            ws.config()
+                .ui()
+                .verbose(Status::new("Skipping package", package_name.as_str()));
+            skipped_packages.push(SkippedPackage::new(&package, reason));
+        }
+
+        packages_to_lint.push((package, filtered_by_target_names_package_compilation_units));
+    }
+
+    // Select proc macro units that need to be compiled for the linted Cairo compilation units.
+    let required_plugins = plugins_required_for_units(
+        packages_to_lint
+            .iter()
+            .flat_map(|(_, compilation_units)| compilation_units.iter().copied()),
+    );
+
+    // We process all proc-macro units that are required by linted compilation units beforehand.
+    for compilation_unit in compilation_units.iter() {
+        if let CompilationUnit::ProcMacro(_) = compilation_unit {
+            if required_plugins.contains(&compilation_unit.main_package_id()) {
+                compile_unit(compilation_unit.clone(), ws)?;
+            }
+        }
+    }
+
+    // We store the state of the workspace diagnostics, so we can decide upon throwing an error later on.
+    // Also we want to apply fixes only if there were no previous errors.
+    let mut packages_with_error: Vec<PackageName> = Default::default();
+    let mut diagnostics_per_cu: Vec<CompilationUnitDiagnostics> = Default::default();
+    // Packages whose compilation units were left unchanged since they were last linted clean.
+    let mut fresh_packages: Vec<PackageName> = Default::default();
+    let mut warnings_count: usize = 0;
+    let mut report = LintReport::new(ws.config().ui().output_format());
+
+    for (package, package_compilation_units) in packages_to_lint {
+        let package_name = &package.id.name;
+        let formatter_config = package.fmt_config()?;
+        let changed_files = changed_files(&package, &opts, ws);
+        for compilation_unit in package_compilation_units {
             match compilation_unit {
                 CompilationUnit::ProcMacro(_) => {
                     continue;
                 }
                 CompilationUnit::Cairo(compilation_unit) => {
+                    let fingerprint = LintFingerprint::new(
+                        compilation_unit,
+                        &package,
+                        opts.include_generated,
+                        ws,
+                    );
+                    if let Some(fingerprint) = &fingerprint {
+                        if fingerprint.is_fresh()? {
+                            ws.config()
+                                .ui()
+                                .print(Status::new("Fresh", &compilation_unit.name()));
+                            fresh_packages.push(package_name.clone());
+                            continue;
+                        }
+                    }
+
                     ws.config()
                         .ui()
                         .print(Status::new("Linting", &compilation_unit.name()));
 
-                    let additional_plugins = vec![cairo_lint_plugin_suite(
-                        cairo_lint_tool_metadata(&package)?,
+                    let additional_plugins = vec![lint_plugin_suite(
+                        cairo_lint_tool_metadata(&package, ws)?,
+                        opts.include_generated,
                     )?];
                     let ScarbDatabase { db, .. } =
                         build_scarb_root_database(compilation_unit, ws, additional_plugins)?;
@@ -186,7 +308,8 @@ pub fn lint(opts: LintOptions, ws: &Workspace<'_>) -> Result<()> {
                     let crate_id = main_component.crate_id(&db);
 
                     // Diagnostics generated by the `cairo-lint` plugin.
-                    // Only user-defined code is included, since virtual files are filtered by the `linter`.
+                    // Only user-defined code is included, since virtual files are filtered by the `linter`,
+                    // unless `--include-generated` was provided.
                     let diags = db
                         .crate_modules(crate_id)
                         .iter()
@@ -194,52 +317,59 @@ pub fn lint(opts: LintOptions, ws: &Workspace<'_>) -> Result<()> {
                         .flat_map(|diags| diags.get_all())
                         .collect_vec();
 
-                    // Filter diagnostics if `SCARB_ACTION_PATH` was provided.
-                    let diagnostics = match &absolute_path {
-                        Some(path) => diags
+                    // Only units without any diagnostics are remembered, so that diagnostics
+                    // are always reported, until they are fixed.
+                    if let Some(fingerprint) = &fingerprint {
+                        if diags.is_empty() {
+                            fingerprint.save(ws)?;
+                        }
+                    }
+
+                    // Filter diagnostics if `SCARB_ACTION_PATH`, `--staged` or `--since` was provided.
+                    let diagnostics = if absolute_path.is_some() || changed_files.is_some() {
+                        diags
                             .into_iter()
                             .filter(|diag| {
                                 let file_id = diag.stable_location.file_id(&db);
 
-                                if let Ok(diag_path) = canonicalize(file_id.full_path(&db)) {
+                                let Ok(diag_path) = canonicalize(file_id.full_path(&db)) else {
+                                    return false;
+                                };
+                                let in_path = absolute_path.as_ref().is_none_or(|path| {
                                     (path.is_dir() && diag_path.starts_with(path))
                                         || (path.is_file() && diag_path == *path)
-                                } else {
-                                    false
-                                }
+                                });
+                                let in_changed = changed_files
+                                    .as_ref()
+                                    .is_none_or(|changed| changed.contains(&diag_path));
+                                in_path && in_changed
                             })
-                            .collect::<Vec<_>>(),
-                        None => diags,
+                            .collect::<Vec<_>>()
+                    } else {
+                        diags
                     };
 
                     // Display diagnostics.
                     for diag in &diagnostics {
-                        match diag.severity() {
-                            Severity::Error => {
-                                if let Some(code) = diag.error_code() {
-                                    ws.config().ui().error_with_code(
-                                        code.as_str(),
-                                        format_diagnostic(diag, &db),
-                                    )
-                                } else {
-                                    ws.config().ui().error(format_diagnostic(diag, &db))
-                                }
-                            }
-                            Severity::Warning => {
-                                if let Some(code) = diag.error_code() {
-                                    ws.config()
-                                        .ui()
-                                        .warn_with_code(code.as_str(), format_diagnostic(diag, &db))
-                                } else {
-                                    ws.config().ui().warn(format_diagnostic(diag, &db))
-                                }
-                            }
-                        }
+                        report.add(diag, &db);
+                        let code = diag.error_code();
+                        print_diagnostic(
+                            &ws.config().ui(),
+                            ws.config().diagnostics_format(),
+                            diag.severity(),
+                            code.as_ref().map(|code| code.as_str()),
+                            &format_diagnostic(diag, &db),
+                        );
                     }
 
                     let warnings_allowed =
                         compilation_unit.compiler_config.allow_warnings && !opts.deny_warnings;
 
+                    warnings_count += diagnostics
+                        .iter()
+                        .filter(|diag| matches!(diag.severity(), Severity::Warning))
+                        .count();
+
                     if diagnostics.iter().any(|diag| {
                         matches!(diag.severity(), Severity::Error)
                             || (!warnings_allowed && matches!(diag.severity(), Severity::Warning))
@@ -247,6 +377,7 @@ pub fn lint(opts: LintOptions, ws: &Workspace<'_>) -> Result<()> {
                         packages_with_error.push(package_name.clone());
                     }
                     diagnostics_per_cu.push(CompilationUnitDiagnostics {
+                        package_name: package_name.clone(),
                         db,
                         diagnostics,
                         formatter_config: formatter_config.clone(),
@@ -256,6 +387,20 @@ pub fn lint(opts: LintOptions, ws: &Workspace<'_>) -> Result<()> {
         }
     }
 
+    // The report is written even if linting failed, as that is when it is most useful.
+    if let Some(output_file) = &opts.output_file {
+        report.write(output_file, ws)?;
+    }
+
+    if !skipped_packages.is_empty() {
+        ws.config().ui().print(SkippedPackages { skipped_packages });
+    }
+
+    ws.config().ui().print(LintSummary::from_diagnostics(
+        &diagnostics_per_cu,
+        &fresh_packages,
+    ));
+
     packages_with_error = packages_with_error
         .into_iter()
         .unique_by(|name| name.to_string())
@@ -279,30 +424,479 @@ pub fn lint(opts: LintOptions, ws: &Workspace<'_>) -> Result<()> {
         }
     }
 
+    if let Some(max_warnings) = opts.max_warnings {
+        ensure!(
+            warnings_count <= max_warnings,
+            "lint checking found {warnings_count} warnings, exceeding the maximum of {max_warnings}"
+        );
+    }
+
     if opts.fix {
+        let mut fixes_count: usize = 0;
+        let mut fixed_files_count: usize = 0;
         for CompilationUnitDiagnostics {
             db,
             diagnostics,
             formatter_config,
+            ..
         } in diagnostics_per_cu.into_iter()
         {
             let fixes = get_fixes(&db, diagnostics);
             for (file_id, fixes) in fixes.into_iter() {
-                ws.config()
-                    .ui()
-                    .print(Status::new("Fixing", &file_id.file_name(&db)));
+                let message = format!(
+                    "{} ({})",
+                    file_id.file_name(&db),
+                    plural(fixes.len(), "fix", "fixes")
+                );
+                ws.config().ui().print(Status::new("Fixing", &message));
+                fixes_count += fixes.len();
+                fixed_files_count += 1;
                 apply_file_fixes(file_id, fixes, &db, formatter_config.clone())?;
             }
         }
+
+        if fixed_files_count > 0 {
+            let message = format!(
+                "{} in {}",
+                plural(fixes_count, "fix", "fixes"),
+                plural(fixed_files_count, "file", "files")
+            );
+            ws.config().ui().print(Status::new("Fixed", &message));
+        }
+    }
+
+    Ok(())
+}
+
+/// Lints manifests of selected packages, reporting problems like code lint diagnostics.
+fn lint_manifests(
+    opts: &LintOptions,
+    compilation_units: &[CompilationUnit],
+    ws: &Workspace<'_>,
+) -> Result<()> {
+    let mut warnings = 0;
+    let mut packages_with_warnings: Vec<PackageName> = Default::default();
+    for package in &opts.packages {
+        ws.config()
+            .ui()
+            .print(Status::new("Linting", package.manifest_path().as_str()));
+        let diagnostics = lint_manifest(package, compilation_units);
+        for diag in &diagnostics {
+            print_diagnostic(
+                &ws.config().ui(),
+                ws.config().diagnostics_format(),
+                diag.severity(),
+                None,
+                &diag.message,
+            );
+        }
+        warnings += diagnostics.len();
+        if !diagnostics.is_empty() {
+            packages_with_warnings.push(package.id.name.clone());
+        }
     }
 
+    ws.config().ui().print(LintSummary {
+        errors: 0,
+        warnings,
+        packages: opts.packages.len(),
+    });
+
+    if opts.deny_warnings && !packages_with_warnings.is_empty() {
+        let package_names = packages_with_warnings
+            .iter()
+            .map(|name| format!("`{name}`"))
+            .join(", ");
+        bail!("lint checking {package_names} manifests failed due to previous errors");
+    }
+    if let Some(max_warnings) = opts.max_warnings {
+        ensure!(
+            warnings <= max_warnings,
+            "lint checking found {warnings} warnings, exceeding the maximum of {max_warnings}"
+        );
+    }
     Ok(())
 }
 
-fn cairo_lint_tool_metadata(package: &Package) -> Result<CairoLintToolMetadata> {
-    Ok(package
-        .tool_metadata(CAIRO_LINT_TOOL_NAME)
-        .cloned()
+/// Fingerprint of inputs of linting a compilation unit.
+///
+/// If the unit has been linted without any diagnostics, and neither the unit (including its
+/// dependencies) nor the lint configuration has changed since, linting it again can be skipped.
+struct LintFingerprint {
+    fingerprint_dir: Filesystem,
+    target_name: String,
+    digest: String,
+}
+
+impl LintFingerprint {
+    /// Returns `None` if incremental compilation is disabled for the unit.
+    fn new(
+        unit: &CairoCompilationUnit,
+        package: &Package,
+        include_generated: bool,
+        ws: &Workspace<'_>,
+    ) -> Option<Self> {
+        if !incremental_allowed(unit) {
+            return None;
+        }
+        let main_component = unit.main_component();
+        let component_digest = UnitFingerprint::new(unit, ws)
+            .get(&main_component.id)?
+            .digest();
+        let digest = short_hash((
+            component_digest,
+            ws.tool_metadata(CAIRO_LINT_TOOL_NAME)
+                .map(ToString::to_string),
+            package
+                .tool_metadata(CAIRO_LINT_TOOL_NAME)
+                .map(ToString::to_string),
+            include_generated,
+        ));
+        Some(Self {
+            fingerprint_dir: unit.fingerprint_dir(ws).child(LINT_FINGERPRINT_DIR_NAME),
+            target_name: main_component.target_name().to_string(),
+            digest,
+        })
+    }
+
+    fn is_fresh(&self) -> Result<bool> {
+        is_fresh(&self.fingerprint_dir, &self.target_name, &self.digest)
+    }
+
+    fn save(&self, ws: &Workspace<'_>) -> Result<()> {
+        self.fingerprint_dir
+            .create_rw(&self.target_name, "fingerprint file", ws.config())?
+            .deref()
+            .write_all(self.digest.as_bytes())
+            .with_context(|| format!("failed to write lint fingerprint of {}", self.target_name))
+    }
+}
+
+/// Why a selected package was not linted.
+#[derive(Serialize)]
+#[serde(rename_all = "kebab-case")]
+enum SkipReason {
+    /// The package has no compilation unit which could be linted.
+    NoCu,
+    /// None of the package targets is named in `--target-names`.
+    NoMatchingTargetName,
+    /// Targets named in `--target-names` exist, but none of them is among linted units.
+    EmptyAfterFilter,
+}
+
+#[derive(Serialize)]
+struct SkippedPackage {
+    package: PackageName,
+    reason: SkipReason,
+}
+
+impl SkippedPackage {
+    fn new(package: &Package, reason: SkipReason) -> Self {
+        Self {
+            package: package.id.name.clone(),
+            reason,
+        }
+    }
+}
+
+/// Packages skipped by `scarb lint`, only reported in JSON output, as human readable output
+/// notes skipped packages as they are encountered.
+#[derive(Serialize)]
+struct SkippedPackages {
+    skipped_packages: Vec<SkippedPackage>,
+}
+
+impl Message for SkippedPackages {
+    fn structured<S: Serializer>(self, ser: S) -> Result<S::Ok, S::Error> {
+        self.serialize(ser)
+    }
+}
+
+/// Number of diagnostics found by `scarb lint`, printed after all packages are linted.
+struct LintSummary {
+    errors: usize,
+    warnings: usize,
+    packages: usize,
+}
+
+impl LintSummary {
+    fn from_diagnostics(
+        diagnostics_per_cu: &[CompilationUnitDiagnostics],
+        fresh_packages: &[PackageName],
+    ) -> Self {
+        let diagnostics = diagnostics_per_cu
+            .iter()
+            .flat_map(|cu| cu.diagnostics.iter())
+            .collect_vec();
+        Self {
+            errors: diagnostics
+                .iter()
+                .filter(|diag| matches!(diag.severity(), Severity::Error))
+                .count(),
+            warnings: diagnostics
+                .iter()
+                .filter(|diag| matches!(diag.severity(), Severity::Warning))
+                .count(),
+            packages: diagnostics_per_cu
+                .iter()
+                .map(|cu| &cu.package_name)
+                .chain(fresh_packages)
+                .unique()
+                .count(),
+        }
+    }
+}
+
+fn plural(count: usize, singular: &str, plural: &str) -> String {
+    if count == 1 {
+        format!("{count} {singular}")
+    } else {
+        format!("{count} {plural}")
+    }
+}
+
+impl Message for LintSummary {
+    fn text(self) -> String {
+        format!(
+            "lint: {}, {} across {}",
+            plural(self.errors, "error", "errors"),
+            plural(self.warnings, "warning", "warnings"),
+            plural(self.packages, "package", "packages"),
+        )
+    }
+
+    fn structured<S: Serializer>(self, ser: S) -> Result<S::Ok, S::Error> {
+        #[derive(Serialize)]
+        struct LintSummary {
+            errors: usize,
+            warnings: usize,
+            packages: usize,
+        }
+
+        LintSummary {
+            errors: self.errors,
+            warnings: self.warnings,
+            packages: self.packages,
+        }
+        .serialize(ser)
+    }
+}
+
+/// Diagnostics collected for the `--output-file` report, formatted according to the UI output
+/// format.
+struct LintReport {
+    output_format: OutputFormat,
+    entries: Vec<String>,
+}
+
+impl LintReport {
+    fn new(output_format: OutputFormat) -> Self {
+        Self {
+            output_format,
+            entries: Vec::new(),
+        }
+    }
+
+    fn add(&mut self, diag: &SemanticDiagnostic, db: &RootDatabase) {
+        let ty = match diag.severity() {
+            Severity::Error => "error",
+            Severity::Warning => "warn",
+        };
+        let code = diag.error_code();
+        let code = code.as_ref().map(|code| code.as_str());
+        let message = format_diagnostic(diag, db);
+        let entry = match self.output_format {
+            OutputFormat::Text => {
+                let code = code.map(|code| format!("[{code}]")).unwrap_or_default();
+                format!("{ty}{code}: {message}")
+            }
+            OutputFormat::Json => {
+                let message = TypedMessage::styled(ty, "", &message);
+                let message = match code {
+                    Some(code) => message.with_code(code),
+                    None => message,
+                };
+                serde_json::to_string(&message).expect("lint diagnostic must serialize to JSON")
+            }
+        };
+        self.entries.push(entry);
+    }
+
+    /// Writes the report to `path`, which is resolved relative to the target directory.
+    ///
+    /// The report is not a build artifact, so it is never passed to the artifact sink.
+    fn write(&self, path: &Utf8Path, ws: &Workspace<'_>) -> Result<()> {
+        let content = self
+            .entries
+            .iter()
+            .map(|entry| format!("{entry}\n"))
+            .join("");
+        write_atomically(
+            path.as_str(),
+            "lint report",
+            ws.target_dir(),
+            ws,
+            |mut file| {
+                file.write_all(content.as_bytes())
+                    .with_context(|| format!("failed to write lint report to `{path}`"))
+            },
+        )
+    }
+}
+
+/// Collects Cairo files of the package's Git repository that are staged for commit with `--staged`,
+/// or changed since the revision given with `--since`.
+///
+/// Returns `None` if neither option is provided, or the package is not located in a Git repository,
+/// so that all files are linted.
+fn changed_files(
+    package: &Package,
+    opts: &LintOptions,
+    ws: &Workspace<'_>,
+) -> Option<HashSet<PathBuf>> {
+    let description = match &opts.since {
+        Some(rev) => format!("files changed since `{rev}`"),
+        None if opts.staged => "staged files".to_string(),
+        None => return None,
+    };
+    let files = PackageRepository::open(package).and_then(|repo| match &opts.since {
+        Some(rev) => repo.changed_files_since(rev),
+        None => repo.staged_files(),
+    });
+    match files {
+        Ok(files) => Some(
+            files
+                .into_iter()
+                .filter(|path| path.extension().is_some_and(|ext| ext == "cairo"))
+                .filter_map(|path| canonicalize(path).ok())
+                .collect(),
+        ),
+        Err(err) => {
+            ws.config().ui().warn(format!(
+                "cannot list {description} of package `{}`, linting all files\nreason: {err:#}",
+                package.id.name
+            ));
+            None
+        }
+    }
+}
+
+/// Describes the lint identified by `code` using its rule definition in `cairo-lint`, or
+/// suggests the closest known lint name if the code is unknown.
+pub fn explain_lint(code: &str) -> Result<String> {
+    let Some(lint) = get_all_lints()
+        .into_iter()
+        .find(|lint| lint.allowed_name() == code)
+    else {
+        let mut msg = format!("unknown lint code: `{code}`");
+        if let Some(suggestion) = closest_match(code, get_unique_allowed_names()) {
+            write!(
+                msg,
+                "\nhelp: a lint with a similar name exists: `{suggestion}`"
+            )
+            .unwrap();
+        }
+        bail!(msg);
+    };
+
+    let mut explanation = format!("{code}: {}", lint.diagnostic_message());
+    if lint.has_fixer() {
+        write!(
+            explanation,
+            "\n\nThis lint can be fixed automatically with `scarb lint --fix`"
+        )
+        .unwrap();
+        if let Some(fix_message) = lint.fix_message() {
+            write!(explanation, ": {fix_message}").unwrap();
+        }
+    }
+    Ok(explanation)
+}
+
+/// Severity of diagnostics of enabled lints, unless `--deny-warnings` is used.
+const ENABLED_LINT_SEVERITY: &str = "warn";
+
+/// Severity of lints which are not enabled, unless turned on in `[tool.cairo-lint]`.
+const DISABLED_LINT_SEVERITY: &str = "allow";
+
+/// Lint rules compiled into the linked `cairo-lint` version.
+#[derive(Serialize)]
+pub struct LintRules {
+    rules: Vec<LintRule>,
+}
+
+#[derive(Serialize)]
+struct LintRule {
+    name: &'static str,
+    enabled: bool,
+    severity: &'static str,
+}
+
+/// Lists all lint rules known to `scarb lint`, along with their default state and severity,
+/// as defined by `cairo-lint`.
+pub fn list_lint_rules() -> LintRules {
+    let lints = get_all_lints();
+    let rules = get_unique_allowed_names()
+        .into_iter()
+        .sorted()
+        .map(|name| {
+            // Lints sharing a name are all switched with the same `[tool.cairo-lint]` key.
+            let enabled = lints
+                .iter()
+                .filter(|lint| lint.allowed_name() == name)
+                .any(|lint| lint.is_enabled());
+            let severity = if enabled {
+                ENABLED_LINT_SEVERITY
+            } else {
+                DISABLED_LINT_SEVERITY
+            };
+            LintRule {
+                name,
+                enabled,
+                severity,
+            }
+        })
+        .collect();
+    LintRules { rules }
+}
+
+impl Message for LintRules {
+    fn text(self) -> String {
+        let width = self
+            .rules
+            .iter()
+            .map(|rule| rule.name.len())
+            .max()
+            .unwrap_or_default()
+            .max("rule".len());
+        let mut text = format!("{:<width$}  severity", "rule");
+        for LintRule { name, severity, .. } in &self.rules {
+            write!(text, "\n{name:<width$}  {severity}").unwrap();
+        }
+        text
+    }
+
+    fn structured<S: Serializer>(self, ser: S) -> Result<S::Ok, S::Error> {
+        self.rules.serialize(ser)
+    }
+}
+
+/// Reads the `[tool.cairo-lint]` table of the package, inheriting keys it does not define from
+/// the `[workspace.tool.cairo-lint]` table.
+fn cairo_lint_tool_metadata(
+    package: &Package,
+    ws: &Workspace<'_>,
+) -> Result<CairoLintToolMetadata> {
+    let metadata = match (
+        ws.tool_metadata(CAIRO_LINT_TOOL_NAME),
+        package.tool_metadata(CAIRO_LINT_TOOL_NAME),
+    ) {
+        (Some(workspace @ toml::Value::Table(_)), Some(package @ toml::Value::Table(_))) => {
+            Some(toml_merge(workspace, package)?)
+        }
+        (workspace, package) => package.or(workspace).cloned(),
+    };
+    Ok(metadata
         .map(toml::Value::try_into)
         .transpose()
         .context("Failed to parse Cairo lint tool metadata")?
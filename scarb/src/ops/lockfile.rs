@@ -1,13 +1,20 @@
 use crate::core::Workspace;
 use crate::core::lockfile::Lockfile;
-use anyhow::{Context, Result};
+use crate::ops::resolve_workspace;
+use anyhow::{Context, Result, ensure};
 use fs4::FileExt;
+use std::fs;
 use std::fs::{File, OpenOptions};
 use std::io::{Read, Write};
 use std::str::FromStr;
 
 #[tracing::instrument(skip_all, level = "debug")]
 pub fn read_lockfile(ws: &Workspace<'_>) -> Result<Lockfile> {
+    // The lockfile must not be created in the frozen mode.
+    if ws.config().frozen() && !ws.lockfile_path().exists() {
+        return Ok(Lockfile::new([]));
+    }
+
     let mut file = OpenOptions::new()
         .read(true)
         .write(true)
@@ -24,8 +31,38 @@ pub fn read_lockfile(ws: &Workspace<'_>) -> Result<Lockfile> {
     Lockfile::from_str(&content)
 }
 
+/// Read the workspace lockfile without creating it if it does not exist yet.
+#[tracing::instrument(skip_all, level = "debug")]
+pub fn read_lockfile_if_exists(ws: &Workspace<'_>) -> Result<Lockfile> {
+    if !ws.lockfile_path().exists() {
+        return Ok(Lockfile::new([]));
+    }
+
+    let mut file = File::open(ws.lockfile_path()).context("failed to open lockfile")?;
+    FileExt::lock_shared(&file).context("failed to acquire shared lockfile access")?;
+
+    let mut content = String::new();
+    file.read_to_string(&mut content)?;
+
+    Lockfile::from_str(&content)
+}
+
 #[tracing::instrument(skip_all, level = "debug")]
 pub fn write_lockfile(lockfile: Lockfile, ws: &Workspace<'_>) -> Result<()> {
+    if ws.config().frozen() {
+        // The `[std]` entry follows the running Scarb version, so it is not compared,
+        // in order not to fail frozen builds after every toolchain upgrade.
+        let current = match fs::read_to_string(ws.lockfile_path()) {
+            Ok(content) => Some(Lockfile::from_str(&content)?.with_std(None).render()?),
+            Err(_) => None,
+        };
+        ensure!(
+            current.as_deref() == Some(lockfile.with_std(None).render()?.as_str()),
+            "the lockfile needs to be updated, but --frozen was passed to prevent this"
+        );
+        return Ok(());
+    }
+
     let mut file = File::create(ws.lockfile_path()).context("failed to create lockfile")?;
 
     file.lock_exclusive()
@@ -36,3 +73,14 @@ pub fn write_lockfile(lockfile: Lockfile, ws: &Workspace<'_>) -> Result<()> {
 
     Ok(())
 }
+
+/// Resolves the workspace and returns contents of its lockfile, serialized as JSON.
+///
+/// Resolution brings the lockfile up to date first, so the output describes
+/// the same packages as the ones that would be used when building the workspace.
+#[tracing::instrument(skip_all, level = "debug")]
+pub fn lockfile_json(ws: &Workspace<'_>) -> Result<serde_json::Value> {
+    resolve_workspace(ws)?;
+    let lockfile = read_lockfile(ws)?;
+    serde_json::to_value(lockfile).context("failed to serialize lockfile")
+}
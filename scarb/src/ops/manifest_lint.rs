@@ -0,0 +1,202 @@
+use std::fs;
+
+use cairo_lang_diagnostics::Severity;
+use camino::Utf8Path;
+use itertools::Itertools;
+use walkdir::WalkDir;
+
+use crate::compiler::{
+    CairoCompilationUnit, CompilationUnit, CompilationUnitAttributes, CompilationUnitDependency,
+};
+use crate::core::{DepKind, Package, TargetKind};
+
+/// A problem found in the manifest of a package by `scarb lint --manifest`.
+pub(crate) struct ManifestDiagnostic {
+    pub message: String,
+}
+
+impl ManifestDiagnostic {
+    fn new(package: &Package, message: String) -> Self {
+        Self {
+            message: format!("{message}\n --> {}", package.manifest_path()),
+        }
+    }
+
+    pub fn severity(&self) -> Severity {
+        Severity::Warning
+    }
+}
+
+/// Checks the manifest of `package` for common smells, using the compilation units resolved for
+/// the workspace to find out which dependencies are actually used.
+pub(crate) fn lint_manifest(
+    package: &Package,
+    compilation_units: &[CompilationUnit],
+) -> Vec<ManifestDiagnostic> {
+    let mut diagnostics = Vec::new();
+
+    let main_unit = compilation_units.iter().find_map(|unit| match unit {
+        CompilationUnit::Cairo(unit)
+            if unit.main_package_id() == package.id
+                && unit.main_component().target_kind() != TargetKind::TEST =>
+        {
+            Some(unit)
+        }
+        _ => None,
+    });
+    if let Some(unit) = main_unit {
+        diagnostics.extend(unused_dependencies(package, unit));
+    }
+    diagnostics.extend(duplicate_external_contracts(package));
+    diagnostics.extend(targets_without_effect(package));
+    diagnostics
+}
+
+/// Finds `[dependencies]` entries which are Cairo libraries never referred to by the package code.
+///
+/// This is a best-effort textual check, dependencies providing compiler plugins are never reported.
+fn unused_dependencies(package: &Package, unit: &CairoCompilationUnit) -> Vec<ManifestDiagnostic> {
+    let main_component = unit.main_component();
+    let sources = read_cairo_sources(main_component.targets.source_root());
+    package
+        .manifest
+        .summary
+        .dependencies
+        .iter()
+        .filter(|dep| dep.kind == DepKind::Normal && !dep.source_id.is_std())
+        .filter(|dep| {
+            main_component
+                .dependencies
+                .iter()
+                .any(|unit_dep| match unit_dep {
+                    CompilationUnitDependency::Library(id) => id.package_id.name == dep.name,
+                    CompilationUnitDependency::Plugin(_) => false,
+                })
+        })
+        .filter(|dep| {
+            !sources
+                .iter()
+                .any(|source| refers_to_crate(source, dep.name.as_str()))
+        })
+        .map(|dep| {
+            ManifestDiagnostic::new(
+                package,
+                format!(
+                    "unused dependency `{}`, it is not referred to by any crate of package `{}`",
+                    dep.name, package.id.name
+                ),
+            )
+        })
+        .collect()
+}
+
+fn read_cairo_sources(root: &Utf8Path) -> Vec<String> {
+    WalkDir::new(root)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter(|entry| entry.file_type().is_file())
+        .filter(|entry| entry.path().extension().is_some_and(|ext| ext == "cairo"))
+        .filter_map(|entry| fs::read_to_string(entry.path()).ok())
+        .collect()
+}
+
+/// Whether `source` contains `name` as a standalone identifier, like in `use name::item;`.
+fn refers_to_crate(source: &str, name: &str) -> bool {
+    let is_ident_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    source.match_indices(name).any(|(start, _)| {
+        let before = source[..start].chars().next_back();
+        let after = source[start + name.len()..].chars().next();
+        !before.is_some_and(is_ident_char) && !after.is_some_and(is_ident_char)
+    })
+}
+
+/// Finds `build-external-contracts` selectors listed more than once in a single target.
+fn duplicate_external_contracts(package: &Package) -> Vec<ManifestDiagnostic> {
+    package
+        .manifest
+        .targets
+        .iter()
+        .flat_map(|target| {
+            let selectors = target
+                .params
+                .get("build-external-contracts")
+                .and_then(|selectors| selectors.as_array())
+                .map(|selectors| selectors.iter().filter_map(|s| s.as_str()).collect_vec())
+                .unwrap_or_default();
+            selectors
+                .into_iter()
+                .duplicates()
+                .map(|selector| {
+                    ManifestDiagnostic::new(
+                        package,
+                        format!(
+                            "duplicate external contract selector `{selector}` in target `{}` \
+                            of kind `{}`",
+                            target.name, target.kind
+                        ),
+                    )
+                })
+                .collect_vec()
+        })
+        .collect()
+}
+
+/// Finds `lib` and `starknet-contract` targets with all of their outputs disabled.
+fn targets_without_effect(package: &Package) -> Vec<ManifestDiagnostic> {
+    package
+        .manifest
+        .targets
+        .iter()
+        .filter(|target| {
+            let enabled = |key: &str, default: bool| match target.params.get(key) {
+                Some(toml::Value::Boolean(enabled)) => *enabled,
+                // Tables, like `casm = { ... }`, enable the output with options.
+                Some(_) => true,
+                None => default,
+            };
+            let outputs = if target.kind == TargetKind::LIB {
+                vec![
+                    enabled("sierra", true),
+                    enabled("casm", false),
+                    enabled("sierra-text", false),
+                ]
+            } else if target.kind == TargetKind::STARKNET_CONTRACT {
+                vec![
+                    enabled("sierra", true),
+                    enabled("casm", false),
+                    enabled("emit-sierra-text", false),
+                    enabled("native", false),
+                ]
+            } else {
+                return false;
+            };
+            outputs.iter().all(|enabled| !enabled)
+        })
+        .map(|target| {
+            ManifestDiagnostic::new(
+                package,
+                format!(
+                    "target `{}` of kind `{}` has no effect, all of its outputs are disabled",
+                    target.name, target.kind
+                ),
+            )
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use test_case::test_case;
+
+    use super::refers_to_crate;
+
+    #[test_case("use foo::bar;" => true; "use path")]
+    #[test_case("fn f() { foo::bar() }" => true; "call path")]
+    #[test_case("use foo;" => true; "use crate")]
+    #[test_case("use foobar::baz;" => false; "prefix")]
+    #[test_case("use my_foo::baz;" => false; "suffix")]
+    #[test_case("fn f() {}" => false; "absent")]
+    fn refers_to_foo(source: &str) -> bool {
+        refers_to_crate(source, "foo")
+    }
+}
@@ -2,11 +2,16 @@
 //!
 //! For datastructures describing the state, see [`crate::core`] module.
 
+pub use abi_diff::*;
 pub use cache::*;
 pub use clean::*;
 pub use compile::*;
+pub use components::*;
+pub use contracts::*;
 pub use expand::*;
+pub use fetch::*;
 pub use fmt::*;
+pub use lockfile::lockfile_json;
 pub use manifest::*;
 pub use metadata::*;
 pub use new::*;
@@ -18,10 +23,14 @@ pub use scripts::*;
 pub use subcommands::*;
 pub use workspace::*;
 
+mod abi_diff;
 mod cache;
 mod clean;
 mod compile;
+mod components;
+mod contracts;
 mod expand;
+mod fetch;
 mod fmt;
 mod lockfile;
 mod manifest;
@@ -38,4 +47,6 @@ mod workspace;
 #[cfg(feature = "scarb-lint")]
 mod lint;
 #[cfg(feature = "scarb-lint")]
+mod manifest_lint;
+#[cfg(feature = "scarb-lint")]
 pub use lint::*;
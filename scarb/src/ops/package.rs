@@ -438,6 +438,10 @@ fn run_verify(
             include_target_names: Vec::new(),
             features,
             ignore_cairo_version,
+            no_external_contracts: false,
+            report_ignore: false,
+            verify_deterministic: false,
+            inlining_strategy: None,
         },
         &ws,
     )?;
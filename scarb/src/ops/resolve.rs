@@ -230,6 +230,8 @@ impl WorkspaceResolve {
 #[derive(Debug, Default)]
 pub struct ResolveOpts {
     /// Do not use lockfile when resolving.
+    ///
+    /// Locks of Git branch dependencies declared with `track = false` are kept nevertheless.
     pub update: bool,
 }
 
@@ -247,78 +249,47 @@ pub fn resolve_workspace_with_opts(
     ws.config()
         .tokio_handle()
         .block_on(TryFutureExt::into_future(async {
-            let mut patch_map = PatchMap::new();
-
-            for (source, patches) in ws.patch() {
-                patch_map.insert(source.clone(), patches.clone());
-            }
-
-            let cairo_version = crate::version::get().cairo.version.parse().unwrap();
-            let version_req = DependencyVersionReq::exact(&cairo_version);
-            patch_map.insert(
-                SourceId::default().canonical_url.clone(),
-                [
-                    ManifestDependency::builder()
-                        .name(PackageName::CORE)
-                        .source_id(SourceId::for_std())
-                        .version_req(version_req.clone())
-                        .build(),
-                    ManifestDependency::builder()
-                        .name(PackageName::STARKNET)
-                        .version_req(version_req.clone())
-                        .source_id(SourceId::for_std())
-                        .build(),
-                    ManifestDependency::builder()
-                        .name(PackageName::EXECUTABLE)
-                        .version_req(version_req.clone())
-                        .source_id(SourceId::for_std())
-                        .build(),
-                    ManifestDependency::builder()
-                        .kind(DepKind::Target(TargetKind::TEST))
-                        .name(PackageName::TEST_PLUGIN)
-                        .version_req(version_req.clone())
-                        .source_id(SourceId::for_std())
-                        .build(),
-                    ManifestDependency::builder()
-                        .kind(DepKind::Target(TargetKind::LIB))
-                        .name(PackageName::CAIRO_RUN_PLUGIN)
-                        .version_req(version_req.clone())
-                        .source_id(SourceId::for_std())
-                        .build(),
-                    ManifestDependency::builder()
-                        .kind(DepKind::Target(TargetKind::TEST))
-                        .name(PackageName::TEST_ASSERTS_PLUGIN)
-                        .version_req(version_req.clone())
-                        .source_id(SourceId::for_std())
-                        .build(),
-                ],
-            );
-            if let Some(custom_source_patches) = ws.config().custom_source_patches() {
-                patch_map.insert(
-                    SourceId::default().canonical_url.clone(),
-                    custom_source_patches.clone(),
-                );
-            }
+            let (user_patch_map, patch_map) = workspace_patch_maps(ws);
 
             let members_summaries = ws
                 .members()
                 .map(|pkg| pkg.manifest.summary.clone())
                 .collect::<Vec<_>>();
 
+            let precise_overrides = ws.config().precise_overrides();
             let (lockfile, yanked_whitelist) = if opts.update {
-                (Lockfile::new([]), HashSet::new())
+                let pinned = members_summaries
+                    .iter()
+                    .flat_map(|summary| summary.full_dependencies())
+                    .filter(|dep| !dep.track);
+                let lockfile = read_lockfile(ws)?.with_precise_overrides(precise_overrides)?;
+                // Overridden locks are kept, just like pinned ones.
+                let overridden = lockfile
+                    .packages()
+                    .filter(|lock| precise_overrides.contains_key(&lock.name))
+                    .cloned();
+                let locks = lockfile
+                    .retain_locked(pinned)
+                    .packages()
+                    .cloned()
+                    .chain(overridden)
+                    .sorted()
+                    .dedup()
+                    .collect_vec();
+                (Lockfile::new(locks), HashSet::new())
             } else {
-                let lockfile = read_lockfile(ws)?;
+                let lockfile = read_lockfile(ws)?.with_precise_overrides(precise_overrides)?;
                 let yanked_whitelist = lockfile.create_yanked_whitelist();
                 (lockfile, yanked_whitelist)
             };
 
-            let source_map = SourceMap::preloaded(ws.members(), ws.config(), yanked_whitelist);
+            let source_map = workspace_source_map(ws, yanked_whitelist);
             let cached = RegistryCache::new(&source_map);
             let patched = RegistryPatcher::new(&cached, &patch_map);
 
             let resolve =
                 resolver::resolve(&members_summaries, &patched, &patch_map, lockfile).await?;
+            user_patch_map.ensure_compatible_versions(&resolve)?;
 
             write_lockfile(Lockfile::from_resolve(&resolve), ws)?;
             patch_map.warn_unused(ws.config().ui());
@@ -335,6 +306,80 @@ pub fn resolve_workspace_with_opts(
         }))
 }
 
+/// Build patch maps applied during workspace resolution.
+///
+/// Returns the patches declared by the user, and the full patch map, which additionally
+/// redirects standard library packages to the bundled sources.
+pub(crate) fn workspace_patch_maps(ws: &Workspace<'_>) -> (PatchMap, PatchMap) {
+    let mut user_patch_map = PatchMap::new();
+    for (source, patches) in ws.patch() {
+        user_patch_map.insert(source.clone(), patches.clone());
+    }
+    let mut patch_map = user_patch_map.clone();
+
+    let cairo_version = crate::version::get().cairo.version.parse().unwrap();
+    let version_req = DependencyVersionReq::exact(&cairo_version);
+    patch_map.insert(
+        SourceId::default().canonical_url.clone(),
+        [
+            ManifestDependency::builder()
+                .name(PackageName::CORE)
+                .source_id(SourceId::for_std())
+                .version_req(version_req.clone())
+                .build(),
+            ManifestDependency::builder()
+                .name(PackageName::STARKNET)
+                .version_req(version_req.clone())
+                .source_id(SourceId::for_std())
+                .build(),
+            ManifestDependency::builder()
+                .name(PackageName::EXECUTABLE)
+                .version_req(version_req.clone())
+                .source_id(SourceId::for_std())
+                .build(),
+            ManifestDependency::builder()
+                .kind(DepKind::Target(TargetKind::TEST))
+                .name(PackageName::TEST_PLUGIN)
+                .version_req(version_req.clone())
+                .source_id(SourceId::for_std())
+                .build(),
+            ManifestDependency::builder()
+                .kind(DepKind::Target(TargetKind::LIB))
+                .name(PackageName::CAIRO_RUN_PLUGIN)
+                .version_req(version_req.clone())
+                .source_id(SourceId::for_std())
+                .build(),
+            ManifestDependency::builder()
+                .kind(DepKind::Target(TargetKind::TEST))
+                .name(PackageName::TEST_ASSERTS_PLUGIN)
+                .version_req(version_req.clone())
+                .source_id(SourceId::for_std())
+                .build(),
+        ],
+    );
+    if let Some(custom_source_patches) = ws.config().custom_source_patches() {
+        patch_map.insert(
+            SourceId::default().canonical_url.clone(),
+            custom_source_patches.clone(),
+        );
+    }
+
+    (user_patch_map, patch_map)
+}
+
+/// Build the [`SourceMap`] used for workspace resolution, honouring source replacements,
+/// registry mirrors and the default registry override configured in the workspace.
+pub(crate) fn workspace_source_map<'c>(
+    ws: &Workspace<'c>,
+    yanked_whitelist: HashSet<PackageId>,
+) -> SourceMap<'c> {
+    SourceMap::preloaded(ws.members(), ws.config(), yanked_whitelist)
+        .with_source_replacements(ws.source_replacements().clone())
+        .with_registry_mirrors(ws.registry_mirrors().clone())
+        .with_default_registry_index(ws.default_registry_index().cloned())
+        .with_source_cache(ws.config().source_cache().clone())
+}
+
 /// Gather [`Package`] instances from this resolver result, by asking the [`RegistryCache`]
 /// to download resolved packages.
 ///
@@ -718,6 +763,8 @@ fn cairo_compilation_unit_for_target(
         profile: profile.clone(),
         compiler_config: member.manifest.compiler_config.clone(),
         cfg_set: unit_cfg_set,
+        build_external_contracts: true,
+        verify_deterministic: false,
     })
 }
 
@@ -866,6 +913,8 @@ impl<'a> PackageSolutionCollector<'a> {
             ignore_cairo_version,
         )?;
 
+        check_duplicate_package_names(&packages, self.member, &mut self.warnings)?;
+
         // Print warnings for dependencies that are not usable.
         let other = classes.remove(&PackageClass::Other).unwrap_or_default();
         for pkg in other {
@@ -1004,6 +1053,47 @@ fn build_cfg_set(target: &Target, compiler_config: &ManifestCompilerConfig) -> C
     cfg
 }
 
+/// Warn about packages sharing the same name, but coming from different sources.
+///
+/// Such packages are distinguished only by crate discriminators, and their presence in a single
+/// compilation unit often indicates a dependency resolution problem.
+fn check_duplicate_package_names(
+    packages: &[Package],
+    member: &Package,
+    warnings: &mut HashSet<String>,
+) -> Result<()> {
+    let allowed = member
+        .scarb_tool_metadata()?
+        .allow_duplicate_package_names
+        .unwrap_or_default();
+    if allowed {
+        return Ok(());
+    }
+
+    let sources_by_name = packages
+        .iter()
+        .map(|package| (package.id.name.clone(), package.id.source_id))
+        .unique()
+        .into_group_map();
+    for (name, source_ids) in sources_by_name
+        .into_iter()
+        .filter(|(_, source_ids)| source_ids.len() > 1)
+        .sorted_by(|(a, _), (b, _)| a.cmp(b))
+    {
+        let sources = source_ids
+            .iter()
+            .sorted()
+            .map(|source_id| format!("`{source_id}`"))
+            .join(" and ");
+        warnings.insert(formatdoc! {r#"
+            package `{name}` is used by `{member_name}` from multiple sources: {sources}
+            note: this often indicates a dependency resolution problem
+            help: if this is intentional, set `allow-duplicate-package-names = true` in the `[tool.scarb]` section
+        "#, member_name = member.id.name});
+    }
+    Ok(())
+}
+
 fn check_cairo_version_compatibility(
     packages: &[Package],
     ws: &Workspace<'_>,
@@ -1103,3 +1193,79 @@ pub fn get_test_package_ids(packages: Vec<PackageId>, ws: &Workspace<'_>) -> Vec
         })
         .collect::<Vec<PackageId>>()
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use assert_fs::TempDir;
+    use assert_fs::prelude::*;
+    use camino::Utf8Path;
+    use indoc::indoc;
+
+    use scarb_test_support::project_builder::ProjectBuilder;
+
+    use super::check_duplicate_package_names;
+    use crate::core::{Config, Package};
+    use crate::ops;
+
+    /// Reads packages named `foo` from two different directories, thus from two different sources.
+    fn read_duplicates(member_extra: &str) -> Vec<Package> {
+        let t = TempDir::new().unwrap();
+        ProjectBuilder::start()
+            .name("foo")
+            .manifest_extra(member_extra)
+            .build(&t.child("first"));
+        ProjectBuilder::start()
+            .name("foo")
+            .build(&t.child("second"));
+        let cache_dir = TempDir::new().unwrap();
+        let config_dir = TempDir::new().unwrap();
+
+        ["first", "second"]
+            .into_iter()
+            .map(|dir| {
+                let manifest_path = Utf8Path::from_path(t.child(dir).child("Scarb.toml").path())
+                    .unwrap()
+                    .to_path_buf();
+                let config = Config::builder(manifest_path.clone())
+                    .global_cache_dir_override(Some(
+                        Utf8Path::from_path(&cache_dir).unwrap().to_path_buf(),
+                    ))
+                    .global_config_dir_override(Some(
+                        Utf8Path::from_path(&config_dir).unwrap().to_path_buf(),
+                    ))
+                    .build()
+                    .unwrap();
+                let ws = ops::read_workspace(&manifest_path, &config).unwrap();
+                ws.members().next().unwrap()
+            })
+            .collect()
+    }
+
+    #[test]
+    fn warns_about_duplicate_package_names() {
+        let packages = read_duplicates("");
+        let mut warnings = HashSet::new();
+        check_duplicate_package_names(&packages, &packages[0], &mut warnings).unwrap();
+        assert_eq!(warnings.len(), 1);
+        let warning = warnings.into_iter().next().unwrap();
+        assert!(warning.starts_with("package `foo` is used by `foo` from multiple sources: `"));
+        assert!(
+            warning.contains(
+                "help: if this is intentional, set `allow-duplicate-package-names = true`"
+            )
+        );
+    }
+
+    #[test]
+    fn duplicate_package_names_can_be_allowed() {
+        let packages = read_duplicates(indoc! {r#"
+            [tool.scarb]
+            allow-duplicate-package-names = true
+        "#});
+        let mut warnings = HashSet::new();
+        check_duplicate_package_names(&packages, &packages[0], &mut warnings).unwrap();
+        assert!(warnings.is_empty());
+    }
+}
@@ -14,7 +14,9 @@ use crate::core::{Config, Package, ScriptDefinition, Workspace};
 use crate::internal::fsx::is_executable;
 use crate::ops;
 use crate::process::exec_replace;
-use crate::subcommands::{EXTERNAL_CMD_PREFIX, SCARB_MANIFEST_PATH_ENV, get_env_vars};
+use crate::subcommands::{
+    EXTERNAL_CMD_PREFIX, SCARB_IGNORE_CAIRO_VERSION_ENV, SCARB_MANIFEST_PATH_ENV, get_env_vars,
+};
 use itertools::Itertools;
 use scarb_ui::components::Status;
 
@@ -82,6 +84,7 @@ pub fn execute_test_subcommand(
     args: &[OsString],
     ws: &Workspace<'_>,
     features: FeaturesSpec,
+    ignore_cairo_version: bool,
 ) -> Result<()> {
     let package_name = &package.id.name;
     let mut env = HashMap::from_iter([(
@@ -89,6 +92,10 @@ pub fn execute_test_subcommand(
         package.manifest_path().to_string(),
     )]);
     env.extend(features.to_env_vars());
+    if ignore_cairo_version {
+        // Test runners compile tests by calling back into Scarb, which picks this override up.
+        env.insert(SCARB_IGNORE_CAIRO_VERSION_ENV.into(), "true".into());
+    }
     if let Some(script_definition) = package.manifest.scripts.get("test") {
         debug!("using `test` script: {script_definition}");
         ws.config().ui().print(Status::new(
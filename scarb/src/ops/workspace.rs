@@ -21,7 +21,7 @@ use crate::ops::find_workspace_manifest_path;
 #[tracing::instrument(level = "debug", skip(config))]
 pub fn read_workspace<'c>(manifest_path: &Utf8Path, config: &'c Config) -> Result<Workspace<'c>> {
     let source_id = SourceId::for_path(manifest_path)?;
-    read_workspace_impl(manifest_path, source_id, config)
+    read_workspace_impl(manifest_path, source_id, false, config)
 }
 
 #[tracing::instrument(level = "debug", skip(config))]
@@ -30,19 +30,25 @@ pub fn read_workspace_with_source_id<'c>(
     source_id: SourceId,
     config: &'c Config,
 ) -> Result<Workspace<'c>> {
-    read_workspace_impl(manifest_path, source_id, config)
+    read_workspace_impl(manifest_path, source_id, true, config)
 }
 
+/// Reads the workspace containing `package_manifest`.
+///
+/// If `is_dependency` is set, the workspace is read as a dependency of the workspace Scarb
+/// operates on, which skips checks only meant for manifests written by the user.
 fn read_workspace_impl<'c>(
     package_manifest: &Utf8Path,
     source_id: SourceId,
+    is_dependency: bool,
     config: &'c Config,
 ) -> Result<Workspace<'c>> {
     // Find workspace candidate, if any.
     // This is only a candidate, because it is not guaranteed to add the package as a member.
     let workspace_manifest =
         find_workspace_manifest_path(package_manifest.into())?.unwrap_or(package_manifest.into());
-    let workspace_candidate = read_workspace_root(&workspace_manifest, source_id, config)?;
+    let workspace_candidate =
+        read_workspace_root(&workspace_manifest, source_id, is_dependency, config)?;
     // Check if the package is a member of the workspace candidate.
     if workspace_manifest == package_manifest
         || workspace_candidate
@@ -53,7 +59,7 @@ fn read_workspace_impl<'c>(
         Ok(workspace_candidate)
     } else {
         // Otherwise, we need to create a virtual workspace
-        read_workspace_root(package_manifest, source_id, config)
+        read_workspace_root(package_manifest, source_id, is_dependency, config)
     }
 }
 
@@ -72,6 +78,7 @@ fn validate_virtual_manifest(manifest_path: &Utf8Path, manifest: &TomlManifest)
 fn read_workspace_root<'c>(
     manifest_path: &Utf8Path,
     source_id: SourceId,
+    is_dependency: bool,
     config: &'c Config,
 ) -> Result<Workspace<'c>> {
     let toml_manifest = TomlManifest::read_from_path(manifest_path)?;
@@ -86,6 +93,7 @@ fn read_workspace_root<'c>(
                 source_id,
                 config.profile(),
                 Some(&toml_manifest),
+                is_dependency,
                 config,
             )
             .with_context(|| format!("failed to parse manifest at: {manifest_path}"))?;
@@ -98,6 +106,15 @@ fn read_workspace_root<'c>(
     };
 
     let patch = toml_manifest.collect_patch(manifest_path)?;
+    let source_replacements = toml_manifest
+        .collect_source_replacements(manifest_path)
+        .with_context(|| format!("failed to parse manifest at: {manifest_path}"))?;
+    let registry_mirrors = toml_manifest
+        .collect_registry_mirrors()
+        .with_context(|| format!("failed to parse manifest at: {manifest_path}"))?;
+    let default_registry_index = toml_manifest
+        .collect_default_registry_index()
+        .with_context(|| format!("failed to parse manifest at: {manifest_path}"))?;
 
     if let Some(workspace) = toml_workspace {
         let workspace_root = manifest_path
@@ -105,6 +122,7 @@ fn read_workspace_root<'c>(
             .expect("Manifest path must have parent.");
 
         let scripts = workspace.scripts.unwrap_or_default();
+        let tool_metadata = workspace.tool.unwrap_or_default();
         // Read workspace members.
         let mut packages = workspace
             .members
@@ -114,6 +132,12 @@ fn read_workspace_root<'c>(
             .map(AsRef::as_ref)
             .map(|package_path| {
                 let package_manifest = TomlManifest::read_from_path(package_path)?;
+                if package_manifest.registries.is_some() {
+                    config.ui().warn(format!(
+                        "`[registries]` section is only read from the workspace root manifest, \
+                        ignoring it in: {package_path}"
+                    ));
+                }
                 // Read the member package.
                 let manifest = package_manifest
                     .to_manifest(
@@ -122,6 +146,7 @@ fn read_workspace_root<'c>(
                         source_id,
                         config.profile(),
                         Some(&toml_manifest),
+                        is_dependency,
                         config,
                     )
                     .with_context(|| format!("failed to parse manifest at: {manifest_path}"))?;
@@ -145,10 +170,20 @@ fn read_workspace_root<'c>(
             scripts,
             patch,
         )
+        .map(|ws| {
+            ws.with_source_replacements(source_replacements)
+                .with_registry_mirrors(registry_mirrors)
+                .with_default_registry_index(default_registry_index)
+                .with_tool_metadata(tool_metadata)
+        })
     } else {
         // Read single package workspace
         let package = root_package.ok_or_else(|| anyhow!("the [package] section is missing"))?;
-        Workspace::from_single_package(package, config, profiles, patch)
+        Workspace::from_single_package(package, config, profiles, patch).map(|ws| {
+            ws.with_source_replacements(source_replacements)
+                .with_registry_mirrors(registry_mirrors)
+                .with_default_registry_index(default_registry_index)
+        })
     }
 }
 
@@ -221,7 +256,7 @@ pub fn find_all_workspaces_recursive_with_source_id<'c>(
             trace!(manifest_path=%manifest_path.display());
             if manifest_path.exists() {
                 let manifest_path = manifest_path.try_into_utf8()?;
-                let ws = read_workspace_root(&manifest_path, source_id, config)?;
+                let ws = read_workspace_root(&manifest_path, source_id, true, config)?;
                 found.push(ws);
             }
         }
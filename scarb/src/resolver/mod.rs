@@ -500,13 +500,19 @@ mod tests {
                     [("baz", "1.0.0", "git+https://example.com/baz.git")]
                 ),
                 ("baz v1.0.0 (git+https://example.com/baz.git)", []),
-                ("baz v1.0.0 (git+https://example.com/baz.git#some_rev)", []),
+                (
+                    "baz v1.0.0 (git+https://example.com/baz.git#3356bf0c5c1a089167d7d3c28d543e195325e596)",
+                    []
+                ),
             ],
             &[deps![("foo", "1.0.0"), ("bar", "1.0.0")]],
-            locks![("baz v1.0.0 (git+https://example.com/baz.git#some_rev)", [])],
+            locks![(
+                "baz v1.0.0 (git+https://example.com/baz.git#3356bf0c5c1a089167d7d3c28d543e195325e596)",
+                []
+            )],
             Ok(pkgs![
                 "bar v1.0.0",
-                "baz v1.0.0 (git+https://example.com/baz.git#some_rev)",
+                "baz v1.0.0 (git+https://example.com/baz.git#3356bf0c5c1a089167d7d3c28d543e195325e596)",
                 "foo v1.0.0"
             ]),
         )
@@ -8,10 +8,10 @@
 //!    repositories as source of super important information.
 
 use std::fmt;
-use std::path::Path;
+use std::path::{Path, PathBuf};
 use std::process::Command;
 
-use anyhow::{Context, Result, anyhow, bail};
+use anyhow::{Context, Result, anyhow, bail, ensure};
 use camino::Utf8PathBuf;
 use tracing::debug;
 
@@ -147,7 +147,25 @@ impl GitRemote {
             .with_context(|| format!("failed to clone into: {fs}"))?;
         let rev = match locked_rev {
             Some(rev) if db.contains(rev) => rev,
-            _ => db.resolve(reference)?,
+            // The fetch has succeeded, so the remote is reachable, but it does not contain
+            // the locked revision anymore. This happens when upstream history has been rewritten,
+            // for example by a force push followed by garbage collection.
+            Some(rev) => {
+                let resolved = db.resolve(reference).with_context(|| {
+                    format!(
+                        "locked revision `{rev}` is no longer reachable in git repository {self}\n\
+                        help: run `scarb update` to lock the dependency to an existing revision"
+                    )
+                })?;
+                config.ui().warn(format!(
+                    "locked revision `{rev}` is no longer reachable in git repository {self}, \
+                    it may have been removed by a force push\n\
+                    note: falling back to revision `{resolved}` of the requested reference, \
+                    the lockfile will be updated accordingly"
+                ));
+                resolved
+            }
+            None => db.resolve(reference)?,
         };
         Ok((db, rev))
     }
@@ -180,7 +198,11 @@ impl GitDatabase {
     #[tracing::instrument(level = "trace", skip(config))]
     fn fetch(&self, url: &str, reference: &GitReference, config: &Config) -> Result<()> {
         if !config.network_allowed() {
-            bail!("cannot fetch from `{}` in offline mode", self.remote);
+            bail!(
+                "cannot fetch from `{}` in {} mode",
+                self.remote,
+                config.offline_mode_name()
+            );
         }
 
         let (refspecs, fetch_tags) = collect_refspecs(reference);
@@ -257,12 +279,13 @@ impl GitDatabase {
                 .detach()
                 .into()),
 
-            Rev(rev) => Ok(repo
-                .rev_parse_single(rev.as_str())?
-                .object()?
-                .peel_tags_to_end()?
-                .id
-                .into()),
+            Rev(rev) => {
+                let oid = repo
+                    .rev_parse_single(rev.as_str())
+                    .with_context(|| format!("failed to find revision `{rev}`"))?
+                    .detach();
+                self.peel_to_commit(oid.into())
+            }
 
             DefaultBranch => Ok(repo
                 .find_reference("refs/remotes/origin/HEAD")?
@@ -272,6 +295,17 @@ impl GitDatabase {
         }
     }
 
+    /// Peel the object pointed by `rev` (which may be an annotated tag) to the commit it targets.
+    pub fn peel_to_commit(&self, rev: Rev) -> Result<Rev> {
+        Ok(self
+            .repo
+            .find_object(rev.oid)?
+            .peel_to_commit()
+            .with_context(|| format!("revision `{rev}` does not point to a commit"))?
+            .id
+            .into())
+    }
+
     pub fn short_id_of(&self, rev: Rev) -> Result<String> {
         let obj = self.repo.find_object(rev.oid)?;
         Ok(obj.id().shorten_or_id().to_string())
@@ -380,6 +414,48 @@ impl PackageRepository {
         Ok(output.stdout.is_empty())
     }
 
+    /// List files staged for commit in the repository index, as absolute paths.
+    ///
+    /// Files deleted in the index are omitted.
+    pub fn staged_files(&self) -> Result<Vec<PathBuf>> {
+        self.diff_files(&["--cached"])
+            .context("failed to list staged files")
+    }
+
+    /// List files changed in the working tree relative to the `rev`, as absolute paths.
+    ///
+    /// Files deleted since the `rev` are omitted.
+    /// Revisions starting with `-` are rejected, as Git would interpret them as options.
+    pub fn changed_files_since(&self, rev: &str) -> Result<Vec<PathBuf>> {
+        ensure!(
+            !rev.starts_with('-'),
+            "invalid revision `{rev}`, revisions must not start with `-`"
+        );
+        self.diff_files(&[rev, "--"])
+            .with_context(|| format!("failed to list files changed since `{rev}`"))
+    }
+
+    fn diff_files(&self, args: &[&str]) -> Result<Vec<PathBuf>> {
+        let work_dir = self.work_dir()?;
+        let output = git_command()
+            .current_dir(work_dir)
+            .args(["diff", "--name-only", "--diff-filter=d", "-z"])
+            .args(args)
+            .output()?;
+        ensure!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+
+        Ok(output
+            .stdout
+            .split(|byte| *byte == 0)
+            .filter(|path| !path.is_empty())
+            .map(|path| work_dir.join(String::from_utf8_lossy(path).as_ref()))
+            .collect())
+    }
+
     pub fn head_rev_hash(&self) -> Result<String> {
         Ok(self.repo.rev_parse_single("HEAD")?.to_string())
     }
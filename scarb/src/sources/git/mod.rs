@@ -111,10 +111,13 @@ impl<'c> GitSource<'c> {
                 // and there is a functional database, then try to resolve our reference
                 // with the preexisting repository.
                 (Some(db), None) if !config.network_allowed() => {
-                    let rev = db.resolve(&requested_reference).context(
-                        "failed to lookup reference in preexisting repository, and \
-                        cannot check for updates in offline mode (--offline)",
-                    )?;
+                    let rev = db.resolve(&requested_reference).with_context(|| {
+                        let mode = config.offline_mode_name();
+                        format!(
+                            "failed to lookup reference in preexisting repository, and \
+                            cannot check for updates in {mode} mode (--{mode})"
+                        )
+                    })?;
                     (db, rev)
                 }
 
@@ -131,6 +134,10 @@ impl<'c> GitSource<'c> {
                 }
             };
 
+            // Locked revisions may come from lockfiles pointing at annotated tag objects,
+            // always record the commit they target.
+            let actual_rev = db.peel_to_commit(actual_rev)?;
+
             let checkout_fs = git_fs
                 .child("checkouts")
                 .into_child(&remote_ident)
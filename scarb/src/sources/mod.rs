@@ -2,8 +2,10 @@ pub use git::*;
 pub use path::*;
 pub use registry::*;
 pub use standard_lib::*;
+pub use vendored::*;
 
 mod git;
 mod path;
 mod registry;
 mod standard_lib;
+mod vendored;
@@ -1,4 +1,6 @@
+use std::collections::{HashMap, HashSet};
 use std::fmt;
+use std::sync::Mutex;
 
 use anyhow::{Context, Result, anyhow, bail};
 use async_trait::async_trait;
@@ -6,6 +8,8 @@ use tracing::trace;
 
 use scarb_ui::components::Status;
 
+use crate::core::errors::PackageNotFoundError;
+use crate::core::registry::RegistryMirrors;
 use crate::core::registry::client::RegistryClient;
 use crate::core::registry::client::cache::RegistryClientCache;
 use crate::core::registry::client::http::HttpRegistryClient;
@@ -19,10 +23,12 @@ use crate::core::{
 };
 use crate::flock::FileLockGuard;
 use crate::sources::PathSource;
-use std::collections::HashSet;
 
 pub struct RegistrySource<'c> {
     source_id: SourceId,
+    /// Source of packages provided by this registry, which differs from `source_id` for
+    /// pure mirrors of another registry.
+    package_source_id: SourceId,
     config: &'c Config,
     client: RegistryClientCache<'c>,
     package_sources: PackageSourceStore<'c>,
@@ -42,6 +48,7 @@ impl<'c> RegistrySource<'c> {
 
         Ok(Self {
             source_id,
+            package_source_id: source_id,
             config,
             client,
             package_sources,
@@ -49,6 +56,14 @@ impl<'c> RegistrySource<'c> {
         })
     }
 
+    /// Provide packages of this registry as if they came from the `package_source_id` registry.
+    pub fn with_package_source_id(self, package_source_id: SourceId) -> Self {
+        Self {
+            package_source_id,
+            ..self
+        }
+    }
+
     pub fn create_client(
         source_id: SourceId,
         config: &'c Config,
@@ -93,7 +108,7 @@ impl Source for RegistrySource<'_> {
             let package_id = PackageId::new(
                 dependency.name.clone(),
                 record.version.clone(),
-                self.source_id,
+                self.package_source_id,
             );
 
             if record.yanked && !self.yanked_whitelist.contains(&package_id) {
@@ -106,7 +121,7 @@ impl Source for RegistrySource<'_> {
                     ManifestDependency::builder()
                         .name(index_dep.name.clone())
                         .version_req(DependencyVersionReq::from(index_dep.req.clone()))
-                        .source_id(self.source_id)
+                        .source_id(self.package_source_id)
                         .build()
                 })
                 .collect();
@@ -166,7 +181,7 @@ impl RegistrySource<'_> {
         // `extract` drops the archive internally and thus handles file unlocking.
         let path = self.package_sources.extract(id, archive).await?;
 
-        let path_source = PathSource::recursive_at(&path, self.source_id, self.config);
+        let path_source = PathSource::recursive_at(&path, self.package_source_id, self.config);
         let mut package = path_source.download(id).await?;
 
         package.manifest_mut().summary.set_checksum(checksum);
@@ -182,3 +197,110 @@ impl fmt::Debug for RegistrySource<'_> {
             .finish_non_exhaustive()
     }
 }
+
+/// A registry which consults its mirrors, in order, for packages it does not have.
+pub struct MirroredRegistrySource<'c> {
+    source_id: SourceId,
+    config: &'c Config,
+    primary: RegistrySource<'c>,
+    mirrors: Vec<RegistrySource<'c>>,
+    /// Packages which keep the identity of this registry, but are downloaded from a mirror.
+    mirrored_packages: Mutex<HashMap<PackageId, usize>>,
+}
+
+impl<'c> MirroredRegistrySource<'c> {
+    pub fn new(
+        source_id: SourceId,
+        primary: RegistrySource<'c>,
+        mirrors: &RegistryMirrors,
+        config: &'c Config,
+        yanked_whitelist: &HashSet<PackageId>,
+    ) -> Result<Self> {
+        let mirrors = mirrors
+            .mirrors
+            .iter()
+            .map(|&mirror_id| {
+                let mirror = RegistrySource::new(mirror_id, config, yanked_whitelist)?;
+                Ok(if mirrors.pure {
+                    mirror.with_package_source_id(source_id)
+                } else {
+                    mirror
+                })
+            })
+            .collect::<Result<_>>()?;
+        Ok(Self {
+            source_id,
+            config,
+            primary,
+            mirrors,
+            mirrored_packages: Mutex::new(HashMap::new()),
+        })
+    }
+}
+
+/// Checks whether the registry did not find the queried package in its index.
+fn is_package_not_found(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<PackageNotFoundError>().is_some()
+}
+
+#[async_trait]
+impl Source for MirroredRegistrySource<'_> {
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn query(&self, dependency: &ManifestDependency) -> Result<Vec<Summary>> {
+        let err = match self.primary.query(dependency).await {
+            Err(err) if is_package_not_found(&err) => err,
+            result => return result,
+        };
+
+        for (index, mirror) in self.mirrors.iter().enumerate() {
+            trace!(
+                "`{dependency}` not found in registry: {}, trying mirror: {}",
+                self.source_id, mirror.source_id
+            );
+            let summaries = match mirror.query(dependency).await {
+                Err(err) if is_package_not_found(&err) => continue,
+                result => result?,
+            };
+
+            self.config.ui().print(Status::new(
+                "Mirrored",
+                &format!(
+                    "{} from registry: {}",
+                    dependency.name, mirror.source_id.url
+                ),
+            ));
+            let mut mirrored_packages = self.mirrored_packages.lock().unwrap();
+            for summary in &summaries {
+                if summary.package_id.source_id == self.source_id {
+                    mirrored_packages.insert(summary.package_id, index);
+                }
+            }
+            return Ok(summaries);
+        }
+
+        Err(err).with_context(|| {
+            format!(
+                "`{}` not found in any mirror of registry: {}",
+                dependency.name, self.source_id
+            )
+        })
+    }
+
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn download(&self, id: PackageId) -> Result<Package> {
+        let mirror = self.mirrored_packages.lock().unwrap().get(&id).copied();
+        match mirror {
+            Some(index) => self.mirrors[index].download(id).await,
+            None => self.primary.download(id).await,
+        }
+    }
+}
+
+impl fmt::Debug for MirroredRegistrySource<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("MirroredRegistrySource")
+            .field("source", &self.source_id.to_string())
+            .field("mirrors", &self.mirrors)
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,73 @@
+use std::fmt;
+
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use camino::Utf8Path;
+
+use crate::core::source::Source;
+use crate::core::{Checksum, Config, ManifestDependency, Package, PackageId, SourceId, Summary};
+use crate::internal::fsx;
+use crate::sources::PathSource;
+
+/// Name of the file in a vendored package directory, holding the checksum of the package.
+pub const VENDORED_CHECKSUM_FILE_NAME: &str = ".scarb-checksum";
+
+/// Serves packages vendored into a local directory, in place of a replaced Git or registry source.
+///
+/// Loaded packages keep the [`SourceId`] of the replaced source, so that lockfiles generated
+/// against the original source remain valid.
+/// If a vendored package directory contains a [`VENDORED_CHECKSUM_FILE_NAME`] file, its contents
+/// are used as the package checksum.
+pub struct VendoredSource<'c> {
+    source_id: SourceId,
+    path_source: PathSource<'c>,
+}
+
+impl<'c> VendoredSource<'c> {
+    pub fn new(directory: &Utf8Path, source_id: SourceId, config: &'c Config) -> Self {
+        Self {
+            source_id,
+            path_source: PathSource::recursive_at(directory, source_id, config),
+        }
+    }
+
+    async fn load_package(&self, id: PackageId) -> Result<Package> {
+        let mut package = self.path_source.download(id).await?;
+
+        let checksum_path = package.root().join(VENDORED_CHECKSUM_FILE_NAME);
+        if checksum_path.exists() {
+            let checksum = fsx::read_to_string(&checksum_path)?;
+            let checksum = Checksum::parse(checksum.trim())
+                .with_context(|| format!("failed to parse checksum file: {checksum_path}"))?;
+            package.manifest_mut().summary.set_checksum(checksum);
+        }
+
+        Ok(package)
+    }
+}
+
+#[async_trait]
+impl Source for VendoredSource<'_> {
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn query(&self, dependency: &ManifestDependency) -> Result<Vec<Summary>> {
+        let mut summaries = Vec::new();
+        for summary in self.path_source.query(dependency).await? {
+            let package = self.load_package(summary.package_id).await?;
+            summaries.push(package.manifest.summary.clone());
+        }
+        Ok(summaries)
+    }
+
+    #[tracing::instrument(level = "trace", skip(self))]
+    async fn download(&self, id: PackageId) -> Result<Package> {
+        self.load_package(id).await
+    }
+}
+
+impl fmt::Debug for VendoredSource<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("VendoredSource")
+            .field("source", &self.source_id.to_string())
+            .finish_non_exhaustive()
+    }
+}
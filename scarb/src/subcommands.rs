@@ -6,6 +6,7 @@ use std::ffi::OsString;
 
 pub const EXTERNAL_CMD_PREFIX: &str = "scarb-";
 pub const SCARB_MANIFEST_PATH_ENV: &str = "SCARB_MANIFEST_PATH";
+pub const SCARB_IGNORE_CAIRO_VERSION_ENV: &str = "SCARB_IGNORE_CAIRO_VERSION";
 
 /// Defines env vars passed to external subcommands.
 pub fn get_env_vars(
@@ -0,0 +1,169 @@
+use assert_fs::TempDir;
+use assert_fs::prelude::*;
+use indoc::indoc;
+use serde_json::json;
+
+use scarb_test_support::command::Scarb;
+
+fn write_contract_class(t: &TempDir, path: &str, abi: serde_json::Value) {
+    let class = json!({
+        "sierra_program": [],
+        "contract_class_version": "0.1.0",
+        "entry_points_by_type": {
+            "EXTERNAL": [],
+            "L1_HANDLER": [],
+            "CONSTRUCTOR": []
+        },
+        "abi": abi
+    });
+    t.child(path)
+        .write_str(&serde_json::to_string(&class).unwrap())
+        .unwrap();
+}
+
+fn balance_abi(get_balance_output: &str) -> serde_json::Value {
+    json!([
+        {
+            "type": "impl",
+            "name": "HelloStarknetImpl",
+            "interface_name": "hello::IHelloStarknet"
+        },
+        {
+            "type": "interface",
+            "name": "hello::IHelloStarknet",
+            "items": [
+                {
+                    "type": "function",
+                    "name": "increase_balance",
+                    "inputs": [{ "name": "amount", "type": "core::felt252" }],
+                    "outputs": [],
+                    "state_mutability": "external"
+                },
+                {
+                    "type": "function",
+                    "name": "get_balance",
+                    "inputs": [],
+                    "outputs": [{ "type": get_balance_output }],
+                    "state_mutability": "view"
+                }
+            ]
+        },
+        {
+            "type": "constructor",
+            "name": "constructor",
+            "inputs": [{ "name": "initial", "type": "core::felt252" }]
+        }
+    ])
+}
+
+#[test]
+fn no_changes() {
+    let t = TempDir::new().unwrap();
+    write_contract_class(&t, "old.json", balance_abi("core::felt252"));
+    write_contract_class(&t, "new.json", balance_abi("core::felt252"));
+
+    Scarb::quick_snapbox()
+        .args(["abi-diff", "old.json", "new.json"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            no entrypoint changes
+        "#});
+}
+
+#[test]
+fn added_entrypoints_are_not_breaking() {
+    let t = TempDir::new().unwrap();
+    write_contract_class(&t, "old.json", balance_abi("core::felt252"));
+    let mut abi = balance_abi("core::felt252");
+    abi.as_array_mut().unwrap().push(json!({
+        "type": "l1_handler",
+        "name": "deposit",
+        "inputs": [{ "name": "from_address", "type": "core::felt252" }],
+        "outputs": [],
+        "state_mutability": "external"
+    }));
+    write_contract_class(&t, "new.json", abi);
+
+    Scarb::quick_snapbox()
+        .args(["abi-diff", "old.json", "new.json"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            added l1_handler `deposit(from_address: core::felt252) [external]`
+        "#});
+}
+
+#[test]
+fn removed_and_changed_entrypoints_are_breaking() {
+    let t = TempDir::new().unwrap();
+    write_contract_class(&t, "old.json", balance_abi("core::felt252"));
+    let mut abi = balance_abi("core::integer::u128");
+    abi.as_array_mut().unwrap().pop();
+    write_contract_class(&t, "new.json", abi);
+
+    Scarb::quick_snapbox()
+        .args(["abi-diff", "old.json", "new.json"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            removed constructor `constructor(initial: core::felt252)`
+            changed function `get_balance() -> core::felt252 [view]` to `get_balance() -> core::integer::u128 [view]`
+            error: breaking changes detected in contract interface: new.json
+        "#});
+}
+
+#[test]
+fn renamed_parameters_are_not_changes() {
+    let t = TempDir::new().unwrap();
+    write_contract_class(&t, "old.json", balance_abi("core::felt252"));
+    let mut abi = balance_abi("core::felt252");
+    abi[1]["items"][0]["inputs"][0]["name"] = json!("value");
+    abi[2]["inputs"][0]["name"] = json!("initial_balance");
+    write_contract_class(&t, "new.json", abi);
+
+    Scarb::quick_snapbox()
+        .args(["abi-diff", "old.json", "new.json"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            no entrypoint changes
+        "#});
+}
+
+#[test]
+fn json_output() {
+    let t = TempDir::new().unwrap();
+    write_contract_class(&t, "old.json", balance_abi("core::felt252"));
+    write_contract_class(&t, "new.json", balance_abi("core::integer::u128"));
+
+    Scarb::quick_snapbox()
+        .args(["--json", "abi-diff", "old.json", "new.json"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            {"added":[],"removed":[],"changed":[{"kind":"function","name":"get_balance","old_signature":"() -> core::felt252 [view]","new_signature":"() -> core::integer::u128 [view]"}]}
+            {"type":"error","message":"breaking changes detected in contract interface: new.json"}
+        "#});
+}
+
+#[test]
+fn missing_abi() {
+    let t = TempDir::new().unwrap();
+    write_contract_class(&t, "old.json", serde_json::Value::Null);
+    write_contract_class(&t, "new.json", balance_abi("core::felt252"));
+
+    Scarb::quick_snapbox()
+        .args(["abi-diff", "old.json", "new.json"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: contract class does not contain ABI: old.json
+        "#});
+}
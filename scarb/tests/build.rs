@@ -116,6 +116,27 @@ fn compile_with_syntax_error_json() {
         "#});
 }
 
+#[test]
+fn compile_with_short_message_format() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .lib_cairo("not_a_keyword")
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["check", "--message-format", "short"])
+        .current_dir(&t)
+        .assert()
+        .code(1)
+        .stdout_matches(indoc! {r#"
+                Checking hello v0.1.0 ([..]Scarb.toml)
+            [..]/lib.cairo:1:14: error: Skipped tokens. Expected: Const/Enum/ExternFunction/ExternType/Function/Impl/InlineMacro/Module/Struct/Trait/TypeAlias/Use or an attribute.
+            error: could not check `hello` due to previous error
+        "#});
+}
+
 #[test]
 fn compile_without_manifest() {
     let t = TempDir::new().unwrap();
@@ -1058,7 +1079,7 @@ fn error_codes_shown_in_json_output() {
         .stdout_matches(indoc! {r#"
             {"status":"compiling","message":"[..] v1.0.0 ([..]Scarb.toml)"}
             {"type":"warn","message":"Unused variable. Consider ignoring by prefixing with `_`./n --> [..]lib.cairo:2:9/n    let a = 41;/n        ^/n","code":"E0001"}
-            {"status":"finished","message":"`dev` profile target(s) in [..]"}
+            {"status":"finished","message":"`dev` profile target(s) in [..]","profile":"dev","elapsed_ms":[..],"compilation_units":1,"artifacts":1}
         "#});
 }
 
@@ -1081,6 +1102,75 @@ fn can_compile_no_core_package() {
         .success();
 }
 
+#[test]
+fn no_std_package_does_not_depend_on_core() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .manifest_package_extra("no-std = true")
+        .lib_cairo("fn f() {}")
+        .build(&t);
+    let metadata = Scarb::quick_snapbox()
+        .args(["--json", "metadata", "--format-version", "1"])
+        .current_dir(&t)
+        .stdout_json::<Metadata>();
+    assert!(metadata.packages.iter().all(|p| p.name != "core"));
+    assert!(
+        metadata
+            .compilation_units
+            .iter()
+            .all(|cu| cu.components.iter().all(|c| c.name != "core"))
+    );
+}
+
+#[test]
+fn no_std_package_referencing_std_fails() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .manifest_package_extra("no-std = true")
+        .lib_cairo(indoc! {r#"
+            use core::array::ArrayTrait;
+
+            fn f() {}
+        "#})
+        .build(&t);
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling hello v1.0.0 ([..]Scarb.toml)
+            ...
+            error: could not compile `hello` due to previous error
+            note: std not available in no-std package `hello`
+        "#});
+}
+
+#[test]
+fn no_std_package_unrelated_error_has_no_std_note() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .manifest_package_extra("no-std = true")
+        .lib_cairo(indoc! {r#"
+            fn f() {}
+            fn f() {}
+        "#})
+        .build(&t);
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling hello v1.0.0 ([..]Scarb.toml)
+            ...
+            error: could not compile `hello` due to previous error
+        "#});
+}
+
 #[test]
 fn gas_enabled_by_default() {
     let t = TempDir::new().unwrap();
@@ -1411,6 +1501,49 @@ fn add_statements_code_locations_debug_info() {
     );
 }
 
+#[test]
+fn debug_flag_enables_statements_debug_info() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .lib_cairo(indoc! {r#"
+            fn main() -> felt252 {
+                42
+            }
+        "#})
+        .manifest_extra(indoc! {r#"
+            [profile.dev.cairo]
+            debug = true
+            unstable-add-statements-functions-debug-info = false
+        "#})
+        .build(&t);
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    let lib_sierra_string = t.child("target/dev/hello.sierra.json").read_to_string();
+    let lib_sierra = serde_json::from_str::<VersionedProgram>(&lib_sierra_string).unwrap();
+    let debug_info = lib_sierra
+        .into_v1()
+        .unwrap()
+        .debug_info
+        .expect("Expected debug info to exist");
+    assert!(
+        debug_info
+            .annotations
+            .contains_key("github.com/software-mansion/cairo-coverage"),
+        "Expected cairo-coverage annotations to exist"
+    );
+    assert!(
+        !debug_info
+            .annotations
+            .contains_key("github.com/software-mansion/cairo-profiler"),
+        "Expected cairo-profiler annotations to be overridden"
+    );
+}
+
 #[test]
 fn add_statements_functions_debug_info_to_tests() {
     let t = TempDir::new().unwrap();
@@ -1906,3 +2039,99 @@ fn can_use_dependency_twice_with_different_kinds() {
             [..]Finished `dev` profile target(s) in [..]
         "#});
 }
+
+#[test]
+fn time_report() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .arg("--time-report")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..]Compiling hello v0.1.0 ([..]Scarb.toml)
+            [..]Finished `dev` profile target(s) in [..]
+            time report:
+              resolve     [..]s
+              db-build    [..]s
+              sierra-gen  [..]s
+              serialize   [..]s
+              total       [..]s
+        "#});
+}
+
+#[test]
+fn inlining_strategy_override() {
+    let code = indoc! {r#"
+        fn add(a: felt252, b: felt252) -> felt252 {
+            a + b
+        }
+
+        fn main() -> felt252 {
+            add(1, 2)
+        }
+    "#};
+    let build = |manifest_extra: &str, args: &[&str]| {
+        let t = TempDir::new().unwrap();
+        ProjectBuilder::start()
+            .name("hello")
+            .manifest_extra(manifest_extra)
+            .lib_cairo(code)
+            .build(&t);
+        Scarb::quick_snapbox()
+            .arg("build")
+            .args(args)
+            .current_dir(&t)
+            .assert()
+            .success();
+        fs::read_to_string(t.child("target/dev/hello.sierra.json")).unwrap()
+    };
+
+    let from_manifest = build(
+        indoc! {r#"
+            [cairo]
+            inlining-strategy = "avoid"
+        "#},
+        &[],
+    );
+    let from_cli = build("", &["--inlining-strategy", "avoid"]);
+    assert_eq!(from_cli, from_manifest);
+
+    let from_manifest = build(
+        indoc! {r#"
+            [cairo]
+            inlining-strategy = 10
+        "#},
+        &[],
+    );
+    let from_cli = build(
+        indoc! {r#"
+            [cairo]
+            inlining-strategy = "avoid"
+        "#},
+        &["--inlining-strategy", "small=10"],
+    );
+    assert_eq!(from_cli, from_manifest);
+}
+
+#[test]
+fn inlining_strategy_weight_must_be_positive() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start().name("hello").build(&t);
+    Scarb::quick_snapbox()
+        .args(["build", "--inlining-strategy", "small=0"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stderr_matches(indoc! {r#"
+            error: invalid value 'small=0' for '--inlining-strategy <STRATEGY>': inlining weight must be a positive integer
+
+            For more information, try '--help'.
+        "#});
+}
@@ -88,6 +88,96 @@ fn compile_starknet_contract() {
         ));
 }
 
+#[test]
+fn compile_starknet_contract_to_target_dir_from_env() {
+    let t = assert_fs::TempDir::new().unwrap();
+    let hello = t.child("hello");
+    ProjectBuilder::start()
+        .name("hello")
+        .edition("2023_01")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            casm = true
+        "#})
+        .dep_starknet()
+        .lib_cairo(BALANCE_CONTRACT)
+        .build(&hello);
+
+    Scarb::quick_snapbox()
+        .env("SCARB_TARGET_DIR", "../out")
+        .arg("build")
+        .current_dir(&hello)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling hello v0.1.0 ([..])
+        [..]  Finished `dev` profile target(s) in [..]
+        "#});
+
+    hello
+        .child("target")
+        .assert(predicates::path::exists().not());
+    assert_eq!(
+        t.child("out/dev").files(),
+        vec![
+            ".fingerprint",
+            "hello.starknet_artifacts.json",
+            "hello_Balance.compiled_contract_class.json",
+            "hello_Balance.contract_class.json",
+            "incremental",
+        ]
+    );
+    t.child("out/dev/hello.starknet_artifacts.json")
+        .assert(predicates::str::contains(
+            r#""sierra":"hello_Balance.contract_class.json","casm":"hello_Balance.compiled_contract_class.json""#,
+        ));
+}
+
+#[test]
+fn compile_starknet_contract_to_sierra_text() {
+    let t = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .edition("2023_01")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            emit-sierra-text = true
+        "#})
+        .dep_starknet()
+        .lib_cairo(BALANCE_CONTRACT)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling hello v0.1.0 ([..])
+        [..]  Finished `dev` profile target(s) in [..]
+        "#});
+
+    assert_eq!(
+        t.child("target/dev").files(),
+        vec![
+            ".fingerprint",
+            "hello.starknet_artifacts.json",
+            "hello_Balance.contract_class.json",
+            "hello_Balance.contract_class.sierra",
+            "incremental",
+        ]
+    );
+
+    t.child("target/dev/hello_Balance.contract_class.sierra")
+        .assert(predicates::str::contains("hello::Balance::"));
+    t.child("target/dev/hello.starknet_artifacts.json")
+        .assert(predicates::str::contains(
+            r#""sierra_text":"hello_Balance.contract_class.sierra""#,
+        ));
+}
+
 #[test]
 fn compile_starknet_contract_to_casm() {
     let t = assert_fs::TempDir::new().unwrap();
@@ -128,6 +218,124 @@ fn compile_starknet_contract_to_casm() {
         .assert_is_json::<CasmContractClass>();
 }
 
+#[test]
+fn compile_starknet_contract_to_casm_with_options() {
+    let t = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .edition("2023_01")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            sierra = false
+            casm = { max-bytecode-size = 1000000, add-pythonic-hints = true }
+        "#})
+        .dep_starknet()
+        .lib_cairo(BALANCE_CONTRACT)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling hello v0.1.0 ([..])
+        [..]  Finished `dev` profile target(s) in [..]
+        "#});
+
+    let casm_class = t
+        .child("target/dev/hello_Balance.compiled_contract_class.json")
+        .assert_is_json::<CasmContractClass>();
+    assert!(casm_class.pythonic_hints.is_some());
+}
+
+#[test]
+fn casm_max_bytecode_size_exceeded() {
+    let t = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .edition("2023_01")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            casm = { max-bytecode-size = 10 }
+        "#})
+        .dep_starknet()
+        .lib_cairo(BALANCE_CONTRACT)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling hello v0.1.0 ([..])
+        error: Balance: failed to compile Sierra contract to CASM
+
+        Caused by:
+            [..]
+        error: could not compile `hello` due to previous error
+        "#});
+}
+
+#[test]
+fn casm_options_reject_gas_settings() {
+    let t = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .edition("2023_01")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            casm = { gas-usage-check = false }
+        "#})
+        .dep_starknet()
+        .lib_cairo(BALANCE_CONTRACT)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling hello v0.1.0 ([..])
+        ...
+        [..]unknown field `gas-usage-check`, expected `max-bytecode-size` or `add-pythonic-hints`[..]
+        ...
+        "#});
+}
+
+#[test]
+fn casm_options_conflict_with_legacy_property() {
+    let t = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .edition("2023_01")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            casm = { add-pythonic-hints = false }
+            casm-add-pythonic-hints = true
+        "#})
+        .dep_starknet()
+        .lib_cairo(BALANCE_CONTRACT)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling hello v0.1.0 ([..])
+        error: `casm-add-pythonic-hints` and `casm.add-pythonic-hints` target properties cannot be used together, use `casm.add-pythonic-hints` only
+        error: could not compile `hello` due to previous error
+        "#});
+}
+
 #[test]
 fn compile_many_contracts() {
     let t = assert_fs::TempDir::new().unwrap();
@@ -250,85 +458,82 @@ fn compile_same_name_contracts() {
 }
 
 #[test]
-fn casm_add_pythonic_hints() {
+fn compile_many_contracts_to_bundle() {
     let t = assert_fs::TempDir::new().unwrap();
     ProjectBuilder::start()
         .name("hello")
-        .edition("2023_01")
         .version("0.1.0")
         .manifest_extra(indoc! {r#"
             [[target.starknet-contract]]
-            sierra = false
             casm = true
-            casm-add-pythonic-hints = true
+            bundle = true
         "#})
         .dep_starknet()
-        .lib_cairo(BALANCE_CONTRACT)
+        .lib_cairo(indoc! {r#"
+            mod balance;
+            mod forty_two;
+        "#})
+        .src("src/balance.cairo", BALANCE_CONTRACT)
+        .src("src/forty_two.cairo", FORTY_TWO_CONTRACT)
         .build(&t);
 
     Scarb::quick_snapbox()
         .arg("build")
         .current_dir(&t)
         .assert()
-        .success()
-        .stdout_matches(indoc! {r#"
-        [..] Compiling hello v0.1.0 ([..])
-        [..]  Finished `dev` profile target(s) in [..]
-        "#});
+        .success();
 
-    t.child("target/dev/hello_Balance.compiled_contract_class.json")
-        .assert_is_json::<CasmContractClass>();
+    assert_eq!(
+        t.child("target/dev").files(),
+        vec![
+            ".fingerprint",
+            "hello.contracts.bundle.json",
+            "hello.starknet_artifacts.json",
+            "hello_Balance.compiled_contract_class.json",
+            "hello_Balance.contract_class.json",
+            "hello_FortyTwo.compiled_contract_class.json",
+            "hello_FortyTwo.contract_class.json",
+            "incremental",
+        ]
+    );
+
+    let bundle = t
+        .child("target/dev/hello.contracts.bundle.json")
+        .assert_is_json::<serde_json::Value>();
+    let bundle = bundle.as_object().unwrap();
+    assert_eq!(bundle.keys().collect_vec(), vec!["Balance", "FortyTwo"]);
+    for entry in bundle.values() {
+        serde_json::from_value::<ContractClass>(entry["sierra"].clone()).unwrap();
+        serde_json::from_value::<CasmContractClass>(entry["casm"].clone()).unwrap();
+    }
+
+    let artifacts = t
+        .child("target/dev/hello.starknet_artifacts.json")
+        .assert_is_json::<serde_json::Value>();
+    assert_eq!(artifacts["bundle"], "hello.contracts.bundle.json");
+    assert_eq!(
+        artifacts["contracts"][0]["artifacts"]["sierra"],
+        "hello_Balance.contract_class.json"
+    );
 }
 
 #[test]
-fn compile_starknet_contract_only_with_cfg() {
+fn compile_same_name_contracts_to_bundle_only() {
     let t = assert_fs::TempDir::new().unwrap();
     ProjectBuilder::start()
         .name("hello")
-        .edition("2023_01")
         .version("0.1.0")
         .manifest_extra(indoc! {r#"
-            [lib]
-
             [[target.starknet-contract]]
+            bundle-only = true
         "#})
         .dep_starknet()
         .lib_cairo(indoc! {r#"
-            #[cfg(target: 'starknet-contract')]
-            #[starknet::interface]
-            trait IBalance<T> {
-                // Returns the current balance.
-                fn get(self: @T) -> u128;
-                // Increases the balance by the given amount.
-                fn increase(ref self: T, a: u128);
-            }
-
-            #[cfg(target: 'starknet-contract')]
-            #[starknet::contract]
-            mod Balance {
-                use traits::Into;
-
-                #[storage]
-                struct Storage {
-                    value: u128,
-                }
-
-                #[constructor]
-                fn constructor(ref self: ContractState, value_: u128) {
-                    self.value.write(value_);
-                }
-
-                #[abi(embed_v0)]
-                impl Balance of super::IBalance<ContractState> {
-                    fn get(self: @ContractState) -> u128 {
-                        self.value.read()
-                    }
-                    fn increase(ref self: ContractState, a: u128)  {
-                        self.value.write( self.value.read() + a );
-                    }
-                }
-            }
+            mod forty_two;
+            mod world;
         "#})
+        .src("src/forty_two.cairo", FORTY_TWO_CONTRACT)
+        .src("src/world.cairo", FORTY_TWO_CONTRACT)
         .build(&t);
 
     Scarb::quick_snapbox()
@@ -341,17 +546,194 @@ fn compile_starknet_contract_only_with_cfg() {
         t.child("target/dev").files(),
         vec![
             ".fingerprint",
-            "hello.sierra.json",
+            "hello.contracts.bundle.json",
             "hello.starknet_artifacts.json",
-            "hello_Balance.contract_class.json",
             "incremental",
         ]
     );
 
-    t.child("target/dev/hello.sierra.json")
-        .assert(predicates::str::contains("hello::Balance::balance::read").not());
-
-    t.child("target/dev/hello_Balance.contract_class.json")
+    let bundle = t
+        .child("target/dev/hello.contracts.bundle.json")
+        .assert_is_json::<serde_json::Value>();
+    let bundle = bundle.as_object().unwrap();
+    assert_eq!(
+        bundle.keys().collect_vec(),
+        vec!["hello_forty_two_FortyTwo", "hello_world_FortyTwo"]
+    );
+    for entry in bundle.values() {
+        serde_json::from_value::<ContractClass>(entry["sierra"].clone()).unwrap();
+        assert!(entry.get("casm").is_none());
+    }
+
+    let artifacts = t
+        .child("target/dev/hello.starknet_artifacts.json")
+        .assert_is_json::<serde_json::Value>();
+    assert_eq!(artifacts["bundle"], "hello.contracts.bundle.json");
+    for contract in artifacts["contracts"].as_array().unwrap() {
+        assert!(contract["artifacts"]["sierra"].is_null());
+    }
+}
+
+#[test]
+fn warn_on_contract_class_size_limit() {
+    let t = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            max-contract-class-size = 100
+        "#})
+        .dep_starknet()
+        .lib_cairo(BALANCE_CONTRACT)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling hello v0.1.0 ([..])
+        warn: Contract class size exceeds maximum allowed size on Starknet for contract `Balance`:
+        100 bytes allowed. Actual size (without debug info): [..] bytes.
+
+        [..]  Finished `dev` profile target(s) in [..]
+        "#});
+}
+
+#[test]
+fn deny_contract_class_size_limit() {
+    let t = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            casm = true
+            contract-size-limits-deny = true
+            max-compiled-contract-class-size = 100
+        "#})
+        .dep_starknet()
+        .lib_cairo(BALANCE_CONTRACT)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling hello v0.1.0 ([..])
+        error: Compiled contract class size exceeds maximum allowed size on Starknet for contract `Balance`:
+        100 bytes allowed. Actual size: [..] bytes.
+        error: could not compile `hello` due to previous error
+        "#});
+}
+
+#[test]
+fn casm_add_pythonic_hints() {
+    let t = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .edition("2023_01")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            sierra = false
+            casm = true
+            casm-add-pythonic-hints = true
+        "#})
+        .dep_starknet()
+        .lib_cairo(BALANCE_CONTRACT)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling hello v0.1.0 ([..])
+        [..]  Finished `dev` profile target(s) in [..]
+        "#});
+
+    t.child("target/dev/hello_Balance.compiled_contract_class.json")
+        .assert_is_json::<CasmContractClass>();
+}
+
+#[test]
+fn compile_starknet_contract_only_with_cfg() {
+    let t = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .edition("2023_01")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [lib]
+
+            [[target.starknet-contract]]
+        "#})
+        .dep_starknet()
+        .lib_cairo(indoc! {r#"
+            #[cfg(target: 'starknet-contract')]
+            #[starknet::interface]
+            trait IBalance<T> {
+                // Returns the current balance.
+                fn get(self: @T) -> u128;
+                // Increases the balance by the given amount.
+                fn increase(ref self: T, a: u128);
+            }
+
+            #[cfg(target: 'starknet-contract')]
+            #[starknet::contract]
+            mod Balance {
+                use traits::Into;
+
+                #[storage]
+                struct Storage {
+                    value: u128,
+                }
+
+                #[constructor]
+                fn constructor(ref self: ContractState, value_: u128) {
+                    self.value.write(value_);
+                }
+
+                #[abi(embed_v0)]
+                impl Balance of super::IBalance<ContractState> {
+                    fn get(self: @ContractState) -> u128 {
+                        self.value.read()
+                    }
+                    fn increase(ref self: ContractState, a: u128)  {
+                        self.value.write( self.value.read() + a );
+                    }
+                }
+            }
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    assert_eq!(
+        t.child("target/dev").files(),
+        vec![
+            ".fingerprint",
+            "hello.sierra.json",
+            "hello.starknet_artifacts.json",
+            "hello_Balance.contract_class.json",
+            "incremental",
+        ]
+    );
+
+    t.child("target/dev/hello.sierra.json")
+        .assert(predicates::str::contains("hello::Balance::balance::read").not());
+
+    t.child("target/dev/hello_Balance.contract_class.json")
         .assert_is_json::<ContractClass>();
 }
 
@@ -473,3 +855,658 @@ fn do_not_compile_dep_contracts() {
         .child("target/dev/world_HelloContract.contract_class.json")
         .assert_is_json::<ContractClass>();
 }
+
+#[test]
+#[cfg(unix)]
+fn post_build_command_receives_artifacts() {
+    let t = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            post-build = "sh -c 'cp \"$SCARB_ARTIFACTS\" copied.json && echo \"declaring $0\"'"
+        "#})
+        .dep_starknet()
+        .lib_cairo(BALANCE_CONTRACT)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling hello v0.1.0 ([..])
+        declaring [..]hello.starknet_artifacts.json
+        [..]  Finished `dev` profile target(s) in [..]
+        "#});
+
+    t.child("target/dev/copied.json")
+        .assert(predicates::str::contains(
+            r#""module_path":"hello::Balance""#,
+        ));
+}
+
+#[test]
+#[cfg(unix)]
+fn failing_post_build_command_fails_build() {
+    let t = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            post-build = "sh -c 'echo cannot declare >&2; exit 1'"
+        "#})
+        .dep_starknet()
+        .lib_cairo(BALANCE_CONTRACT)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling hello v0.1.0 ([..])
+        error: cannot declare
+        error: `post-build` command failed: sh -c 'echo cannot declare >&2; exit 1'
+
+        Caused by:
+            process did not exit successfully: exit status: 1
+        error: could not compile `hello` due to previous error
+        "#});
+}
+
+#[test]
+fn report_ignore_patterns_for_written_artifacts() {
+    let t = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra("[[target.starknet-contract]]")
+        .dep_starknet()
+        .lib_cairo(format!("{BALANCE_CONTRACT}\n{FORTY_TWO_CONTRACT}"))
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .arg("--report-ignore")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling hello v0.1.0 ([..])
+        [..]  Finished `dev` profile target(s) in [..]
+        add the following patterns to `.gitignore` to avoid committing build artifacts:
+        target/dev/hello.starknet_artifacts.json
+        target/dev/hello_*.contract_class.json
+        "#});
+}
+
+#[test]
+fn unchanged_contract_classes_are_not_rewritten() {
+    let t = assert_fs::TempDir::new().unwrap();
+    let write_project = |lib_cairo: String| {
+        ProjectBuilder::start()
+            .name("hello")
+            .edition("2023_01")
+            .version("0.1.0")
+            .manifest_extra(indoc! {r#"
+                [[target.starknet-contract]]
+                casm = true
+            "#})
+            .dep_starknet()
+            .lib_cairo(lib_cairo)
+            .build(&t);
+    };
+    write_project(format!("{BALANCE_CONTRACT}\n{FORTY_TWO_CONTRACT}"));
+
+    let build = || {
+        Scarb::quick_snapbox()
+            .arg("build")
+            .current_dir(&t)
+            .assert()
+            .success();
+    };
+    let modified = |file_name: &str| {
+        t.child("target/dev")
+            .child(file_name)
+            .path()
+            .metadata()
+            .unwrap()
+            .modified()
+            .unwrap()
+    };
+    let files = [
+        "hello_Balance.contract_class.json",
+        "hello_Balance.compiled_contract_class.json",
+        "hello_FortyTwo.contract_class.json",
+        "hello_FortyTwo.compiled_contract_class.json",
+    ];
+
+    build();
+    let before = files.map(modified);
+
+    // No-op rebuild does not touch any contract class.
+    build();
+    assert_eq!(files.map(modified), before);
+
+    // Only classes of the changed contract are rewritten.
+    write_project(format!(
+        "{BALANCE_CONTRACT}\n{}",
+        FORTY_TWO_CONTRACT.replace("{ 42 }", "{ 43 }")
+    ));
+    build();
+    let after = files.map(modified);
+    assert_eq!(after[0], before[0]);
+    assert_eq!(after[1], before[1]);
+    assert_ne!(after[2], before[2]);
+    assert_ne!(after[3], before[3]);
+}
+
+#[test]
+#[cfg(not(feature = "cairo-native"))]
+fn native_contracts_require_cairo_native_feature() {
+    let t = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            native = true
+        "#})
+        .dep_starknet()
+        .lib_cairo(BALANCE_CONTRACT)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling hello v0.1.0 ([..])
+        error: native support not built, rebuild Scarb with the `cairo-native` feature enabled to compile contracts to native libraries
+        error: could not compile `hello` due to previous error
+        "#});
+
+    t.child("target/dev/hello_Balance.contract_class.json")
+        .assert(predicates::path::missing());
+}
+
+#[test]
+fn custom_artifacts_manifest_name() {
+    let t = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            artifacts-manifest-name = "artifacts.json"
+        "#})
+        .dep_starknet()
+        .lib_cairo(BALANCE_CONTRACT)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    assert_eq!(
+        t.child("target/dev").files(),
+        vec![
+            ".fingerprint",
+            "artifacts.json",
+            "hello_Balance.contract_class.json",
+            "incremental",
+        ]
+    );
+    t.child("target/dev/artifacts.json")
+        .assert(predicates::str::contains(
+            "hello_Balance.contract_class.json",
+        ));
+}
+
+#[test]
+fn nested_contracts_layout() {
+    let t = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            casm = true
+            emit-sierra-text = true
+            layout = "nested"
+        "#})
+        .dep_starknet()
+        .lib_cairo(BALANCE_CONTRACT)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    assert_eq!(
+        t.child("target/dev").files(),
+        vec![
+            ".fingerprint",
+            "contracts",
+            "hello.starknet_artifacts.json",
+            "incremental",
+        ]
+    );
+    assert_eq!(
+        t.child("target/dev/contracts/hello").files(),
+        vec![
+            "Balance.compiled_contract_class.json",
+            "Balance.contract_class.json",
+            "Balance.contract_class.sierra",
+        ]
+    );
+    t.child("target/dev/contracts/hello/Balance.contract_class.json")
+        .assert_is_json::<ContractClass>();
+    t.child("target/dev/contracts/hello/Balance.compiled_contract_class.json")
+        .assert_is_json::<CasmContractClass>();
+
+    let artifacts = t
+        .child("target/dev/hello.starknet_artifacts.json")
+        .assert_is_json::<serde_json::Value>();
+    assert_eq!(
+        artifacts["contracts"][0]["artifacts"],
+        serde_json::json!({
+            "sierra": "contracts/hello/Balance.contract_class.json",
+            "sierra_text": "contracts/hello/Balance.contract_class.sierra",
+            "casm": "contracts/hello/Balance.compiled_contract_class.json",
+        })
+    );
+}
+
+#[test]
+fn msgpack_artifacts_format() {
+    let t = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            casm = true
+            format = "msgpack"
+        "#})
+        .dep_starknet()
+        .lib_cairo(BALANCE_CONTRACT)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    let artifacts = t
+        .child("target/dev/hello.starknet_artifacts.json")
+        .assert_is_json::<serde_json::Value>();
+    assert_eq!(artifacts["format"], "msgpack");
+    assert_eq!(
+        artifacts["contracts"][0]["artifacts"],
+        serde_json::json!({
+            "sierra": "hello_Balance.contract_class.msgpack",
+            "casm": "hello_Balance.compiled_contract_class.msgpack",
+        })
+    );
+
+    let sierra = std::fs::read(t.child("target/dev/hello_Balance.contract_class.msgpack")).unwrap();
+    let class: ContractClass = rmp_serde::from_slice(&sierra).unwrap();
+    assert!(!class.sierra_program.is_empty());
+    assert!(class.abi.is_some());
+
+    let casm =
+        std::fs::read(t.child("target/dev/hello_Balance.compiled_contract_class.msgpack")).unwrap();
+    let casm_class: CasmContractClass = rmp_serde::from_slice(&casm).unwrap();
+    assert!(!casm_class.bytecode.is_empty());
+}
+
+#[test]
+fn compressed_artifacts() {
+    let t = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            casm = true
+            compress = true
+        "#})
+        .dep_starknet()
+        .lib_cairo(BALANCE_CONTRACT)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling hello v0.1.0 ([..])
+            [..]Compressed hello_Balance.contract_class.json.gz from [..] to [..] bytes
+            [..]Compressed hello_Balance.compiled_contract_class.json.gz from [..] to [..] bytes
+            [..]  Finished `dev` profile target(s) in [..]
+        "#});
+
+    let artifacts = t
+        .child("target/dev/hello.starknet_artifacts.json")
+        .assert_is_json::<serde_json::Value>();
+    assert_eq!(artifacts["encoding"], "gzip");
+    assert_eq!(
+        artifacts["contracts"][0]["artifacts"],
+        serde_json::json!({
+            "sierra": "hello_Balance.contract_class.json.gz",
+            "casm": "hello_Balance.compiled_contract_class.json.gz",
+        })
+    );
+
+    let sierra =
+        std::fs::File::open(t.child("target/dev/hello_Balance.contract_class.json.gz")).unwrap();
+    let class: ContractClass =
+        serde_json::from_reader(flate2::read::GzDecoder::new(sierra)).unwrap();
+    assert!(!class.sierra_program.is_empty());
+
+    let casm =
+        std::fs::File::open(t.child("target/dev/hello_Balance.compiled_contract_class.json.gz"))
+            .unwrap();
+    let casm_class: CasmContractClass =
+        serde_json::from_reader(flate2::read::GzDecoder::new(casm)).unwrap();
+    assert!(!casm_class.bytecode.is_empty());
+}
+
+#[test]
+fn preserve_contracts_order() {
+    let t = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            preserve-contracts-order = true
+        "#})
+        .dep_starknet()
+        .lib_cairo(formatdoc! {r#"
+            {FORTY_TWO_CONTRACT}
+            {HELLO_CONTRACT}
+        "#})
+        .build(&t);
+
+    let build_and_list_contracts = || {
+        Scarb::quick_snapbox()
+            .arg("build")
+            .current_dir(&t)
+            .assert()
+            .success();
+        let artifacts = t
+            .child("target/dev/hello.starknet_artifacts.json")
+            .assert_is_json::<serde_json::Value>();
+        artifacts["contracts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|contract| contract["contract_name"].as_str().unwrap().to_string())
+            .collect_vec()
+    };
+
+    assert_eq!(
+        build_and_list_contracts(),
+        vec!["FortyTwo", "HelloContract"]
+    );
+
+    // `Balance` would be sorted first, but is appended after previously built contracts.
+    t.child("src/lib.cairo")
+        .write_str(&formatdoc! {r#"
+            {BALANCE_CONTRACT}
+            {FORTY_TWO_CONTRACT}
+            {HELLO_CONTRACT}
+        "#})
+        .unwrap();
+    assert_eq!(
+        build_and_list_contracts(),
+        vec!["FortyTwo", "HelloContract", "Balance"]
+    );
+}
+
+#[test]
+fn warn_about_identical_contract_classes() {
+    let build = |manifest_extra: &str| {
+        let t = assert_fs::TempDir::new().unwrap();
+        ProjectBuilder::start()
+            .name("hello")
+            .version("0.1.0")
+            .manifest_extra(manifest_extra)
+            .dep_starknet()
+            .lib_cairo(formatdoc! {r#"
+                {BALANCE_CONTRACT}
+                {}
+            "#, BALANCE_CONTRACT.replace("Balance", "Wallet")})
+            .build(&t);
+        Scarb::quick_snapbox()
+            .arg("build")
+            .current_dir(&t)
+            .assert()
+            .success()
+    };
+
+    build(indoc! {r#"
+        [[target.starknet-contract]]
+        casm = true
+    "#})
+    .stdout_matches(indoc! {r#"
+        [..] Compiling hello v0.1.0 ([..]Scarb.toml)
+        warn: contracts `hello::Balance`, `hello::Wallet` compiled to identical contract classes, set `allow-duplicate-classes = true` if this is intended
+        [..]  Finished `dev` profile target(s) in [..]
+    "#});
+
+    build(indoc! {r#"
+        [[target.starknet-contract]]
+        casm = true
+        allow-duplicate-classes = true
+    "#})
+    .stdout_matches(indoc! {r#"
+        [..] Compiling hello v0.1.0 ([..]Scarb.toml)
+        [..]  Finished `dev` profile target(s) in [..]
+    "#});
+}
+
+#[test]
+fn require_interfaces() {
+    let t = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            require-interfaces = ["IBalance", "hello::IResettable"]
+        "#})
+        .dep_starknet()
+        .lib_cairo(formatdoc! {r#"
+            {BALANCE_CONTRACT}
+
+            #[starknet::interface]
+            trait IResettable<T> {{
+                fn get(self: @T) -> u128;
+                fn reset(ref self: T);
+            }}
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling hello v0.1.0 ([..]Scarb.toml)
+        error: contract `hello::Balance` does not implement interface `hello::IResettable`, missing entrypoints: `reset`
+        error: could not compile `hello` due to previous error
+        "#});
+}
+
+#[test]
+fn invalid_artifacts_manifest_name() {
+    let t = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            artifacts-manifest-name = "artifacts.txt"
+        "#})
+        .dep_starknet()
+        .lib_cairo(BALANCE_CONTRACT)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling hello v0.1.0 ([..])
+        error: artifacts manifest name `artifacts.txt` must be a file name with `.json` extension
+        error: could not compile `hello` due to previous error
+        "#});
+}
+
+#[test]
+fn artifacts_manifest_name_colliding_with_contract_artifact() {
+    let t = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            artifacts-manifest-name = "hello_Balance.contract_class.json"
+        "#})
+        .dep_starknet()
+        .lib_cairo(BALANCE_CONTRACT)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling hello v0.1.0 ([..])
+        error: artifacts manifest name `hello_Balance.contract_class.json` collides with a contract artifact file name
+        error: could not compile `hello` due to previous error
+        "#});
+}
+
+#[test]
+fn verify_deterministic_compilation() {
+    let t = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            casm = true
+        "#})
+        .dep_starknet()
+        .lib_cairo(BALANCE_CONTRACT)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .arg("--verify-deterministic")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling hello v0.1.0 ([..])
+        [..]  Finished `dev` profile target(s) in [..]
+        "#});
+
+    assert_eq!(
+        t.child("target/dev").files(),
+        vec![
+            ".fingerprint",
+            "hello.starknet_artifacts.json",
+            "hello_Balance.compiled_contract_class.json",
+            "hello_Balance.contract_class.json",
+            "incremental",
+        ]
+    );
+}
+
+#[test]
+fn check_rejects_verify_deterministic() {
+    let t = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+        "#})
+        .dep_starknet()
+        .lib_cairo(BALANCE_CONTRACT)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("check")
+        .arg("--verify-deterministic")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: `--verify-deterministic` is not supported by `scarb check`, as it does not compile contracts, use `scarb build --verify-deterministic` instead
+        "#});
+}
+
+#[test]
+fn build_multiple_profiles() {
+    let t = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+        "#})
+        .dep_starknet()
+        .lib_cairo(BALANCE_CONTRACT)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .arg("--profiles")
+        .arg("dev,release")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling hello v0.1.0 ([..])
+        [..]  Finished `dev` profile target(s) in [..]
+        [..] Compiling hello v0.1.0 ([..])
+        [..]  Finished `release` profile target(s) in [..]
+        "#});
+
+    for profile in ["dev", "release"] {
+        let target_dir = t.child("target").child(profile);
+        assert_eq!(
+            target_dir.files(),
+            vec![
+                ".fingerprint",
+                "hello.starknet_artifacts.json",
+                "hello_Balance.contract_class.json",
+                "incremental",
+            ]
+        );
+        target_dir
+            .child("hello.starknet_artifacts.json")
+            .assert_is_json::<serde_json::Value>();
+    }
+}
@@ -8,7 +8,8 @@ use itertools::Itertools;
 use scarb_test_support::command::Scarb;
 use scarb_test_support::contracts::{BALANCE_CONTRACT, FORTY_TWO_CONTRACT, HELLO_CONTRACT};
 use scarb_test_support::fsx::ChildPathEx;
-use scarb_test_support::project_builder::ProjectBuilder;
+use scarb_test_support::project_builder::{DepBuilder, ProjectBuilder};
+use test_case::test_case;
 
 fn compile_dep_test_case(hello: &ChildPath, world: &ChildPath, target_extra: &str) {
     ProjectBuilder::start()
@@ -84,6 +85,124 @@ fn compile_imported_contracts() {
         .assert_is_json::<ContractClass>();
 }
 
+#[test]
+fn list_contracts_without_building() {
+    let t = TempDir::new().unwrap();
+    let hello = t.child("hello");
+    let world = t.child("world");
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [lib]
+            [[target.starknet-contract]]
+        "#})
+        .dep_starknet()
+        .lib_cairo(format!("{BALANCE_CONTRACT}\n{HELLO_CONTRACT}"))
+        .build(&hello);
+    ProjectBuilder::start()
+        .name("world")
+        .version("0.1.0")
+        .dep("hello", &hello)
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            build-external-contracts = ["hello::Balance"]
+        "#})
+        .dep_starknet()
+        .lib_cairo(FORTY_TWO_CONTRACT)
+        .build(&world);
+
+    let config = Scarb::test_config(world.child("Scarb.toml"));
+    let ws = scarb::ops::read_workspace(config.manifest_path(), &config).unwrap();
+    let packages = ws.members().map(|package| package.id).collect();
+    let opts = scarb::ops::ListContractsOpts {
+        features: scarb::ops::FeaturesOpts {
+            features: scarb::ops::FeaturesSelector::Features(Vec::new()),
+            no_default_features: false,
+        },
+        ignore_cairo_version: false,
+    };
+    let contracts = scarb::ops::list_contracts(packages, opts, &ws)
+        .unwrap()
+        .into_iter()
+        .map(|contract| {
+            (
+                contract.package_name.to_string(),
+                contract.contract_name,
+                contract.module_path,
+            )
+        })
+        .collect_vec();
+
+    assert_eq!(
+        contracts,
+        vec![
+            (
+                "hello".to_string(),
+                "Balance".to_string(),
+                "hello::Balance".to_string()
+            ),
+            (
+                "world".to_string(),
+                "FortyTwo".to_string(),
+                "world::FortyTwo".to_string()
+            ),
+        ]
+    );
+    assert!(!world.child("target").path().exists());
+}
+
+#[test]
+fn list_contracts_cli() {
+    let t = TempDir::new().unwrap();
+    let hello = t.child("hello");
+    let world = t.child("world");
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [lib]
+            [[target.starknet-contract]]
+        "#})
+        .dep_starknet()
+        .lib_cairo(format!("{BALANCE_CONTRACT}\n{HELLO_CONTRACT}"))
+        .build(&hello);
+    ProjectBuilder::start()
+        .name("world")
+        .version("0.1.0")
+        .dep("hello", &hello)
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            build-external-contracts = ["hello::*"]
+        "#})
+        .dep_starknet()
+        .lib_cairo(FORTY_TWO_CONTRACT)
+        .build(&world);
+
+    Scarb::quick_snapbox()
+        .args(["build", "--list-contracts"])
+        .current_dir(&world)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            PACKAGE  CONTRACT       MODULE PATH           SELECTOR
+            hello    Balance        hello::Balance        hello::*
+            hello    HelloContract  hello::HelloContract  hello::*
+            world    FortyTwo       world::FortyTwo       -
+        "#});
+
+    Scarb::quick_snapbox()
+        .args(["--json", "build", "--list-contracts"])
+        .current_dir(&world)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [{"package_name":"hello","contract_name":"Balance","module_path":"hello::Balance","selector":"hello::*"},{"package_name":"hello","contract_name":"HelloContract","module_path":"hello::HelloContract","selector":"hello::*"},{"package_name":"world","contract_name":"FortyTwo","module_path":"world::FortyTwo"}]
+        "#});
+
+    assert!(!world.child("target").path().exists());
+}
+
 #[test]
 fn compile_multiple_imported_contracts() {
     let t = TempDir::new().unwrap();
@@ -377,7 +496,7 @@ fn compile_multiple_with_glob_subpath() {
         .dep("y", &y)
         .manifest_extra(indoc! {r#"
             [[target.starknet-contract]]
-            build-external-contracts = ["y::subfolder::*"]
+            build-external-contracts = ["y::subfolder::**"]
         "#})
         .lib_cairo(indoc! {r#"
             #[starknet::contract]
@@ -403,48 +522,79 @@ fn compile_multiple_with_glob_subpath() {
 }
 
 #[test]
-fn compile_with_bad_glob_path() {
+fn glob_path_is_not_recursive() {
     let t = TempDir::new().unwrap();
     let hello = t.child("hello");
     let world = t.child("world");
 
     ProjectBuilder::start()
         .name("hello")
+        .edition("2023_01")
         .version("0.1.0")
         .manifest_extra(indoc! {r#"
             [lib]
             [[target.starknet-contract]]
         "#})
         .dep_starknet()
-        .lib_cairo(format!("{BALANCE_CONTRACT}\n{HELLO_CONTRACT}"))
+        .lib_cairo(indoc! {r#"
+            pub mod lorem;
+        "#})
+        .src(
+            "src/lorem.cairo",
+            format!("pub mod ipsum;\n{BALANCE_CONTRACT}"),
+        )
+        .src("src/lorem/ipsum.cairo", HELLO_CONTRACT)
         .build(&hello);
 
-    ProjectBuilder::start()
-        .name("world")
-        .version("0.1.0")
-        .dep("hello", &hello)
-        .manifest_extra(formatdoc! {r#"
-            [[target.starknet-contract]]
-            build-external-contracts = ["hello::**",]
-        "#})
-        .dep_starknet()
-        .lib_cairo(format!("{FORTY_TWO_CONTRACT}\n{HELLO_CONTRACT}"))
-        .build(&world);
+    let build = |selector: &str| {
+        ProjectBuilder::start()
+            .name("world")
+            .edition("2023_01")
+            .version("0.1.0")
+            .dep("hello", &hello)
+            .manifest_extra(formatdoc! {r#"
+                [[target.starknet-contract]]
+                build-external-contracts = ["{selector}"]
+                include-main-contracts = false
+            "#})
+            .dep_starknet()
+            .build(&world);
+        Scarb::quick_snapbox()
+            .arg("clean")
+            .current_dir(&world)
+            .assert()
+            .success();
+        Scarb::quick_snapbox()
+            .arg("build")
+            .current_dir(&world)
+            .assert()
+            .success();
+        world.child("target/dev").files()
+    };
 
-    Scarb::quick_snapbox()
-        .arg("build")
-        .current_dir(t.child("world"))
-        .assert()
-        .failure()
-        .stdout_matches(indoc! {r#"
-        [..] Compiling world v0.1.0 ([..]/Scarb.toml)
-        error: external contract path `hello::**` has multiple global path selectors, only one '*' selector is allowed
-        error: could not compile `world` due to previous error
-        "#});
+    assert_eq!(
+        build("hello::lorem::*"),
+        vec![
+            ".fingerprint",
+            "incremental",
+            "world.starknet_artifacts.json",
+            "world_Balance.contract_class.json",
+        ]
+    );
+    assert_eq!(
+        build("hello::lorem::**"),
+        vec![
+            ".fingerprint",
+            "incremental",
+            "world.starknet_artifacts.json",
+            "world_Balance.contract_class.json",
+            "world_HelloContract.contract_class.json",
+        ]
+    );
 }
 
 #[test]
-fn will_warn_about_unmatched_paths() {
+fn hint_recursive_glob_path() {
     let t = TempDir::new().unwrap();
     let hello = t.child("hello");
     let world = t.child("world");
@@ -459,34 +609,26 @@ fn will_warn_about_unmatched_paths() {
         "#})
         .dep_starknet()
         .lib_cairo(indoc! {r#"
-            mod lorem;
+            pub mod lorem;
         "#})
         .src(
             "src/lorem.cairo",
-            indoc! {r#"
-            mod ipsum;
-        "#},
-        )
-        .src(
-            "src/lorem/ipsum.cairo",
-            format!("{BALANCE_CONTRACT}\n{HELLO_CONTRACT}"),
+            format!("pub mod ipsum;\n{BALANCE_CONTRACT}"),
         )
+        .src("src/lorem/ipsum.cairo", HELLO_CONTRACT)
         .build(&hello);
 
     ProjectBuilder::start()
         .name("world")
+        .edition("2023_01")
         .version("0.1.0")
         .dep("hello", &hello)
         .manifest_extra(indoc! {r#"
             [[target.starknet-contract]]
-            build-external-contracts = [
-                "hello::lorem::ipsum::Balance",
-                "hello::lorem::ipsum::HelloContract",
-                "hello::lorem::mopsum::*",
-            ]
+            build-external-contracts = ["hello::lorem::*"]
+            include-main-contracts = false
         "#})
         .dep_starknet()
-        .lib_cairo(format!("{FORTY_TWO_CONTRACT}\n{HELLO_CONTRACT}"))
         .build(&world);
 
     Scarb::quick_snapbox()
@@ -495,143 +637,1265 @@ fn will_warn_about_unmatched_paths() {
         .assert()
         .success()
         .stdout_matches(indoc! {r#"
-            [..] Compiling world v0.1.0 ([..]/Scarb.toml)
-            warn: external contracts not found for selectors: `hello::lorem::mopsum::*`
+            [..] Compiling world v0.1.0 ([..]Scarb.toml)
             [..]  Finished `dev` profile target(s) in [..]
         "#});
-    assert_eq!(
-        world.child("target/dev").files(),
-        vec![
-            ".fingerprint",
-            "incremental",
-            "world.starknet_artifacts.json",
-            "world_Balance.contract_class.json",
-            "world_FortyTwo.contract_class.json",
-            "world_hello_lorem_ipsum_HelloContract.contract_class.json",
-            "world_world_HelloContract.contract_class.json",
-        ]
-    );
+
+    Scarb::quick_snapbox()
+        .args(["clean"])
+        .current_dir(&world)
+        .assert()
+        .success();
+
+    Scarb::quick_snapbox()
+        .args(["-v", "build"])
+        .current_dir(&world)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            ...
+            help: selector `hello::lorem::*` does not match 1 contract(s) defined in nested submodules, use `hello::lorem::**` to select them recursively
+            ...
+        "#});
 }
 
 #[test]
-fn can_build_external_reexported_contracts() {
+fn compile_with_bad_glob_path() {
     let t = TempDir::new().unwrap();
     let hello = t.child("hello");
-    let beautiful = t.child("beautiful");
     let world = t.child("world");
 
     ProjectBuilder::start()
         .name("hello")
-        .edition("2023_01")
         .version("0.1.0")
         .manifest_extra(indoc! {r#"
             [lib]
             [[target.starknet-contract]]
         "#})
         .dep_starknet()
-        .lib_cairo("pub mod a; pub mod b;")
-        .src("src/a.cairo", BALANCE_CONTRACT)
-        .src("src/b.cairo", HELLO_CONTRACT)
+        .lib_cairo(format!("{BALANCE_CONTRACT}\n{HELLO_CONTRACT}"))
         .build(&hello);
 
     ProjectBuilder::start()
-        .name("beautiful")
-        .edition("2023_01")
+        .name("world")
+        .version("0.1.0")
+        .dep("hello", &hello)
+        .manifest_extra(formatdoc! {r#"
+            [[target.starknet-contract]]
+            build-external-contracts = ["hello::*::*",]
+        "#})
+        .dep_starknet()
+        .lib_cairo(format!("{FORTY_TWO_CONTRACT}\n{HELLO_CONTRACT}"))
+        .build(&world);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(t.child("world"))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: external contract path `hello::*::*` has invalid global path selectors, only a single trailing '*' or '**' selector is allowed
+        ...
+        "#});
+}
+
+#[test_case("Balance"; "bare name")]
+#[test_case("::Balance"; "empty package")]
+fn compile_with_selector_without_package(selector: &str) {
+    let t = TempDir::new().unwrap();
+    let hello = t.child("hello");
+    let world = t.child("world");
+
+    ProjectBuilder::start()
+        .name("hello")
         .version("0.1.0")
         .manifest_extra(indoc! {r#"
             [lib]
             [[target.starknet-contract]]
         "#})
         .dep_starknet()
-        .dep("hello", &hello)
-        .lib_cairo("pub use hello as hello_world; pub use hello::a::Balance;")
-        .build(&beautiful);
+        .lib_cairo(BALANCE_CONTRACT)
+        .build(&hello);
 
     ProjectBuilder::start()
         .name("world")
-        .edition("2023_01")
         .version("0.1.0")
-        .dep("beautiful", beautiful)
+        .dep("hello", &hello)
         .manifest_extra(formatdoc! {r#"
             [[target.starknet-contract]]
-            build-external-contracts = ["beautiful::hello_world::b::*", "beautiful::Balance"]
+            build-external-contracts = ["{selector}"]
         "#})
         .dep_starknet()
+        .lib_cairo(FORTY_TWO_CONTRACT)
         .build(&world);
 
     Scarb::quick_snapbox()
         .arg("build")
-        .current_dir(&world)
+        .current_dir(t.child("world"))
         .assert()
-        .success()
-        .stdout_matches(indoc! {r#"
-        [..] Compiling world v0.1.0 ([..]/Scarb.toml)
-        [..]  Finished `dev` profile target(s) in [..]
-    "#});
-
-    assert_eq!(
-        world.child("target/dev").files(),
-        vec![
-            ".fingerprint",
-            "incremental",
-            "world.starknet_artifacts.json",
-            "world_Balance.contract_class.json",
-            "world_HelloContract.contract_class.json"
-        ]
-    );
+        .failure()
+        .stdout_matches(formatdoc! {r#"
+        error: external contract path `{selector}` must consist of a package name and a path within the package, like `package_name::ContractName` or `package_name::*`
+        ...
+        "#});
 }
-#[test]
-fn can_dedup_contract_reexports() {
+
+#[test_case("Balance"; "bare name")]
+#[test_case("hello::*::*"; "bad glob")]
+fn compile_test_target_with_invalid_selector(selector: &str) {
     let t = TempDir::new().unwrap();
     let hello = t.child("hello");
     let world = t.child("world");
 
     ProjectBuilder::start()
         .name("hello")
-        .edition("2023_01")
         .version("0.1.0")
         .manifest_extra(indoc! {r#"
             [lib]
             [[target.starknet-contract]]
         "#})
         .dep_starknet()
-        .lib_cairo(indoc! {r#"
-            // Note that Balance contract can be accessed both through mod tree and the reexport.
-            pub mod a; 
-            pub use a::Balance;
-        "#})
-        .src("src/a.cairo", BALANCE_CONTRACT)
+        .lib_cairo(BALANCE_CONTRACT)
         .build(&hello);
 
     ProjectBuilder::start()
         .name("world")
-        .edition("2023_01")
         .version("0.1.0")
-        .dep("hello", hello)
+        .dep("hello", &hello)
         .manifest_extra(formatdoc! {r#"
             [[target.starknet-contract]]
-            build-external-contracts = ["hello::*"]
+
+            [[test]]
+            build-external-contracts = ["{selector}"]
         "#})
         .dep_starknet()
+        .dep_cairo_test()
+        .lib_cairo(FORTY_TWO_CONTRACT)
         .build(&world);
 
     Scarb::quick_snapbox()
         .arg("build")
-        .current_dir(&world)
+        .arg("--test")
+        .current_dir(t.child("world"))
         .assert()
-        .success()
-        .stdout_matches(indoc! {r#"
-        [..] Compiling world v0.1.0 ([..]/Scarb.toml)
-        [..]  Finished `dev` profile target(s) in [..]
-    "#});
+        .failure()
+        .stdout_matches(formatdoc! {r#"
+        error: external contract path `{selector}` [..]
+        ...
+        "#});
+}
 
-    assert_eq!(
-        world.child("target/dev").files(),
-        vec![
-            ".fingerprint",
-            "incremental",
+fn env_selector_projects(t: &TempDir) -> ChildPath {
+    let hello = t.child("hello");
+    let world = t.child("world");
+
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [lib]
+            [[target.starknet-contract]]
+        "#})
+        .dep_starknet()
+        .lib_cairo("mod lorem;\nmod ipsum;")
+        .src("src/lorem.cairo", BALANCE_CONTRACT)
+        .src("src/ipsum.cairo", HELLO_CONTRACT)
+        .build(&hello);
+
+    ProjectBuilder::start()
+        .name("world")
+        .version("0.1.0")
+        .dep("hello", &hello)
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            build-external-contracts = ["hello::${CONTRACT_SET}::*"]
+        "#})
+        .dep_starknet()
+        .lib_cairo(FORTY_TWO_CONTRACT)
+        .build(&world);
+
+    world
+}
+
+#[test]
+fn interpolates_env_in_selectors() {
+    let t = TempDir::new().unwrap();
+    let world = env_selector_projects(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .env("CONTRACT_SET", "ipsum")
+        .current_dir(&world)
+        .assert()
+        .success();
+    assert_eq!(
+        world.child("target/dev").files(),
+        vec![
+            ".fingerprint",
+            "incremental",
+            "world.starknet_artifacts.json",
+            "world_FortyTwo.contract_class.json",
+            "world_HelloContract.contract_class.json",
+        ]
+    );
+}
+
+#[test]
+fn fails_on_unset_env_in_selectors() {
+    let t = TempDir::new().unwrap();
+    let world = env_selector_projects(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .env_remove("CONTRACT_SET")
+        .current_dir(&world)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: failed to parse manifest at: [..]/Scarb.toml
+
+            Caused by:
+                environment variable `CONTRACT_SET` referenced in selector `hello::${CONTRACT_SET}::*` is not set
+        "#});
+}
+
+#[test]
+fn does_not_interpolate_env_in_dependency_selectors() {
+    let t = TempDir::new().unwrap();
+    let world = env_selector_projects(&t);
+    let consumer = t.child("consumer");
+
+    ProjectBuilder::start()
+        .name("consumer")
+        .version("0.1.0")
+        .dep("world", &world)
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+        "#})
+        .dep_starknet()
+        .lib_cairo(BALANCE_CONTRACT)
+        .build(&consumer);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .env_remove("CONTRACT_SET")
+        .current_dir(&consumer)
+        .assert()
+        .success();
+}
+
+#[test]
+fn will_warn_about_unmatched_paths() {
+    let t = TempDir::new().unwrap();
+    let hello = t.child("hello");
+    let world = t.child("world");
+
+    ProjectBuilder::start()
+        .name("hello")
+        .edition("2023_01")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [lib]
+            [[target.starknet-contract]]
+        "#})
+        .dep_starknet()
+        .lib_cairo(indoc! {r#"
+            mod lorem;
+        "#})
+        .src(
+            "src/lorem.cairo",
+            indoc! {r#"
+            mod ipsum;
+        "#},
+        )
+        .src(
+            "src/lorem/ipsum.cairo",
+            format!("{BALANCE_CONTRACT}\n{HELLO_CONTRACT}"),
+        )
+        .build(&hello);
+
+    ProjectBuilder::start()
+        .name("world")
+        .version("0.1.0")
+        .dep("hello", &hello)
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            build-external-contracts = [
+                "hello::lorem::ipsum::Balance",
+                "hello::lorem::ipsum::HelloContract",
+                "hello::lorem::mopsum::*",
+            ]
+        "#})
+        .dep_starknet()
+        .lib_cairo(format!("{FORTY_TWO_CONTRACT}\n{HELLO_CONTRACT}"))
+        .build(&world);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&world)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling world v0.1.0 ([..]/Scarb.toml)
+            warn: external contracts not found for selectors: `hello::lorem::mopsum::*`
+            [..]  Finished `dev` profile target(s) in [..]
+        "#});
+    assert_eq!(
+        world.child("target/dev").files(),
+        vec![
+            ".fingerprint",
+            "incremental",
+            "world.starknet_artifacts.json",
+            "world_Balance.contract_class.json",
+            "world_FortyTwo.contract_class.json",
+            "world_hello_lorem_ipsum_HelloContract.contract_class.json",
+            "world_world_HelloContract.contract_class.json",
+        ]
+    );
+}
+
+#[test]
+fn deny_unmatched_selectors() {
+    let t = TempDir::new().unwrap();
+    let hello = t.child("hello");
+    let world = t.child("world");
+
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [lib]
+            [[target.starknet-contract]]
+        "#})
+        .dep_starknet()
+        .lib_cairo(BALANCE_CONTRACT)
+        .build(&hello);
+
+    ProjectBuilder::start()
+        .name("world")
+        .version("0.1.0")
+        .dep("hello", &hello)
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            build-external-contracts = [
+                "hello::Balance",
+                "hello::lorem::*",
+            ]
+            deny-unmatched-selectors = true
+        "#})
+        .dep_starknet()
+        .lib_cairo(FORTY_TWO_CONTRACT)
+        .build(&world);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&world)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling world v0.1.0 ([..]/Scarb.toml)
+            error: external contracts not found for selectors: `hello::lorem::*`
+            error: could not compile `world` due to previous error
+        "#});
+}
+
+#[test]
+fn check_selectors() {
+    let t = TempDir::new().unwrap();
+    let hello = t.child("hello");
+    let world = t.child("world");
+
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [lib]
+            [[target.starknet-contract]]
+        "#})
+        .dep_starknet()
+        .lib_cairo(indoc! {r#"
+            mod lorem;
+        "#})
+        .src(
+            "src/lorem.cairo",
+            format!("{BALANCE_CONTRACT}\n{HELLO_CONTRACT}"),
+        )
+        .build(&hello);
+
+    let build_world = |selectors: &str| {
+        ProjectBuilder::start()
+            .name("world")
+            .version("0.1.0")
+            .dep("hello", &hello)
+            .manifest_extra(formatdoc! {r#"
+                [[target.starknet-contract]]
+                build-external-contracts = [{selectors}]
+            "#})
+            .dep_starknet()
+            .lib_cairo(FORTY_TWO_CONTRACT)
+            .build(&world);
+    };
+
+    build_world(r#""hello::lorem::*""#);
+    Scarb::quick_snapbox()
+        .arg("check-selectors")
+        .current_dir(&world)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            world (starknet-contract target `world`)
+                matched   `hello::lorem::*` (2 contracts)
+        "#});
+
+    build_world(r#""hello::lorem::Balance", "hello::ipsum::*""#);
+    Scarb::quick_snapbox()
+        .arg("check-selectors")
+        .current_dir(&world)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            warn: external contracts not found for selectors: `hello::ipsum::*`
+            world (starknet-contract target `world`)
+                matched   `hello::lorem::Balance` (1 contract)
+                unmatched `hello::ipsum::*`
+            error: 1 external contract selector(s) did not match any contract
+        "#});
+    assert!(!world.child("target").path().exists());
+}
+
+#[test]
+fn can_select_contracts_by_package_version() {
+    let t = TempDir::new().unwrap();
+    let hello = t.child("hello");
+    let world = t.child("world");
+
+    ProjectBuilder::start()
+        .name("hello")
+        .edition("2023_01")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [lib]
+            [[target.starknet-contract]]
+        "#})
+        .dep_starknet()
+        .lib_cairo(format!("{BALANCE_CONTRACT}\n{HELLO_CONTRACT}"))
+        .build(&hello);
+
+    ProjectBuilder::start()
+        .name("world")
+        .version("0.1.0")
+        .dep("hello", &hello)
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            build-external-contracts = [
+                "hello@0.1.0::Balance",
+                "hello@0.2.0::HelloContract",
+            ]
+        "#})
+        .dep_starknet()
+        .lib_cairo(FORTY_TWO_CONTRACT)
+        .build(&world);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&world)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling world v0.1.0 ([..]/Scarb.toml)
+            warn: external contracts not found for selectors: `hello@0.2.0::HelloContract`
+            [..]  Finished `dev` profile target(s) in [..]
+        "#});
+    assert_eq!(
+        world.child("target/dev").files(),
+        vec![
+            ".fingerprint",
+            "incremental",
+            "world.starknet_artifacts.json",
+            "world_Balance.contract_class.json",
+            "world_FortyTwo.contract_class.json",
+        ]
+    );
+}
+
+#[test]
+fn can_build_external_reexported_contracts() {
+    let t = TempDir::new().unwrap();
+    let hello = t.child("hello");
+    let beautiful = t.child("beautiful");
+    let world = t.child("world");
+
+    ProjectBuilder::start()
+        .name("hello")
+        .edition("2023_01")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [lib]
+            [[target.starknet-contract]]
+        "#})
+        .dep_starknet()
+        .lib_cairo("pub mod a; pub mod b;")
+        .src("src/a.cairo", BALANCE_CONTRACT)
+        .src("src/b.cairo", HELLO_CONTRACT)
+        .build(&hello);
+
+    ProjectBuilder::start()
+        .name("beautiful")
+        .edition("2023_01")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [lib]
+            [[target.starknet-contract]]
+        "#})
+        .dep_starknet()
+        .dep("hello", &hello)
+        .lib_cairo("pub use hello as hello_world; pub use hello::a::Balance;")
+        .build(&beautiful);
+
+    ProjectBuilder::start()
+        .name("world")
+        .edition("2023_01")
+        .version("0.1.0")
+        .dep("beautiful", beautiful)
+        .manifest_extra(formatdoc! {r#"
+            [[target.starknet-contract]]
+            build-external-contracts = ["beautiful::hello_world::b::*", "beautiful::Balance"]
+        "#})
+        .dep_starknet()
+        .build(&world);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&world)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling world v0.1.0 ([..]/Scarb.toml)
+        [..]  Finished `dev` profile target(s) in [..]
+    "#});
+
+    assert_eq!(
+        world.child("target/dev").files(),
+        vec![
+            ".fingerprint",
+            "incremental",
+            "world.starknet_artifacts.json",
+            "world_Balance.contract_class.json",
+            "world_HelloContract.contract_class.json"
+        ]
+    );
+}
+#[test]
+fn can_dedup_contract_reexports() {
+    let t = TempDir::new().unwrap();
+    let hello = t.child("hello");
+    let world = t.child("world");
+
+    ProjectBuilder::start()
+        .name("hello")
+        .edition("2023_01")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [lib]
+            [[target.starknet-contract]]
+        "#})
+        .dep_starknet()
+        .lib_cairo(indoc! {r#"
+            // Note that Balance contract can be accessed both through mod tree and the reexport.
+            pub mod a; 
+            pub use a::Balance;
+        "#})
+        .src("src/a.cairo", BALANCE_CONTRACT)
+        .build(&hello);
+
+    ProjectBuilder::start()
+        .name("world")
+        .edition("2023_01")
+        .version("0.1.0")
+        .dep("hello", hello)
+        .manifest_extra(formatdoc! {r#"
+            [[target.starknet-contract]]
+            build-external-contracts = ["hello::*"]
+        "#})
+        .dep_starknet()
+        .build(&world);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&world)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling world v0.1.0 ([..]/Scarb.toml)
+        [..]  Finished `dev` profile target(s) in [..]
+    "#});
+
+    assert_eq!(
+        world.child("target/dev").files(),
+        vec![
+            ".fingerprint",
+            "incremental",
+            "world.starknet_artifacts.json",
+            "world_Balance.contract_class.json",
+        ]
+    );
+}
+
+#[test]
+fn can_dedup_mixed_reexport_and_direct_selectors() {
+    let t = TempDir::new().unwrap();
+    let hello = t.child("hello");
+    let beautiful = t.child("beautiful");
+    let world = t.child("world");
+
+    ProjectBuilder::start()
+        .name("hello")
+        .edition("2023_01")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [lib]
+            [[target.starknet-contract]]
+        "#})
+        .dep_starknet()
+        .lib_cairo("pub mod a;")
+        .src("src/a.cairo", BALANCE_CONTRACT)
+        .build(&hello);
+
+    ProjectBuilder::start()
+        .name("beautiful")
+        .edition("2023_01")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [lib]
+            [[target.starknet-contract]]
+        "#})
+        .dep_starknet()
+        .dep("hello", &hello)
+        .lib_cairo("pub use hello::a as tokens; pub use hello::a::Balance;")
+        .build(&beautiful);
+
+    ProjectBuilder::start()
+        .name("world")
+        .edition("2023_01")
+        .version("0.1.0")
+        .dep("hello", &hello)
+        .dep("beautiful", beautiful)
+        .manifest_extra(formatdoc! {r#"
+            [[target.starknet-contract]]
+            build-external-contracts = ["beautiful::tokens::Balance", "beautiful::Balance", "hello::a::Balance"]
+        "#})
+        .dep_starknet()
+        .build(&world);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&world)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling world v0.1.0 ([..]/Scarb.toml)
+        [..]  Finished `dev` profile target(s) in [..]
+    "#});
+
+    assert_eq!(
+        world.child("target/dev").files(),
+        vec![
+            ".fingerprint",
+            "incremental",
+            "world.starknet_artifacts.json",
+            "world_Balance.contract_class.json",
+        ]
+    );
+    let json: serde_json::Value = serde_json::from_str(
+        &world
+            .child("target/dev/world.starknet_artifacts.json")
+            .read_to_string(),
+    )
+    .unwrap();
+    let contracts = json["contracts"].as_array().unwrap();
+    assert_eq!(contracts.len(), 1);
+    assert_eq!(contracts[0]["module_path"], "hello::a::Balance");
+}
+
+#[test]
+fn reexported_contract_names_use_definition_path() {
+    let t = TempDir::new().unwrap();
+    let hello = t.child("hello");
+    let beautiful = t.child("beautiful");
+    let world = t.child("world");
+
+    ProjectBuilder::start()
+        .name("hello")
+        .edition("2023_01")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [lib]
+            [[target.starknet-contract]]
+        "#})
+        .dep_starknet()
+        .lib_cairo("pub mod a; pub mod b;")
+        .src("src/a.cairo", BALANCE_CONTRACT)
+        .src("src/b.cairo", BALANCE_CONTRACT)
+        .build(&hello);
+
+    ProjectBuilder::start()
+        .name("beautiful")
+        .edition("2023_01")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [lib]
+            [[target.starknet-contract]]
+        "#})
+        .dep_starknet()
+        .dep("hello", &hello)
+        .lib_cairo("pub use hello::a as tokens;")
+        .build(&beautiful);
+
+    ProjectBuilder::start()
+        .name("world")
+        .edition("2023_01")
+        .version("0.1.0")
+        .dep("hello", &hello)
+        .dep("beautiful", beautiful)
+        .manifest_extra(formatdoc! {r#"
+            [[target.starknet-contract]]
+            build-external-contracts = ["beautiful::tokens::Balance", "hello::b::Balance"]
+        "#})
+        .dep_starknet()
+        .build(&world);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&world)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling world v0.1.0 ([..]/Scarb.toml)
+        [..]  Finished `dev` profile target(s) in [..]
+    "#});
+
+    assert_eq!(
+        world.child("target/dev").files(),
+        vec![
+            ".fingerprint",
+            "incremental",
+            "world.starknet_artifacts.json",
+            "world_hello_a_Balance.contract_class.json",
+            "world_hello_b_Balance.contract_class.json",
+        ]
+    );
+}
+
+#[test]
+fn can_exclude_main_contracts() {
+    let t = TempDir::new().unwrap();
+    let hello = t.child("hello");
+    let world = t.child("world");
+    compile_dep_test_case(
+        &hello,
+        &world,
+        indoc! {r#"
+            build-external-contracts = ["hello::Balance"]
+            include-main-contracts = false
+        "#},
+    );
+
+    assert_eq!(
+        world.child("target/dev").files(),
+        vec![
+            ".fingerprint",
+            "incremental",
+            "world.starknet_artifacts.json",
+            "world_Balance.contract_class.json",
+        ]
+    );
+}
+
+#[test]
+fn can_select_main_contracts_with_crate_prefix() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            build-external-contracts = ["crate::Balance"]
+            include-main-contracts = false
+        "#})
+        .dep_starknet()
+        .lib_cairo(format!("{BALANCE_CONTRACT}\n{FORTY_TWO_CONTRACT}"))
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    assert_eq!(
+        t.child("target/dev").files(),
+        vec![
+            ".fingerprint",
+            "hello.starknet_artifacts.json",
+            "hello_Balance.contract_class.json",
+            "incremental",
+        ]
+    );
+}
+
+#[test]
+fn excluding_main_contracts_requires_external_contracts() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            include-main-contracts = false
+        "#})
+        .dep_starknet()
+        .lib_cairo(HELLO_CONTRACT)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling hello v0.1.0 ([..]/Scarb.toml)
+            error: `include-main-contracts` is disabled and no `build-external-contracts` are specified, Scarb would not compile any contracts
+            error: could not compile `hello` due to previous error
+        "#});
+}
+
+#[test]
+fn will_fail_on_selector_from_non_dependency() {
+    let t = TempDir::new().unwrap();
+    let hello = t.child("hello");
+    let world = t.child("world");
+
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [lib]
+            [[target.starknet-contract]]
+        "#})
+        .dep_starknet()
+        .lib_cairo(format!("{BALANCE_CONTRACT}\n{HELLO_CONTRACT}"))
+        .build(&hello);
+
+    ProjectBuilder::start()
+        .name("world")
+        .version("0.1.0")
+        .dep("hello", &hello)
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            build-external-contracts = ["helo::Balance"]
+        "#})
+        .dep_starknet()
+        .lib_cairo(FORTY_TWO_CONTRACT)
+        .build(&world);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&world)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling world v0.1.0 ([..]/Scarb.toml)
+            error: external contract selector `helo::Balance` refers to package `helo`, which is not a dependency of `world`
+            help: a dependency with a similar name exists: `hello`
+            error: could not compile `world` due to previous error
+        "#});
+}
+
+#[test]
+fn can_skip_external_contracts_from_cli() {
+    let t = TempDir::new().unwrap();
+    let hello = t.child("hello");
+    let world = t.child("world");
+
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [lib]
+            [[target.starknet-contract]]
+        "#})
+        .dep_starknet()
+        .lib_cairo(format!("{BALANCE_CONTRACT}\n{HELLO_CONTRACT}"))
+        .build(&hello);
+
+    ProjectBuilder::start()
+        .name("world")
+        .version("0.1.0")
+        .dep("hello", &hello)
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            build-external-contracts = ["hello::Balance"]
+        "#})
+        .dep_starknet()
+        .lib_cairo(FORTY_TWO_CONTRACT)
+        .build(&world);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .arg("--no-external-contracts")
+        .current_dir(&world)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling world v0.1.0 ([..]/Scarb.toml)
+            [..]  Finished `dev` profile target(s) in [..]
+        "#});
+
+    assert_eq!(
+        world.child("target/dev").files(),
+        vec![
+            ".fingerprint",
+            "incremental",
+            "world.starknet_artifacts.json",
+            "world_FortyTwo.contract_class.json",
+        ]
+    );
+    let json: serde_json::Value = serde_json::from_str(
+        &world
+            .child("target/dev/world.starknet_artifacts.json")
+            .read_to_string(),
+    )
+    .unwrap();
+    assert_eq!(json["contracts"].as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn artifacts_order_does_not_depend_on_selectors_order() {
+    let artifacts_for = |selectors: &str| {
+        let t = TempDir::new().unwrap();
+        let hello = t.child("hello");
+        let world = t.child("world");
+        compile_dep_test_case(
+            &hello,
+            &world,
+            &format!("build-external-contracts = [{selectors}]"),
+        );
+        let content = world
+            .child("target/dev/world.starknet_artifacts.json")
+            .read_to_string();
+        let json: serde_json::Value = serde_json::from_str(&content).unwrap();
+        json["contracts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|c| {
+                (
+                    c["package_name"].as_str().unwrap().to_string(),
+                    c["contract_name"].as_str().unwrap().to_string(),
+                    c["module_path"].as_str().unwrap().to_string(),
+                )
+            })
+            .collect_vec()
+    };
+
+    let expected = vec![
+        (
+            "hello".to_string(),
+            "Balance".to_string(),
+            "hello::Balance".to_string(),
+        ),
+        (
+            "hello".to_string(),
+            "HelloContract".to_string(),
+            "hello::HelloContract".to_string(),
+        ),
+        (
+            "world".to_string(),
+            "FortyTwo".to_string(),
+            "world::FortyTwo".to_string(),
+        ),
+        (
+            "world".to_string(),
+            "HelloContract".to_string(),
+            "world::HelloContract".to_string(),
+        ),
+    ];
+    assert_eq!(
+        artifacts_for(r#""hello::Balance", "hello::HelloContract""#),
+        expected
+    );
+    assert_eq!(
+        artifacts_for(r#""hello::HelloContract", "hello::Balance""#),
+        expected
+    );
+}
+
+#[test]
+fn reports_external_contracts_disabled_by_features() {
+    let t = TempDir::new().unwrap();
+    let hello = t.child("hello");
+    let world = t.child("world");
+
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [lib]
+            [[target.starknet-contract]]
+
+            [features]
+            balance = []
+        "#})
+        .dep_starknet()
+        .lib_cairo(indoc! {r#"
+            #[cfg(feature: "balance")]
+            pub mod gated;
+        "#})
+        .src("src/gated.cairo", BALANCE_CONTRACT)
+        .build(&hello);
+
+    ProjectBuilder::start()
+        .name("world")
+        .version("0.1.0")
+        .dep("hello", &hello)
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            build-external-contracts = ["hello::gated::Balance", "hello::gatd::Balance"]
+        "#})
+        .dep_starknet()
+        .build(&world);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&world)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling world v0.1.0 ([..]/Scarb.toml)
+            warn: external contracts not found due to disabled features for selectors: `hello::gated::Balance` (requires feature `balance` of package `hello`)
+            warn: external contracts not found for selectors: `hello::gatd::Balance`
+            [..]  Finished `dev` profile target(s) in [..]
+        "#});
+
+    ProjectBuilder::start()
+        .name("world")
+        .version("0.1.0")
+        .dep("hello", (&hello).features(vec!["balance"].into_iter()))
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            build-external-contracts = ["hello::gated::Balance"]
+        "#})
+        .dep_starknet()
+        .build(&world);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&world)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling world v0.1.0 ([..]/Scarb.toml)
+            [..]  Finished `dev` profile target(s) in [..]
+        "#});
+
+    assert_eq!(
+        world.child("target/dev").files(),
+        vec![
+            ".fingerprint",
+            "incremental",
             "world.starknet_artifacts.json",
             "world_Balance.contract_class.json",
         ]
     );
 }
+
+#[test]
+fn emits_selector_provenance() {
+    let t = TempDir::new().unwrap();
+    let hello = t.child("hello");
+    let world = t.child("world");
+    compile_dep_test_case(
+        &hello,
+        &world,
+        indoc! {r#"
+            build-external-contracts = ["hello::*"]
+            emit-selector-provenance = true
+        "#},
+    );
+
+    let content = world
+        .child("target/dev/world.starknet_artifacts.json")
+        .read_to_string();
+    let json: serde_json::Value = serde_json::from_str(&content).unwrap();
+    let selectors = json["contracts"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|c| {
+            (
+                c["module_path"].as_str().unwrap().to_string(),
+                c["selector"].as_str().map(ToString::to_string),
+            )
+        })
+        .collect_vec();
+    assert_eq!(
+        selectors,
+        vec![
+            ("hello::Balance".to_string(), Some("hello::*".to_string())),
+            (
+                "hello::HelloContract".to_string(),
+                Some("hello::*".to_string())
+            ),
+            ("world::FortyTwo".to_string(), None),
+            ("world::HelloContract".to_string(), None),
+        ]
+    );
+}
+
+#[test]
+fn reports_external_contracts_disabled_by_nested_cfg_predicates() {
+    let t = TempDir::new().unwrap();
+    let hello = t.child("hello");
+    let world = t.child("world");
+
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [lib]
+            [[target.starknet-contract]]
+
+            [features]
+            balance = []
+            legacy = []
+        "#})
+        .dep_starknet()
+        .lib_cairo(indoc! {r#"
+            #[cfg(all(feature: "balance", not(feature: "legacy")))]
+            pub mod gated;
+            #[cfg(not(feature: "legacy"))]
+            pub mod modern;
+        "#})
+        .src("src/gated.cairo", BALANCE_CONTRACT)
+        .src("src/modern.cairo", BALANCE_CONTRACT)
+        .build(&hello);
+
+    ProjectBuilder::start()
+        .name("world")
+        .version("0.1.0")
+        .dep("hello", (&hello).features(vec!["legacy"].into_iter()))
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            build-external-contracts = ["hello::gated::Balance", "hello::modern::Balance"]
+        "#})
+        .dep_starknet()
+        .build(&world);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&world)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling world v0.1.0 ([..]/Scarb.toml)
+            warn: external contracts not found due to disabled features for selectors: `hello::gated::Balance` (requires feature `balance` of package `hello`)
+            warn: external contracts not found for selectors: `hello::modern::Balance`
+            [..]  Finished `dev` profile target(s) in [..]
+        "#});
+}
+
+#[test]
+fn reports_external_contracts_disabled_by_short_string_cfg_predicates() {
+    let t = TempDir::new().unwrap();
+    let hello = t.child("hello");
+    let world = t.child("world");
+
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [lib]
+            [[target.starknet-contract]]
+
+            [features]
+            balance = []
+        "#})
+        .dep_starknet()
+        .lib_cairo(indoc! {r#"
+            #[cfg(feature: 'balance')]
+            pub mod gated;
+        "#})
+        .src("src/gated.cairo", BALANCE_CONTRACT)
+        .build(&hello);
+
+    ProjectBuilder::start()
+        .name("world")
+        .version("0.1.0")
+        .dep("hello", &hello)
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            build-external-contracts = ["hello::gated::Balance"]
+        "#})
+        .dep_starknet()
+        .build(&world);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&world)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling world v0.1.0 ([..]/Scarb.toml)
+            warn: external contracts not found due to disabled features for selectors: `hello::gated::Balance` (requires feature `balance` of package `hello`)
+            [..]  Finished `dev` profile target(s) in [..]
+        "#});
+}
+
+#[test]
+fn feature_gated_modules_are_evaluated_with_unit_cfg_set() {
+    let t = TempDir::new().unwrap();
+    let hello = t.child("hello");
+    let world = t.child("world");
+
+    // No features of `hello` are enabled, so its component does not have a cfg set of its own
+    // and is compiled with the cfg set of the unit, which contains `target: "starknet-contract"`.
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [lib]
+            [[target.starknet-contract]]
+
+            [features]
+            balance = []
+        "#})
+        .dep_starknet()
+        .lib_cairo(indoc! {r#"
+            #[cfg(any(feature: "balance", target: "starknet-contract"))]
+            pub mod gated;
+        "#})
+        .src("src/gated.cairo", BALANCE_CONTRACT)
+        .build(&hello);
+
+    ProjectBuilder::start()
+        .name("world")
+        .version("0.1.0")
+        .dep("hello", &hello)
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            build-external-contracts = ["hello::gated::Balance", "hello::gated::Missing"]
+        "#})
+        .dep_starknet()
+        .build(&world);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&world)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Compiling world v0.1.0 ([..]/Scarb.toml)
+            warn: external contracts not found for selectors: `hello::gated::Missing`
+            [..]  Finished `dev` profile target(s) in [..]
+        "#});
+}
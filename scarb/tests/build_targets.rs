@@ -380,6 +380,95 @@ fn compile_test_target() {
     assert_eq!(tests.len(), 0);
 }
 
+#[test]
+fn compile_test_target_emit_sierra_text() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .dep_cairo_test()
+        .manifest_extra(indoc! {r#"
+            [[test]]
+            name = "hello_unittest"
+            emit-sierra-text = true
+        "#})
+        .lib_cairo(indoc! {r#"
+            fn f() -> felt252 { 42 }
+
+            #[cfg(test)]
+            mod tests {
+                #[test]
+                fn it_works() {
+                    assert(super::f() == 42, 'it works!');
+                }
+            }
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .arg("--test")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    assert_eq!(
+        t.child("target/dev").files(),
+        vec![
+            ".fingerprint",
+            "hello_unittest.test.json",
+            "hello_unittest.test.sierra",
+            "hello_unittest.test.sierra.json",
+            "incremental",
+        ]
+    );
+    t.child("target/dev/hello_unittest.test.sierra")
+        .assert(predicates::str::contains("hello::tests::it_works"));
+}
+
+#[test]
+fn compile_test_target_metadata_only() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .dep_cairo_test()
+        .manifest_extra(indoc! {r#"
+            [[test]]
+            name = "hello_unittest"
+            emit = ["metadata"]
+        "#})
+        .lib_cairo(indoc! {r#"
+            fn f() -> felt252 { 42 }
+
+            #[cfg(test)]
+            mod tests {
+                #[test]
+                fn it_works() {
+                    assert(super::f() == 42, 'it works!');
+                }
+            }
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .arg("--test")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    assert_eq!(
+        t.child("target/dev").files(),
+        vec![".fingerprint", "hello_unittest.test.json", "incremental"]
+    );
+    t.child("target/dev/hello_unittest.test.sierra.json")
+        .assert(predicates::path::exists().not());
+    let json = t
+        .child("target/dev/hello_unittest.test.json")
+        .assert_is_json::<serde_json::Value>();
+    let tests = json.get("named_tests").unwrap().as_array().unwrap();
+    assert_eq!(tests.len(), 1);
+}
+
 #[test]
 fn integration_tests_do_not_enable_cfg_in_main_package() {
     let t = TempDir::new().unwrap();
@@ -1071,6 +1160,158 @@ fn test_target_builds_external() {
         .assert_is_json::<serde_json::Value>();
 }
 
+#[test]
+fn test_target_builds_transitive_external() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("second")
+        .version("0.1.0")
+        .dep_starknet()
+        .lib_cairo(HELLO_CONTRACT)
+        .build(&t.child("second"));
+
+    ProjectBuilder::start()
+        .name("first")
+        .version("0.1.0")
+        .dep("second", Dep.path("../second"))
+        .build(&t.child("first"));
+
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [[test]]
+            name = "hello_unittest"
+            build-external-contracts-transitive = true
+        "#})
+        .dep("first", Dep.path("../first"))
+        .dep_starknet()
+        .dep_cairo_test()
+        .build(&t.child("hello"));
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .arg("--test")
+        .current_dir(t.child("hello"))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..]Compiling test(hello_unittest) hello v0.1.0 ([..]Scarb.toml)
+        warn: searching for contracts in all dependencies of test target `hello_unittest`, this may significantly slow down compilation
+        [..]  Finished `dev` profile target(s) in [..]
+        "#});
+
+    t.child("hello/target/dev/hello_unittest_HelloContract.test.contract_class.json")
+        .assert_is_json::<ContractClass>();
+}
+
+#[test]
+fn test_target_warns_about_external_contracts_without_starknet() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("first")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [lib]
+            [[target.starknet-contract]]
+        "#})
+        .dep_starknet()
+        .lib_cairo(HELLO_CONTRACT)
+        .build(&t.child("first"));
+
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            build-external-contracts = ["first::*"]
+        "#})
+        .dep("first", Dep.path("../first"))
+        .dep_cairo_test()
+        .build(&t.child("hello"));
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .arg("--test")
+        .current_dir(t.child("hello"))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..]Compiling test(hello_unittest) hello v0.1.0 ([..]Scarb.toml)
+        warn: external contracts will not be built for test target `hello_unittest`, because package `hello` does not depend on `starknet` package
+        help: add dependency on `starknet` to package manifest
+        [..]  Finished `dev` profile target(s) in [..]
+        "#});
+}
+
+#[test]
+fn test_targets_reuse_expanded_external_contract_selectors() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("first")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [lib]
+            [[target.starknet-contract]]
+        "#})
+        .dep_starknet()
+        .dep_cairo_test()
+        .lib_cairo(HELLO_CONTRACT)
+        .build(&t.child("first"));
+
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [lib]
+            sierra = true
+
+            [[target.starknet-contract]]
+            build-external-contracts = ["first::*"]
+        "#})
+        .dep("first", Dep.path("../first"))
+        .dep_starknet()
+        .dep_cairo_test()
+        .src("tests/contract_test.cairo", "")
+        .build(&t.child("hello"));
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .arg("--test")
+        .current_dir(t.child("hello"))
+        .assert()
+        .success();
+
+    // Both unit and integration test targets select `first::*`, sharing a single expansion.
+    for target in ["hello_unittest", "hello_integrationtest"] {
+        let content = t
+            .child(format!(
+                "hello/target/dev/{target}.test.starknet_artifacts.json"
+            ))
+            .read_to_string();
+        let json: serde_json::Value = serde_json::from_str(&content).unwrap();
+        let module_paths = json["contracts"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|contract| contract["module_path"].as_str().unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(module_paths, vec!["first::HelloContract".to_string()]);
+    }
+
+    assert_eq!(
+        t.child("hello/target/dev")
+            .files()
+            .into_iter()
+            .filter(|file| file.ends_with("_HelloContract.test.contract_class.json"))
+            .collect::<Vec<_>>(),
+        vec![
+            "hello_integrationtest_HelloContract.test.contract_class.json",
+            "hello_unittest_HelloContract.test.contract_class.json",
+        ]
+    );
+}
+
 #[test]
 fn transitive_dev_deps_not_available() {
     let t = TempDir::new().unwrap();
@@ -1492,3 +1733,54 @@ fn test_target_builds_contracts_with_warning() {
         ]
     );
 }
+
+#[test]
+fn test_target_deny_warnings() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [[test]]
+            deny-warnings = true
+        "#})
+        .dep_cairo_test()
+        .lib_cairo(indoc! {r#"
+            fn f() -> felt252 {
+                let x = 1;
+                42
+            }
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..]Compiling hello v0.1.0 ([..]Scarb.toml)
+            warn[E0001]: Unused variable. Consider ignoring by prefixing with `_`.
+             --> [..]lib.cairo:2:9
+                let x = 1;
+                    ^
+
+                Finished `dev` profile target(s) in [..]
+        "#});
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .arg("--test")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            [..]Compiling test([..]) hello v0.1.0 ([..]Scarb.toml)
+            warn[E0001]: Unused variable. Consider ignoring by prefixing with `_`.
+             --> [..]lib.cairo:2:9
+                let x = 1;
+                    ^
+
+            error: could not compile `hello` due to previous error
+        "#});
+}
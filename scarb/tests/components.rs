@@ -0,0 +1,95 @@
+use assert_fs::TempDir;
+use indoc::indoc;
+
+use scarb_test_support::command::Scarb;
+use scarb_test_support::project_builder::ProjectBuilder;
+
+#[test]
+fn lists_components_of_target() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["components", "--target-name", "hello"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            lib hello
+                hello v0.1.0 ([..]Scarb.toml)
+                    source: [..]
+                    discriminator: [..]
+                    crate: Real { name: "hello", discriminator: Some([..]) }
+                core v[..]
+                    source: [..]
+                    crate: Real { name: "core", discriminator: None }
+        "#});
+}
+
+#[test]
+fn json_output() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .build(&t);
+
+    let output = Scarb::quick_snapbox()
+        .args(["--json", "components"])
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    let components: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let units = components["units"].as_array().unwrap();
+    let targets = units
+        .iter()
+        .map(|unit| {
+            (
+                unit["target_kind"].as_str().unwrap(),
+                unit["target_name"].as_str().unwrap(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(targets, vec![("lib", "hello"), ("test", "hello_unittest")]);
+
+    let components = units[0]["components"].as_array().unwrap();
+    assert!(
+        components[0]["package"]
+            .as_str()
+            .unwrap()
+            .starts_with("hello 0.1.0")
+    );
+    assert!(components[0]["discriminator"].is_string());
+    let core = components
+        .iter()
+        .find(|component| component["package"].as_str().unwrap().starts_with("core "))
+        .unwrap();
+    assert!(core["discriminator"].is_null());
+    assert_eq!(
+        core["crate_long_id"].as_str().unwrap(),
+        r#"Real { name: "core", discriminator: None }"#
+    );
+}
+
+#[test]
+fn unknown_target_name() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["components", "--target-name", "world"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: no compilation units found for `hello`
+        "#});
+}
@@ -1,8 +1,9 @@
-use indoc::indoc;
+use assert_fs::prelude::*;
+use indoc::{formatdoc, indoc};
 use scarb_test_support::gitx;
 
-use scarb_test_support::command::Scarb;
-use scarb_test_support::project_builder::ProjectBuilder;
+use scarb_test_support::command::{Scarb, ScarbSnapboxExt};
+use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
 
 #[test]
 fn simple() {
@@ -40,3 +41,182 @@ fn check_git_fetch_stdout() {
         [..]  Updating git repository file://[..]/dep1
         "#});
 }
+
+#[test]
+fn dry_run_reports_missing_and_cached_sources() {
+    let t = assert_fs::TempDir::new().unwrap();
+    let cache_dir = assert_fs::TempDir::new().unwrap();
+
+    let git_dep = gitx::new("dep1", |t| ProjectBuilder::start().name("dep1").build(&t));
+
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .dep("dep1", &git_dep)
+        .build(&t);
+
+    // Generate the lockfile using a throwaway cache.
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    Scarb::quick_snapbox()
+        .scarb_cache(cache_dir.path())
+        .args(["fetch", "--dry-run"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..]Missing git repository file://[..]/dep1
+        1 of 1 source(s) would be fetched
+        "#});
+
+    Scarb::quick_snapbox()
+        .scarb_cache(cache_dir.path())
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    Scarb::quick_snapbox()
+        .scarb_cache(cache_dir.path())
+        .args(["fetch", "--dry-run"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..]Cached git repository file://[..]/dep1
+        0 of 1 source(s) would be fetched
+        "#});
+}
+
+#[test]
+fn dry_run_does_not_create_lockfile() {
+    let t = assert_fs::TempDir::new().unwrap();
+    let cache_dir = assert_fs::TempDir::new().unwrap();
+
+    let git_dep = gitx::new("dep1", |t| ProjectBuilder::start().name("dep1").build(&t));
+
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .dep("dep1", &git_dep)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .scarb_cache(cache_dir.path())
+        .args(["fetch", "--dry-run"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..]Missing git repository file://[..]/dep1
+        1 of 1 source(s) would be fetched
+        "#});
+
+    t.child("Scarb.lock").assert(predicates::path::missing());
+}
+
+#[test]
+fn dry_run_skips_patched_sources() {
+    let t = assert_fs::TempDir::new().unwrap();
+    let cache_dir = assert_fs::TempDir::new().unwrap();
+
+    let git_dep = gitx::new("dep1", |t| ProjectBuilder::start().name("dep1").build(&t));
+    let patch = t.child("patch");
+    ProjectBuilder::start().name("dep1").build(&patch);
+
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .dep("dep1", &git_dep)
+        .manifest_extra(formatdoc! {r#"
+            [patch."{}"]
+            dep1 = {{ path = "patch" }}
+        "#, git_dep.url()})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .scarb_cache(cache_dir.path())
+        .args(["fetch", "--dry-run"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        0 of 0 source(s) would be fetched
+        "#});
+}
+
+#[test]
+fn path_dep_outside_allowed_roots() {
+    let t = assert_fs::TempDir::new().unwrap();
+    let allowed = t.child("allowed");
+    ProjectBuilder::start()
+        .name("inside")
+        .version("0.1.0")
+        .build(&allowed.child("inside"));
+    ProjectBuilder::start()
+        .name("outside")
+        .version("0.1.0")
+        .build(&t.child("outside"));
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .dep("inside", Dep.path("../inside"))
+        .dep("outside", Dep.path("../../outside"))
+        .build(&allowed.child("hello"));
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .env("SCARB_ALLOWED_PATH_DEP_ROOTS", allowed.path())
+        .current_dir(allowed.child("hello"))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: failed to parse manifest at: [..]Scarb.toml
+
+            Caused by:
+                path dependency `outside` at `[..]outside` is outside of allowed path dependency roots: `[..]allowed`
+        "#});
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .env("SCARB_ALLOWED_PATH_DEP_ROOTS", t.path())
+        .current_dir(allowed.child("hello"))
+        .assert()
+        .success();
+}
+
+#[test]
+fn path_deps_of_git_dependency_ignore_allowed_roots() {
+    let git_dep = gitx::new("dep1", |t| {
+        ProjectBuilder::start()
+            .name("dep1")
+            .lib_cairo("fn hello() -> felt252 { dep2::hello() }")
+            .dep("dep2", Dep.path("vendor/dep2"))
+            .build(&t);
+
+        ProjectBuilder::start()
+            .name("dep2")
+            .lib_cairo("fn hello() -> felt252 { 42 }")
+            .build(&t.child("vendor/dep2"));
+    });
+
+    let t = assert_fs::TempDir::new().unwrap();
+    let cache_dir = assert_fs::TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .dep("dep1", &git_dep)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .scarb_cache(cache_dir.path())
+        .env("SCARB_ALLOWED_PATH_DEP_ROOTS", t.path())
+        .current_dir(&t)
+        .assert()
+        .success();
+}
@@ -4,7 +4,7 @@ use std::fs;
 use assert_fs::TempDir;
 use assert_fs::prelude::*;
 use gix::refs::transaction::PreviousValue;
-use indoc::indoc;
+use indoc::{formatdoc, indoc};
 use scarb_metadata::Metadata;
 
 use scarb_test_support::command::{CommandExt, Scarb};
@@ -106,6 +106,48 @@ fn fetch_git_dep_tag() {
         "#});
 }
 
+#[test]
+fn fetch_git_dep_annotated_tag_rev() {
+    let git_dep = gitx::new("dep1", |t| {
+        ProjectBuilder::start()
+            .name("dep1")
+            .lib_cairo("pub fn hello() -> felt252 { 42 }")
+            .build(&t)
+    });
+
+    git_dep.change_file("src/lib.cairo", "pub fn tagged() -> felt252 { 53 }");
+    git_dep.tag("v1.4.0");
+
+    let tag_sha = git_dep.rev_parse("v1.4.0");
+    let commit_sha = git_dep.rev_parse("v1.4.0^{commit}");
+    assert_ne!(tag_sha, commit_sha);
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("1.0.0")
+        .dep("dep1", git_dep.with("rev", &tag_sha))
+        .lib_cairo("fn world() -> felt252 { dep1::tagged() }")
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    let lockfile = t.child("Scarb.lock").read_to_string();
+    assert!(lockfile.contains(&format!("rev={tag_sha}#{commit_sha}")));
+
+    // Building again with the lockfile in place must use the locked commit.
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success();
+    assert_eq!(t.child("Scarb.lock").read_to_string(), lockfile);
+}
+
 #[test]
 fn fetch_git_dep_pull_request() {
     let git_dep = gitx::new("dep1", |t| {
@@ -370,6 +412,75 @@ fn stale_cached_version_update() {
         .stdout_matches("");
 }
 
+#[test]
+fn update_keeps_untracked_branch() {
+    let dep = gitx::new("dep", |t| {
+        ProjectBuilder::start()
+            .name("dep")
+            .lib_cairo("pub fn hello() -> felt252 { 42 }")
+            .build(&t)
+    });
+    dep.checkout_branch("foo");
+    dep.change_file("src/lib.cairo", "pub fn hello() -> felt252 { 53 }");
+    let locked_rev = dep.rev_parse("foo");
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("1.0.0")
+        .dep("dep", dep.with("branch", "foo").with("track", false))
+        .lib_cairo("fn world() -> felt252 { dep::hello() }")
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    let lockfile = t.child("Scarb.lock").read_to_string();
+    assert!(lockfile.contains(&locked_rev));
+
+    dep.change_file("src/lib.cairo", "pub fn hello() -> felt252 { 64 }");
+
+    Scarb::quick_snapbox()
+        .arg("update")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    assert_eq!(t.child("Scarb.lock").read_to_string(), lockfile);
+}
+
+#[test]
+fn track_requires_branch() {
+    let dep = gitx::new("dep", |t| {
+        ProjectBuilder::start()
+            .name("dep")
+            .lib_cairo("pub fn hello() -> felt252 { 42 }")
+            .build(&t)
+    });
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("1.0.0")
+        .dep("dep", dep.with("track", false))
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: failed to parse manifest at: [..]/Scarb.toml
+
+        Caused by:
+            dependency (dep) provides `track`, which is only allowed along with `branch`
+        "#});
+}
+
 #[test]
 fn change_source() {
     let dep = gitx::new("dep", |t| {
@@ -452,6 +563,8 @@ fn force_push() {
         .success()
         .stdout_matches(indoc! {r#"
         [..]  Updating git repository file://[..]/dep
+        warn: locked revision `[..]` is no longer reachable in git repository file://[..]/dep, it may have been removed by a force push
+        note: falling back to revision `[..]` of the requested reference, the lockfile will be updated accordingly
         "#});
 }
 
@@ -590,3 +703,164 @@ fn deps_only_cloned_to_checkouts_once() {
         [..]Running git[EXE] fetch --verbose --force --update-head-ok [..]dep1 +HEAD:refs/remotes/origin/HEAD
         "#});
 }
+
+#[test]
+fn vendored_git_source_replacement() {
+    let git_dep = gitx::new("dep1", |t| {
+        ProjectBuilder::start()
+            .name("dep1")
+            .lib_cairo("pub fn hello() -> felt252 { 42 }")
+            .build(&t)
+    });
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("1.0.0")
+        .dep("dep1", &git_dep)
+        .lib_cairo("fn world() -> felt252 { dep1::hello() }")
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .success();
+    let lockfile = t.child("Scarb.lock").read_to_string();
+
+    // Vendor the dependency and redirect the Git source to the vendored copy.
+    ProjectBuilder::start()
+        .name("dep1")
+        .lib_cairo("pub fn hello() -> felt252 { 42 }")
+        .build(&t.child("vendor/dep1"));
+    ProjectBuilder::start()
+        .name("hello")
+        .version("1.0.0")
+        .dep("dep1", &git_dep)
+        .lib_cairo("fn world() -> felt252 { dep1::hello() }")
+        .manifest_extra(formatdoc! {r#"
+            [source.dep1-git]
+            git = "{git_dep}"
+            replace-with = "vendored"
+
+            [source.vendored]
+            directory = "vendor"
+        "#})
+        .build(&t);
+
+    // Use an empty cache and offline mode, so that the Git source cannot be used.
+    let cache_dir = TempDir::new().unwrap();
+    Scarb::quick_snapbox()
+        .env("SCARB_CACHE", cache_dir.path())
+        .arg("--offline")
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+        [..] Compiling hello v1.0.0 ([..]Scarb.toml)
+        [..]  Finished `dev` profile target(s) in [..]
+        "#});
+
+    assert_eq!(t.child("Scarb.lock").read_to_string(), lockfile);
+}
+
+#[test]
+fn source_replacement_requires_defined_replacement() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("1.0.0")
+        .manifest_extra(indoc! {r#"
+            [source.dep1-git]
+            git = "https://example.com/dep1.git"
+            replace-with = "vendored"
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: failed to parse manifest at: [..]Scarb.toml
+
+        Caused by:
+            source `dep1-git` is replaced with `vendored`, which is not defined
+        "#});
+}
+
+#[test]
+fn precise_override() {
+    let dep = gitx::new("dep", |t| {
+        ProjectBuilder::start()
+            .name("dep")
+            .lib_cairo("pub fn hello() -> felt252 { 11111111111101 }")
+            .build(&t)
+    });
+    let old_rev = dep.rev_parse("HEAD");
+    dep.change_file(
+        "src/lib.cairo",
+        "pub fn hello() -> felt252 { 11111111111102 }",
+    );
+    let new_rev = dep.rev_parse("HEAD");
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("1.0.0")
+        .dep("dep", &dep)
+        .lib_cairo("fn world() -> felt252 { dep::hello() }")
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .success();
+    t.child("Scarb.lock")
+        .assert(predicates::str::contains(format!("#{new_rev}")));
+
+    Scarb::quick_snapbox()
+        .args(["--precise", &format!("dep={old_rev}"), "build"])
+        .current_dir(&t)
+        .assert()
+        .success();
+    t.child("Scarb.lock")
+        .assert(predicates::str::contains(format!("#{old_rev}")));
+    t.child("target/dev/hello.sierra.json")
+        .assert(predicates::str::contains("11111111111101"));
+}
+
+#[test]
+fn precise_override_requires_git_source() {
+    let t = TempDir::new().unwrap();
+    let dep = t.child("dep");
+    ProjectBuilder::start().name("dep").build(&dep);
+    let hello = t.child("hello");
+    ProjectBuilder::start()
+        .name("hello")
+        .version("1.0.0")
+        .dep("dep", &dep)
+        .build(&hello);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&hello)
+        .assert()
+        .success();
+
+    Scarb::quick_snapbox()
+        .args([
+            "--precise",
+            "dep=0123456789abcdef0123456789abcdef01234567",
+            "fetch",
+        ])
+        .current_dir(&hello)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: cannot set precise version for non-git source of package `dep`
+        "#});
+}
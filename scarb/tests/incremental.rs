@@ -107,6 +107,38 @@ fn incremental_artifacts_emitted() {
     assert_eq!(digest(&hello_component_id), hello_component_digest);
 }
 
+#[test]
+fn incremental_disabled_for_profile() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .manifest_extra(indoc! {r#"
+            [profile.release]
+            incremental = false
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("--release")
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    assert_eq!(t.child("target/release").files(), vec!["hello.sierra.json"]);
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    assert_eq!(
+        t.child("target/dev").files(),
+        vec![".fingerprint", "hello.sierra.json", "incremental"]
+    );
+}
+
 #[test]
 fn deps_are_fingerprinted() {
     let cache_dir = TempDir::new().unwrap().child("c");
@@ -3,6 +3,7 @@ use assert_fs::{TempDir, prelude::PathChild};
 use indoc::{formatdoc, indoc};
 use scarb_test_support::cairo_plugin_project_builder::CairoPluginProjectBuilder;
 use scarb_test_support::fsx::ChildPathEx;
+use scarb_test_support::gitx;
 use scarb_test_support::{
     command::Scarb, project_builder::ProjectBuilder, workspace_builder::WorkspaceBuilder,
 };
@@ -54,6 +55,34 @@ fn lint_main_package() {
               if x == false {
                  ^^^^^^^^^^
   
+          lint: 0 errors, 1 warning across 1 package
+        "#});
+}
+
+#[test]
+fn lint_short_message_format() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .lib_cairo(indoc! {r#"
+          fn main() {
+              let x = true;
+              if x == false {
+                  println!("x is false");
+              }
+          }
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["lint", "--message-format", "short"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+               Linting hello v1.0.0 ([..]/Scarb.toml)
+          [..]/lib.cairo:3:8: warn: Plugin diagnostic: Unnecessary comparison with a boolean value. Use the variable directly.
+          lint: 0 errors, 1 warning across 1 package
         "#});
 }
 
@@ -108,6 +137,7 @@ fn lint_warnings_disallowed_manifest() {
               if x == false {
                  ^^^^^^^^^^
   
+          lint: 0 errors, 1 warning across 1 package
           error: lint checking `hello` failed due to previous errors
         "#});
 }
@@ -160,6 +190,7 @@ fn lint_warnings_disallowed_cli() {
               if x == false {
                  ^^^^^^^^^^
   
+          lint: 0 errors, 1 warning across 1 package
           error: lint checking `hello` failed due to previous errors
         "#});
 }
@@ -216,10 +247,71 @@ fn lint_warnings_disallowed_cli_and_manifest() {
               if x == false {
                  ^^^^^^^^^^
   
+          lint: 0 errors, 1 warning across 1 package
           error: lint checking `hello` failed due to previous errors
         "#});
 }
 
+#[test]
+fn lint_max_warnings() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .lib_cairo(indoc! {r#"
+          fn main() {
+              let x = true;
+              if x == false {
+                  println!("x is false");
+              }
+              if x == true {
+                  println!("x is true");
+              }
+          }
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["lint", "--max-warnings", "2"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+               Linting hello v1.0.0 ([..]/Scarb.toml)
+          warn: Plugin diagnostic: Unnecessary comparison with a boolean value. Use the variable directly.
+           --> [..]/lib.cairo:3:8
+              if x == false {
+                 ^^^^^^^^^^
+
+          warn: Plugin diagnostic: Unnecessary comparison with a boolean value. Use the variable directly.
+           --> [..]/lib.cairo:6:8
+              if x == true {
+                 ^^^^^^^^^
+
+          lint: 0 errors, 2 warnings across 1 package
+        "#});
+
+    Scarb::quick_snapbox()
+        .args(["lint", "--max-warnings", "1"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+               Linting hello v1.0.0 ([..]/Scarb.toml)
+          warn: Plugin diagnostic: Unnecessary comparison with a boolean value. Use the variable directly.
+           --> [..]/lib.cairo:3:8
+              if x == false {
+                 ^^^^^^^^^^
+
+          warn: Plugin diagnostic: Unnecessary comparison with a boolean value. Use the variable directly.
+           --> [..]/lib.cairo:6:8
+              if x == true {
+                 ^^^^^^^^^
+
+          lint: 0 errors, 2 warnings across 1 package
+          error: lint checking found 2 warnings, exceeding the maximum of 1
+        "#});
+}
+
 #[test]
 fn lint_workspace() {
     let t = TempDir::new().unwrap();
@@ -284,6 +376,43 @@ fn lint_workspace() {
           if second == false {
              ^^^^^^^^^^^^^^^
 
+      lint: 0 errors, 3 warnings across 3 packages
+      "#});
+}
+
+#[test]
+fn lint_workspace_members_matching_glob() {
+    let t = TempDir::new().unwrap();
+    for name in ["core_math", "core_utils", "app_utils"] {
+        ProjectBuilder::start()
+            .name(name)
+            .lib_cairo("fn main() {}")
+            .build(&t.child(name));
+    }
+    WorkspaceBuilder::start()
+        .add_member("core_math")
+        .add_member("core_utils")
+        .add_member("app_utils")
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["lint", "--package", "*_utils"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+           Linting app_utils v1.0.0 ([..]/app_utils/Scarb.toml)
+           Linting core_utils v1.0.0 ([..]/core_utils/Scarb.toml)
+      lint: 0 errors, 0 warnings across 2 packages
+      "#});
+
+    Scarb::quick_snapbox()
+        .args(["lint", "--package", "lib_*"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+      error: no workspace members match `lib_*`
       "#});
 }
 
@@ -332,6 +461,7 @@ fn lint_integration_tests() {
               if false == x {
                  ^^^^^^^^^^
 
+          lint: 0 errors, 1 warning across 1 package
         "#});
 }
 
@@ -386,6 +516,7 @@ fn lint_unit_test() {
                   if false == x {
                      ^^^^^^^^^^
 
+          lint: 0 errors, 1 warning across 1 package
         "#});
 }
 
@@ -406,18 +537,75 @@ fn lint_no_panics() {
         .current_dir(&t)
         .assert()
         .success()
-        .stdout_matches("     Linting hello v1.0.0 ([..]/Scarb.toml)\n");
+        .stdout_matches(indoc! {r#"
+             Linting hello v1.0.0 ([..]/Scarb.toml)
+        lint: 0 errors, 0 warnings across 1 package
+        "#});
 }
 
 #[test]
-fn lint_panics() {
+fn lint_skips_unchanged_packages() {
     let t = TempDir::new().unwrap();
     ProjectBuilder::start()
         .name("hello")
-        .manifest_extra(indoc! {r#"
-            [tool]
-            cairo-lint.panic = true
+        .lib_cairo(indoc! {r#"
+            fn main() -> felt252 {
+                42
+            }
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("lint")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+             Linting hello v1.0.0 ([..]/Scarb.toml)
+        lint: 0 errors, 0 warnings across 1 package
+        "#});
+
+    Scarb::quick_snapbox()
+        .arg("lint")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+               Fresh hello v1.0.0 ([..]/Scarb.toml)
+        lint: 0 errors, 0 warnings across 1 package
+        "#});
+
+    t.child("src/lib.cairo")
+        .write_str(indoc! {r#"
+            fn main() -> bool {
+                let x = true;
+                x == false
+            }
         "#})
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .arg("lint")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+             Linting hello v1.0.0 ([..]/Scarb.toml)
+        warn: Plugin diagnostic: Unnecessary comparison with a boolean value. Use the variable directly.
+         --> [..]/lib.cairo:3:5
+            x == false
+            ^^^^^^^^^^
+
+        lint: 0 errors, 1 warning across 1 package
+        "#});
+}
+
+#[test]
+fn lint_panics() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .tool("cairo-lint", toml::toml! { panic = true }.into())
         .lib_cairo(indoc! {r#"
             fn main() {
                 panic!("This should not be linted.");
@@ -437,9 +625,84 @@ fn lint_panics() {
               panic!("This should not be linted.");
               ^^^^^
 
+          lint: 0 errors, 1 warning across 1 package
+        "#});
+}
+
+#[test]
+fn lint_inherits_workspace_tool_metadata() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("first")
+        .lib_cairo(indoc! {r#"
+            fn main() {
+                panic!("This should be linted.");
+            }
+        "#})
+        .build(&t.child("first"));
+    ProjectBuilder::start()
+        .name("second")
+        .tool("cairo-lint", toml::toml! { panic = false }.into())
+        .lib_cairo(indoc! {r#"
+            fn main() {
+                panic!("This should not be linted.");
+            }
+        "#})
+        .build(&t.child("second"));
+    WorkspaceBuilder::start()
+        .add_member("first")
+        .add_member("second")
+        .manifest_extra(indoc! {r#"
+            [workspace.tool.cairo-lint]
+            panic = true
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("lint")
+        .arg("--workspace")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+               Linting first v1.0.0 ([..]/first/Scarb.toml)
+          warn: Plugin diagnostic: Leaving `panic` in the code is discouraged.
+           --> [..]/lib.cairo:2:5
+              panic!("This should be linted.");
+              ^^^^^
+
+               Linting second v1.0.0 ([..]/second/Scarb.toml)
+          lint: 0 errors, 1 warning across 2 packages
         "#});
 }
 
+#[test]
+fn unknown_lint_in_tool_metadata() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .tool("cairo-lint", toml::toml! { panik = false }.into())
+        .lib_cairo(indoc! {r#"
+            fn main() {
+                panic!("This should not be linted.");
+            }
+        "#})
+        .build(&t);
+
+    for args in [&["lint"][..], &["lint", "--include-generated"]] {
+        Scarb::quick_snapbox()
+            .args(args)
+            .current_dir(&t)
+            .assert()
+            .failure()
+            .stdout_matches(indoc! {r#"
+                   Linting hello v1.0.0 ([..]Scarb.toml)
+                error: unknown lint in `[tool.cairo-lint]`: `panik`
+                help: a lint with a similar name exists: `panic`
+            "#});
+    }
+}
+
 #[test]
 fn lint_selected_features() {
     let t = TempDir::new().unwrap();
@@ -477,7 +740,10 @@ fn lint_selected_features() {
         .current_dir(&t)
         .assert()
         .success()
-        .stdout_matches("     Linting hello v1.0.0 ([..]/Scarb.toml)\n");
+        .stdout_matches(indoc! {r#"
+             Linting hello v1.0.0 ([..]/Scarb.toml)
+        lint: 0 errors, 0 warnings across 1 package
+        "#});
 
     Scarb::quick_snapbox()
         .arg("lint")
@@ -580,9 +846,67 @@ fn lint_selected_targets() {
           if second == false {
              ^^^^^^^^^^^^^^^
 
+      lint: 0 errors, 2 warnings across 2 packages
       "#});
 }
 
+#[test]
+fn lint_reports_skipped_packages_in_json() {
+    let t = TempDir::new().unwrap();
+    let code = indoc! {r#"
+        fn main() {
+            let x = true;
+            if x == false {
+                println!("x is false");
+            }
+        }
+    "#};
+    ProjectBuilder::start()
+        .name("first")
+        .lib_cairo(code)
+        .build(&t.child("first"));
+    ProjectBuilder::start()
+        .name("second")
+        .lib_cairo(code)
+        .build(&t.child("second"));
+    ProjectBuilder::start()
+        .name("third")
+        .lib_cairo(code)
+        .build(&t.child("third"));
+    WorkspaceBuilder::start()
+        .add_member("first")
+        .add_member("second")
+        .add_member("third")
+        .build(&t);
+
+    let output = Scarb::quick_snapbox()
+        .arg("--json")
+        .arg("lint")
+        .arg("--workspace")
+        .arg("--target-names=first,second_unittest")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .get_output()
+        .stdout
+        .clone();
+    let output = String::from_utf8(output).unwrap();
+    let skipped = output
+        .lines()
+        .find(|line| line.starts_with(r#"{"skipped_packages":"#))
+        .expect("skipped packages should be reported");
+    let skipped: serde_json::Value = serde_json::from_str(skipped).unwrap();
+    let mut skipped = skipped["skipped_packages"].as_array().unwrap().clone();
+    skipped.sort_by_key(|package| package["package"].as_str().unwrap().to_string());
+    assert_eq!(
+        skipped,
+        vec![
+            serde_json::json!({"package": "second", "reason": "empty-after-filter"}),
+            serde_json::json!({"package": "third", "reason": "no-matching-target-name"}),
+        ]
+    );
+}
+
 #[test]
 fn lint_specific_file() {
     let t = TempDir::new().unwrap();
@@ -622,6 +946,7 @@ fn lint_specific_file() {
                 break ();
                 ^^^^^^^^^
 
+        lint: 0 errors, 1 warning across 1 package
         "#});
 }
 
@@ -708,6 +1033,7 @@ fn lint_specific_directory() {
              let _y = x * 1;
                       ^^^^^
 
+         lint: 0 errors, 3 warnings across 1 package
         "#});
 }
 
@@ -787,7 +1113,9 @@ fn test_fixer_formatting() {
               result.unwrap();
               ^^^^^^^^^^^^^^^
             
-                  Fixing lib.cairo
+            lint: 0 errors, 2 warnings across 1 package
+                  Fixing lib.cairo (1 fix)
+                   Fixed 1 fix in 1 file
         "#});
     let content = t.child("src/lib.cairo").read_to_string();
     assert_eq!(
@@ -874,6 +1202,7 @@ fn test_linter_with_attribute_macros() {
             let _should_lint = (1 == 1);
                                 ^^^^^^
 
+        lint: 0 errors, 2 warnings across 1 package
         "#});
 }
 
@@ -960,39 +1289,112 @@ fn test_linter_with_attribute_macros_complex() {
             let _should_lint = (1 == 1);
                                 ^^^^^^
         
+        lint: 0 errors, 2 warnings across 1 package
         "#});
 }
 
 #[test]
-fn test_with_derive_macro() {
+fn lint_include_generated() {
     let temp = TempDir::new().unwrap();
+    let t = temp.child("some");
+    CairoPluginProjectBuilder::default()
+        .add_primitive_token_dep()
+        .lib_rs(indoc! {r#"
+          use cairo_lang_macro::{attribute_macro, quote, ProcMacroResult, TokenStream};
+
+          #[attribute_macro]
+          pub fn generate_lintable(_args: TokenStream, item: TokenStream) -> ProcMacroResult {
+              let ts = quote! {
+                  #item
+
+                  fn generated_function() {
+                      let _generated_lint = (1 == 1);
+                  }
+              };
+              ProcMacroResult::new(ts)
+          }
+      "#})
+        .build(&t);
     let project = temp.child("hello");
     ProjectBuilder::start()
         .name("hello")
         .version("1.0.0")
+        .dep("some", &t)
         .lib_cairo(indoc! {r#"
-          #[derive(Drop)]
-          enum MyEnum {
-              Data: u8,
-              Empty: () // Comment
+          #[generate_lintable]
+          fn foo() {
+              let _user_lint = (1 == 1);
           }
-  "#})
+      "#})
         .build(&project);
 
-    Scarb::quick_snapbox()
-        .arg("lint")
-        // Disable output from Cargo.
-        .env("CARGO_TERM_QUIET", "true")
-        .current_dir(&project)
-        .assert()
-        .success()
-        .stdout_matches(indoc! {r#"
-             Linting hello v1.0.0 ([..]/Scarb.toml)
-        warn: Plugin diagnostic: redundant parentheses in enum variant definition
+    let lint = |args: &[&str]| {
+        let output = Scarb::quick_snapbox()
+            .arg("lint")
+            .args(args)
+            // Disable output from Cargo.
+            .env("CARGO_TERM_QUIET", "true")
+            .current_dir(&project)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{output:?}");
+        String::from_utf8(output.stdout).unwrap()
+    };
+
+    let stdout = lint(&[]);
+    assert!(stdout.contains("let _user_lint = (1 == 1);"), "{stdout}");
+    assert!(
+        !stdout.contains("let _generated_lint = (1 == 1);"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("lint: 0 errors, 1 warning across 1 package"),
+        "{stdout}"
+    );
+
+    let stdout = lint(&["--include-generated"]);
+    assert!(stdout.contains("let _user_lint = (1 == 1);"), "{stdout}");
+    assert!(
+        stdout.contains("let _generated_lint = (1 == 1);"),
+        "{stdout}"
+    );
+    assert!(
+        stdout.contains("lint: 0 errors, 2 warnings across 1 package"),
+        "{stdout}"
+    );
+}
+
+#[test]
+fn test_with_derive_macro() {
+    let temp = TempDir::new().unwrap();
+    let project = temp.child("hello");
+    ProjectBuilder::start()
+        .name("hello")
+        .version("1.0.0")
+        .lib_cairo(indoc! {r#"
+          #[derive(Drop)]
+          enum MyEnum {
+              Data: u8,
+              Empty: () // Comment
+          }
+  "#})
+        .build(&project);
+
+    Scarb::quick_snapbox()
+        .arg("lint")
+        // Disable output from Cargo.
+        .env("CARGO_TERM_QUIET", "true")
+        .current_dir(&project)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+             Linting hello v1.0.0 ([..]/Scarb.toml)
+        warn: Plugin diagnostic: redundant parentheses in enum variant definition
          --> [..]/src/lib.cairo:4:5
             Empty: () // Comment
             ^^^^^^^^^
       
+        lint: 0 errors, 1 warning across 1 package
       "#});
 }
 
@@ -1061,6 +1463,435 @@ fn lint_workspace_with_deny_warnings() {
           if second == false {
              ^^^^^^^^^^^^^^^
 
+      lint: 0 errors, 3 warnings across 3 packages
       error: lint checking `first`, `main`, `second` packages failed due to previous errors
       "#});
 }
+
+#[test]
+fn lint_does_not_compile_unused_dev_macro() {
+    let temp = TempDir::new().unwrap();
+    let t = temp.child("some");
+    CairoPluginProjectBuilder::default().build(&t);
+    let project = temp.child("hello");
+    ProjectBuilder::start()
+        .name("hello")
+        .version("1.0.0")
+        .dev_dep("some", &t)
+        .lib_cairo(indoc! {r#"
+          fn main() {
+              let x = true;
+              if x == false {
+                  println!("x is false");
+              }
+          }
+      "#})
+        .build(&project);
+
+    Scarb::quick_snapbox()
+        .arg("lint")
+        // Disable output from Cargo.
+        .env("CARGO_TERM_QUIET", "true")
+        .current_dir(&project)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+             Linting hello v1.0.0 ([..]/Scarb.toml)
+        warn: Plugin diagnostic: Unnecessary comparison with a boolean value. Use the variable directly.
+         --> [..]/lib.cairo:3:8
+            if x == false {
+               ^^^^^^^^^^
+
+        lint: 0 errors, 1 warning across 1 package
+        "#});
+}
+
+#[test]
+fn lint_allow_attribute_suppresses_diagnostics() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .lib_cairo(indoc! {r#"
+          #[allow(bool_comparison)]
+          fn main() {
+              let x = true;
+              if x == false {
+                  println!("x is false");
+              }
+          }
+
+          fn other() {
+              let x = true;
+              if x == false {
+                  println!("x is false");
+              }
+          }
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("lint")
+        .arg("--deny-warnings")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+               Linting hello v1.0.0 ([..]/Scarb.toml)
+          warn: Plugin diagnostic: Unnecessary comparison with a boolean value. Use the variable directly.
+           --> [..]/lib.cairo:11:8
+              if x == false {
+                 ^^^^^^^^^^
+
+          lint: 0 errors, 1 warning across 1 package
+          error: lint checking `hello` failed due to previous errors
+        "#});
+}
+
+#[test]
+fn explain_known_lint() {
+    Scarb::quick_snapbox()
+        .arg("explain")
+        .arg("bool_comparison")
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            bool_comparison: Unnecessary comparison with a boolean value. Use the variable directly.
+
+            This lint can be fixed automatically with `scarb lint --fix`[..]
+        "#});
+}
+
+#[test]
+fn explain_unknown_lint_suggests_closest() {
+    Scarb::quick_snapbox()
+        .arg("explain")
+        .arg("bool_comparisn")
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: unknown lint code: `bool_comparisn`
+            help: a lint with a similar name exists: `bool_comparison`
+        "#});
+}
+
+#[test]
+fn list_lint_rules() {
+    Scarb::quick_snapbox()
+        .arg("lint")
+        .arg("--list-rules")
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            rule[..]severity
+            ...
+            bool_comparison[..]warn
+            ...
+            panic[..]allow
+            ...
+        "#});
+}
+
+#[test]
+fn list_lint_rules_json() {
+    Scarb::quick_snapbox()
+        .arg("--json")
+        .arg("lint")
+        .arg("--list-rules")
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..]{"name":"bool_comparison","enabled":true,"severity":"warn"}[..]{"name":"panic","enabled":false,"severity":"allow"}[..]
+        "#});
+}
+
+#[test]
+fn lint_staged_files_only() {
+    let t = TempDir::new().unwrap();
+    let bool_comparison = indoc! {r#"
+        fn main() {
+            let x = true;
+            if x == false {
+                println!("x is false");
+            }
+        }
+    "#};
+    ProjectBuilder::start()
+        .name("hello")
+        .lib_cairo(indoc! {r#"
+            mod first;
+            mod second;
+        "#})
+        .src("src/first.cairo", bool_comparison)
+        .src("src/second.cairo", bool_comparison)
+        .build(&t);
+    gitx::init(t.path());
+    gitx::commit(t.path());
+
+    t.child("src/second.cairo")
+        .write_str(&format!("{bool_comparison}\nfn other() {{}}\n"))
+        .unwrap();
+    gitx::git(t.path(), ["add", "src/second.cairo"]);
+
+    Scarb::quick_snapbox()
+        .arg("lint")
+        .arg("--staged")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+               Linting hello v1.0.0 ([..]/Scarb.toml)
+          warn: Plugin diagnostic: Unnecessary comparison with a boolean value. Use the variable directly.
+           --> [..]/second.cairo:3:8
+              if x == false {
+                 ^^^^^^^^^^
+
+          lint: 0 errors, 1 warning across 1 package
+        "#});
+}
+
+#[test]
+fn lint_files_changed_since_rev() {
+    let t = TempDir::new().unwrap();
+    let bool_comparison = indoc! {r#"
+        fn main() {
+            let x = true;
+            if x == false {
+                println!("x is false");
+            }
+        }
+    "#};
+    ProjectBuilder::start()
+        .name("hello")
+        .lib_cairo(indoc! {r#"
+            mod first;
+            mod second;
+        "#})
+        .src("src/first.cairo", bool_comparison)
+        .src("src/second.cairo", bool_comparison)
+        .build(&t);
+    gitx::init(t.path());
+    gitx::commit(t.path());
+    gitx::git(t.path(), ["tag", "base"]);
+
+    t.child("src/second.cairo")
+        .write_str(&format!("{bool_comparison}\nfn other() {{}}\n"))
+        .unwrap();
+    gitx::commit(t.path());
+
+    Scarb::quick_snapbox()
+        .args(["lint", "--since", "base"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+               Linting hello v1.0.0 ([..]/Scarb.toml)
+          warn: Plugin diagnostic: Unnecessary comparison with a boolean value. Use the variable directly.
+           --> [..]/second.cairo:3:8
+              if x == false {
+                 ^^^^^^^^^^
+
+          lint: 0 errors, 1 warning across 1 package
+        "#});
+}
+
+#[test]
+fn lint_since_rejects_option_like_rev() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .lib_cairo(indoc! {r#"
+          fn main() {
+              let x = true;
+              if x == false {
+                  println!("x is false");
+              }
+          }
+        "#})
+        .build(&t);
+    gitx::init(t.path());
+    gitx::commit(t.path());
+
+    let injected = t.child("injected.txt");
+    Scarb::quick_snapbox()
+        .arg("lint")
+        .arg(format!("--since=--output={}", injected.path().display()))
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+          warn: cannot list files changed since `--output=[..]injected.txt` of package `hello`, linting all files
+          reason: invalid revision `--output=[..]injected.txt`, revisions must not start with `-`
+          ...
+               Linting hello v1.0.0 ([..]/Scarb.toml)
+          warn: Plugin diagnostic: Unnecessary comparison with a boolean value. Use the variable directly.
+           --> [..]/lib.cairo:3:8
+              if x == false {
+                 ^^^^^^^^^^
+
+          lint: 0 errors, 1 warning across 1 package
+        "#});
+    assert!(!injected.exists());
+}
+
+#[test]
+fn lint_staged_outside_git_repository_lints_everything() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .lib_cairo(indoc! {r#"
+          fn main() {
+              let x = true;
+              if x == false {
+                  println!("x is false");
+              }
+          }
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("lint")
+        .arg("--staged")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+          warn: cannot list staged files of package `hello`, linting all files
+          reason: [..]
+          ...
+               Linting hello v1.0.0 ([..]/Scarb.toml)
+          warn: Plugin diagnostic: Unnecessary comparison with a boolean value. Use the variable directly.
+           --> [..]/lib.cairo:3:8
+              if x == false {
+                 ^^^^^^^^^^
+
+          lint: 0 errors, 1 warning across 1 package
+        "#});
+}
+
+#[test]
+fn lint_writes_output_file() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .lib_cairo(indoc! {r#"
+          fn main() {
+              let x = true;
+              if x == false {
+                  println!("x is false");
+              }
+          }
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("lint")
+        .arg("--output-file")
+        .arg("lint/report.txt")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+               Linting hello v1.0.0 ([..]/Scarb.toml)
+          warn: Plugin diagnostic: Unnecessary comparison with a boolean value. Use the variable directly.
+           --> [..]/lib.cairo:3:8
+              if x == false {
+                 ^^^^^^^^^^
+
+          lint: 0 errors, 1 warning across 1 package
+        "#});
+
+    snapbox::assert_matches(
+        indoc! {r#"
+          warn: Plugin diagnostic: Unnecessary comparison with a boolean value. Use the variable directly.
+           --> [..]/lib.cairo:3:8
+              if x == false {
+                 ^^^^^^^^^^
+
+        "#},
+        t.child("target/lint/report.txt").read_to_string(),
+    );
+}
+
+#[test]
+fn lint_manifest_unused_dependency() {
+    let t = TempDir::new().unwrap();
+    let used = t.child("used");
+    ProjectBuilder::start()
+        .name("used")
+        .lib_cairo("pub fn f() -> felt252 { 42 }")
+        .build(&used);
+    let unused = t.child("unused");
+    ProjectBuilder::start()
+        .name("unused")
+        .lib_cairo("pub fn g() -> felt252 { 42 }")
+        .build(&unused);
+    let hello = t.child("hello");
+    ProjectBuilder::start()
+        .name("hello")
+        .dep("used", &used)
+        .dep("unused", &unused)
+        .lib_cairo("fn main() -> felt252 { used::f() }")
+        .build(&hello);
+
+    Scarb::quick_snapbox()
+        .args(["lint", "--manifest"])
+        .current_dir(&hello)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+               Linting [..]/hello/Scarb.toml
+          warn: unused dependency `unused`, it is not referred to by any crate of package `hello`
+           --> [..]/hello/Scarb.toml
+          lint: 0 errors, 1 warning across 1 package
+        "#});
+}
+
+#[test]
+fn lint_manifest_duplicate_external_contracts() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .manifest_extra(indoc! {r#"
+            [[target.starknet-contract]]
+            build-external-contracts = ["hello::Balance", "hello::*", "hello::Balance"]
+        "#})
+        .dep_starknet()
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["lint", "--manifest", "--deny-warnings"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+               Linting [..]/Scarb.toml
+          warn: duplicate external contract selector `hello::Balance` in target `hello` of kind `starknet-contract`
+           --> [..]/Scarb.toml
+          lint: 0 errors, 1 warning across 1 package
+          error: lint checking `hello` manifests failed due to previous errors
+        "#});
+}
+
+#[test]
+fn lint_manifest_target_without_effect() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .manifest_extra(indoc! {r#"
+            [lib]
+            sierra = false
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["lint", "--manifest"])
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+               Linting [..]/Scarb.toml
+          warn: target `hello` of kind `lib` has no effect, all of its outputs are disabled
+           --> [..]/Scarb.toml
+          lint: 0 errors, 1 warning across 1 package
+        "#});
+}
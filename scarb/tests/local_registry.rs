@@ -8,6 +8,7 @@ use scarb_test_support::command::Scarb;
 use scarb_test_support::fsx::ChildPathEx;
 use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
 use scarb_test_support::registry::local::LocalRegistry;
+use scarb_test_support::workspace_builder::WorkspaceBuilder;
 
 #[test]
 fn usage() {
@@ -314,3 +315,233 @@ fn publish_overwrites_existing() {
 // TODO(mkaput): Test errors properly when package is in index, but tarball is missing.
 // TODO(mkaput): Test publishing with target-specific dependencies.
 // TODO(mkaput): Test offline mode.
+
+#[test]
+fn default_registry_from_workspace_manifest() {
+    let mut registry = LocalRegistry::create();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1"))
+        .manifest_extra(formatdoc! {r#"
+            [registries.default]
+            index = "{registry}"
+        "#})
+        .lib_cairo(r#"fn f() -> felt252 { bar::f() }"#)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("tree")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            foo v0.1.0 ([..])
+            └── bar v1.0.0
+        "#});
+
+    let lockfile = t.child("Scarb.lock").read_to_string();
+    assert!(lockfile.contains(r#"source = "registry+https://scarbs.xyz/""#));
+}
+
+#[test]
+fn default_registry_from_env() {
+    let mut registry = LocalRegistry::create();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1"))
+        .lib_cairo(r#"fn f() -> felt252 { bar::f() }"#)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("tree")
+        .env("SCARB_DEFAULT_REGISTRY_INDEX", &registry.url)
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            foo v0.1.0 ([..])
+            └── bar v1.0.0
+        "#});
+}
+
+#[test]
+fn default_registry_from_workspace_manifest_overrides_env() {
+    let empty = LocalRegistry::create();
+    let mut registry = LocalRegistry::create();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1"))
+        .manifest_extra(formatdoc! {r#"
+            [registries.default]
+            index = "{registry}"
+        "#})
+        .lib_cairo(r#"fn f() -> felt252 { bar::f() }"#)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("tree")
+        .env("SCARB_DEFAULT_REGISTRY_INDEX", &empty.url)
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            foo v0.1.0 ([..])
+            └── bar v1.0.0
+        "#});
+}
+
+#[test]
+fn invalid_default_registry_from_env() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .env("SCARB_DEFAULT_REGISTRY_INDEX", "not a url")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: invalid URL in `SCARB_DEFAULT_REGISTRY_INDEX` environment variable: not a url
+
+            Caused by:
+                relative URL without a base
+        "#});
+}
+
+#[test]
+fn registries_in_member_manifest_are_ignored() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("first")
+        .manifest_extra(indoc! {r#"
+            [registries.default]
+            index = "https://mirror.example.com/"
+        "#})
+        .build(&t.child("first"));
+    WorkspaceBuilder::start().add_member("first").build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            warn: `[registries]` section is only read from the workspace root manifest, ignoring it in: [..]/first/Scarb.toml
+        "#});
+}
+
+#[test]
+fn unsupported_named_registry() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .manifest_extra(indoc! {r#"
+            [registries.mirror]
+            index = "https://mirror.example.com/"
+        "#})
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: failed to parse manifest at: [..]/Scarb.toml
+
+            Caused by:
+                unsupported registry `mirror` in the `[registries]` section, only the `default` registry can be configured
+        "#});
+}
+
+fn mirror_test_case(pure_mirrors: bool) -> (TempDir, LocalRegistry, LocalRegistry) {
+    let primary = LocalRegistry::create();
+    let mut mirror = LocalRegistry::create();
+    mirror.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1"))
+        .manifest_extra(formatdoc! {r#"
+            [registries.default]
+            index = "{primary}"
+            mirrors = ["{mirror}"]
+            pure-mirrors = {pure_mirrors}
+        "#})
+        .lib_cairo(r#"fn f() -> felt252 { bar::f() }"#)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            [..] Mirrored bar from registry: file://[..]
+        "#});
+
+    (t, primary, mirror)
+}
+
+#[test]
+fn mirror_serves_package_missing_in_registry() {
+    let (t, _primary, mirror) = mirror_test_case(false);
+    let lockfile = t.child("Scarb.lock").read_to_string();
+    assert!(lockfile.contains(&format!(r#"source = "registry+{mirror}""#)));
+}
+
+#[test]
+fn pure_mirror_keeps_registry_identity() {
+    let (t, _primary, _mirror) = mirror_test_case(true);
+    let lockfile = t.child("Scarb.lock").read_to_string();
+    assert!(lockfile.contains(r#"source = "registry+https://scarbs.xyz/""#));
+
+    Scarb::quick_snapbox()
+        .arg("build")
+        .current_dir(&t)
+        .assert()
+        .success();
+}
@@ -6,11 +6,14 @@ use std::path::Path;
 use assert_fs::TempDir;
 use assert_fs::prelude::*;
 use fs_extra::dir::{CopyOptions, copy};
-use indoc::indoc;
+use indoc::{formatdoc, indoc};
 use snapbox::cmd::Command;
 
+use scarb_build_metadata::CAIRO_VERSION;
 use scarb_test_support::cargo::cargo_bin;
-use scarb_test_support::command::Scarb;
+use scarb_test_support::command::{CommandExt, Scarb};
+use scarb_test_support::fsx::ChildPathEx;
+use scarb_test_support::gitx;
 use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
 use scarb_test_support::registry::local::LocalRegistry;
 use test_for_each_example::test_for_each_example;
@@ -79,3 +82,229 @@ fn store_checksum_for_registry_dependencies() {
     t.child("Scarb.lock")
         .assert(predicates::str::contains(r#"checksum = ""#));
 }
+
+#[test]
+fn store_std_identity() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    t.child("Scarb.lock").assert(formatdoc! {r#"
+        # Code generated by scarb DO NOT EDIT.
+        version = 1
+
+        [[package]]
+        name = "foo"
+        version = "0.1.0"
+
+        [std]
+        version = "{CAIRO_VERSION}"
+        source = "std"
+    "#});
+}
+
+#[test]
+fn print_lockfile_as_json() {
+    let mut registry = LocalRegistry::create();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .lib_cairo(r#"fn f() -> felt252 { bar::f() }"#)
+        .build(&t);
+
+    let json = Scarb::quick_snapbox()
+        .args(["metadata", "--lockfile"])
+        .current_dir(&t)
+        .stdout_json::<serde_json::Value>();
+
+    let lock: toml::Value = fs::read_to_string(t.child("Scarb.lock"))
+        .unwrap()
+        .parse()
+        .unwrap();
+    let toml_packages = lock["package"].as_array().unwrap();
+    let json_packages = json["package"].as_array().unwrap();
+    assert_eq!(json_packages.len(), toml_packages.len());
+    for (json_package, toml_package) in json_packages.iter().zip(toml_packages) {
+        assert_eq!(json_package["name"].as_str(), toml_package["name"].as_str());
+        assert_eq!(
+            json_package.get("source").and_then(|s| s.as_str()),
+            toml_package.get("source").and_then(|s| s.as_str())
+        );
+        assert_eq!(
+            json_package.get("checksum").and_then(|s| s.as_str()),
+            toml_package.get("checksum").and_then(|s| s.as_str())
+        );
+    }
+
+    let bar = json_packages
+        .iter()
+        .find(|package| package["name"] == "bar")
+        .unwrap();
+    assert!(bar["source"].as_str().unwrap().starts_with("registry+"));
+    assert_eq!(json["std"]["version"], CAIRO_VERSION);
+}
+
+#[test]
+fn frozen_forbids_lockfile_changes() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("dep")
+        .version("0.1.0")
+        .build(&t.child("dep"));
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .build(&t.child("hello"));
+
+    // Missing lockfile must not be created.
+    Scarb::quick_snapbox()
+        .args(["--frozen", "fetch"])
+        .current_dir(t.child("hello"))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: the lockfile needs to be updated, but --frozen was passed to prevent this
+        "#});
+    t.child("hello/Scarb.lock")
+        .assert(predicates::path::missing());
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(t.child("hello"))
+        .assert()
+        .success();
+    let lockfile = t.child("hello/Scarb.lock").read_to_string();
+
+    Scarb::quick_snapbox()
+        .args(["--frozen", "fetch"])
+        .current_dir(t.child("hello"))
+        .assert()
+        .success();
+
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .dep("dep", Dep.path("../dep"))
+        .build(&t.child("hello"));
+
+    Scarb::quick_snapbox()
+        .args(["--frozen", "fetch"])
+        .current_dir(t.child("hello"))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: the lockfile needs to be updated, but --frozen was passed to prevent this
+        "#});
+    assert_eq!(t.child("hello/Scarb.lock").read_to_string(), lockfile);
+}
+
+#[test]
+fn frozen_ignores_std_version_changes() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    // Simulate a lockfile written by a different Scarb version.
+    let lockfile = t
+        .child("Scarb.lock")
+        .read_to_string()
+        .replace(CAIRO_VERSION, "2.0.0");
+    t.child("Scarb.lock").write_str(&lockfile).unwrap();
+
+    Scarb::quick_snapbox()
+        .args(["--frozen", "fetch"])
+        .current_dir(&t)
+        .assert()
+        .success();
+    assert_eq!(t.child("Scarb.lock").read_to_string(), lockfile);
+}
+
+#[test]
+fn invalid_precise_revision_in_lockfile() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .build(&t);
+    t.child("Scarb.lock")
+        .write_str(indoc! {r#"
+            # Code generated by scarb DO NOT EDIT.
+            version = 1
+
+            [[package]]
+            name = "dep"
+            version = "0.1.0"
+            source = "git+https://example.com/dep.git#1f06df93"
+        "#})
+        .unwrap();
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: failed to parse lockfile content
+
+            Caused by:
+                TOML parse error at line 7, column 10
+            ...
+                invalid precise revision `1f06df93` for source: git+https://example.com/dep.git
+                note: expected a full, 40 characters long hexadecimal Git commit hash
+                help: remove `Scarb.lock` and run Scarb again to regenerate the lockfile
+        "#});
+}
+
+#[test]
+fn frozen_forbids_network() {
+    let git_dep = gitx::new("dep", |t| {
+        ProjectBuilder::start()
+            .name("dep")
+            .lib_cairo("pub fn hello() -> felt252 { 42 }")
+            .build(&t)
+    });
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .dep("dep", &git_dep)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .args(["--frozen", "fetch"])
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            ...
+            [..]cannot fetch from `[..]dep` in frozen mode
+            ...
+        "#});
+    t.child("Scarb.lock").assert(predicates::path::missing());
+}
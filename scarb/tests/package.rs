@@ -1890,7 +1890,7 @@ fn package_ignores_patches() {
     let path_dep = t.child("path_dep");
     ProjectBuilder::start()
         .name("foo")
-        .version("1.0.0")
+        .version("0.2.1")
         .build(&path_dep);
 
     ProjectBuilder::start()
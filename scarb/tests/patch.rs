@@ -72,7 +72,7 @@ fn patch_scarbs_with_path() {
         .build(&patch);
     ProjectBuilder::start()
         .name("first")
-        .dep("foo", Dep.version("1.0.0"))
+        .dep("foo", Dep.version("2.0.0"))
         .build(&t.child("first"));
     ProjectBuilder::start()
         .name("second")
@@ -80,7 +80,7 @@ fn patch_scarbs_with_path() {
         .build(&t.child("second"));
     ProjectBuilder::start()
         .name("third")
-        .dep("foo", Dep.version("3.0.0"))
+        .dep("foo", Dep.version("2"))
         .build(&t.child("third"));
     WorkspaceBuilder::start()
         .add_member("first")
@@ -132,7 +132,7 @@ fn patch_scarbs_with_git() {
     let t = TempDir::new().unwrap();
     ProjectBuilder::start()
         .name("first")
-        .dep("foo", Dep.version("1.0.0"))
+        .dep("foo", Dep.version("2.0.0"))
         .build(&t.child("first"));
     ProjectBuilder::start()
         .name("second")
@@ -140,7 +140,7 @@ fn patch_scarbs_with_git() {
         .build(&t.child("second"));
     ProjectBuilder::start()
         .name("third")
-        .dep("foo", Dep.version("3.0.0"))
+        .dep("foo", Dep.version("2"))
         .build(&t.child("third"));
     WorkspaceBuilder::start()
         .add_member("first")
@@ -193,7 +193,7 @@ fn patch_scarbs_with_path_by_full_url() {
         .build(&patch);
     ProjectBuilder::start()
         .name("first")
-        .dep("foo", Dep.version("1.0.0"))
+        .dep("foo", Dep.version("2.0.0"))
         .build(&t.child("first"));
     ProjectBuilder::start()
         .name("second")
@@ -201,7 +201,7 @@ fn patch_scarbs_with_path_by_full_url() {
         .build(&t.child("second"));
     ProjectBuilder::start()
         .name("third")
-        .dep("foo", Dep.version("3.0.0"))
+        .dep("foo", Dep.version("2"))
         .build(&t.child("third"));
     WorkspaceBuilder::start()
         .add_member("first")
@@ -254,7 +254,7 @@ fn patch_not_existing_registry_with_path() {
         .name("first")
         .dep(
             "foo",
-            Dep.version("1.0.0")
+            Dep.version("2.0.0")
                 .with("registry", "https://this-registry-does-not-exist/"),
         )
         .build(&t.child("first"));
@@ -266,7 +266,7 @@ fn patch_not_existing_registry_with_path() {
         .name("third")
         .dep(
             "foo",
-            Dep.version("3.0.0")
+            Dep.version("2")
                 .with("registry", "https://this-registry-does-not-exist/"),
         )
         .build(&t.child("third"));
@@ -594,7 +594,7 @@ fn patch_registry_with_registry() {
     registry.publish(|t| {
         ProjectBuilder::start()
             .name("bar")
-            .version("2.0.0")
+            .version("1.1.0")
             .build(t);
     });
     let t = TempDir::new().unwrap();
@@ -605,7 +605,7 @@ fn patch_registry_with_registry() {
         .manifest_extra(formatdoc! {r#"
             [patch."{}"]
             bar = {}
-        "#, registry.url.clone(), Dep.version("2").registry(&registry).build()})
+        "#, registry.url.clone(), Dep.version("=1.0.0").registry(&registry).build()})
         .build(&t);
     // Assert no warnings are emitted.
     Scarb::quick_snapbox()
@@ -627,7 +627,7 @@ fn patch_registry_with_registry() {
         .sorted()
         .collect_vec();
     let expected = vec![
-        "bar 2.0.0 (registry+file:[..])".to_string(),
+        "bar 1.0.0 (registry+file:[..])".to_string(),
         "core [..] (std)".to_string(),
         "foo 0.1.0 (path+[..]Scarb.toml)".to_string(),
     ];
@@ -636,6 +636,94 @@ fn patch_registry_with_registry() {
     }
 }
 
+#[test]
+fn patch_registry_with_path() {
+    let mut registry = LocalRegistry::create();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .build(t);
+    });
+    let t = TempDir::new().unwrap();
+    let patch = t.child("patch");
+    ProjectBuilder::start()
+        .name("bar")
+        .version("1.2.0")
+        .build(&patch);
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .manifest_extra(formatdoc! {r#"
+            [patch."{}"]
+            bar = {}
+        "#, registry.url.clone(), patch.build()})
+        .build(&t);
+    // Assert no warnings are emitted.
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_eq("");
+    let metadata = Scarb::quick_snapbox()
+        .arg("--json")
+        .arg("metadata")
+        .arg("--format-version=1")
+        .current_dir(&t)
+        .stdout_json::<Metadata>();
+    let packages = metadata
+        .packages
+        .into_iter()
+        .map(|p| p.id.to_string())
+        .sorted()
+        .collect_vec();
+    let expected = vec![
+        "bar 1.2.0 (path+file:[..]patch[..]Scarb.toml)".to_string(),
+        "core [..] (std)".to_string(),
+        "foo 0.1.0 (path+[..]Scarb.toml)".to_string(),
+    ];
+    for (expected, real) in zip(&expected, packages) {
+        snapbox::assert_matches(expected, real);
+    }
+}
+
+#[test]
+fn patch_version_must_match_requirement() {
+    let mut registry = LocalRegistry::create();
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .build(t);
+    });
+    let t = TempDir::new().unwrap();
+    let patch = t.child("patch");
+    ProjectBuilder::start()
+        .name("bar")
+        .version("2.0.0")
+        .build(&patch);
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .manifest_extra(formatdoc! {r#"
+            [patch."{}"]
+            bar = {}
+        "#, registry.url.clone(), patch.build()})
+        .build(&t);
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .current_dir(&t)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+            error: patch `bar v2.0.0 ([..]patch[..]Scarb.toml)` is not compatible with dependency `bar` of package `foo v0.1.0 ([..]Scarb.toml)`
+            version `2.0.0` does not match requirement `^1` of the original dependency from `registry+file:[..]`
+        "#});
+}
+
 #[test]
 fn cannot_define_default_registry_both_short_and_long_name() {
     let t = TempDir::new().unwrap();
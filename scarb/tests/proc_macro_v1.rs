@@ -151,7 +151,7 @@ fn can_use_json_output() {
     );
     let (last, lines) = lines.split_last().unwrap();
     assert_matches(
-        r#"{"status":"finished","message":"checking `dev` profile target(s) in [..]"}"#,
+        r#"{"status":"finished","message":"checking `dev` profile target(s) in [..]","profile":"dev","elapsed_ms":[..],"compilation_units":1,"artifacts":0}"#,
         last,
     );
     // Line from Cargo.
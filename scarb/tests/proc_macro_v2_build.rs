@@ -147,7 +147,7 @@ fn can_use_json_output() {
     );
     let (last, lines) = lines.split_last().unwrap();
     assert_matches(
-        r#"{"status":"finished","message":"checking `dev` profile target(s) in [..]"}"#,
+        r#"{"status":"finished","message":"checking `dev` profile target(s) in [..]","profile":"dev","elapsed_ms":[..],"compilation_units":1,"artifacts":0}"#,
         last,
     );
     // Line from Cargo.
@@ -155,6 +155,43 @@ fn can_use_json_output() {
     assert_matches(r#"{"reason":"build-finished","success":true}"#, last);
 }
 
+#[test]
+fn check_and_build_report_same_compilation_units_count() {
+    let temp = TempDir::new().unwrap();
+    let t = temp.child("some");
+    CairoPluginProjectBuilder::default().build(&t);
+    let project = temp.child("hello");
+    ProjectBuilder::start()
+        .name("hello")
+        .version("1.0.0")
+        .dep("some", &t)
+        .build(&project);
+
+    let compilation_units = |command: &str| {
+        let output = Scarb::quick_snapbox()
+            .arg("--json")
+            .arg(command)
+            // Disable output from Cargo.
+            .env("CARGO_TERM_QUIET", "true")
+            .current_dir(&project)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        let stdout = String::from_utf8_lossy(&output.stdout).to_string();
+        let finished: serde_json::Value =
+            serde_json::from_str(stdout.lines().last().unwrap()).unwrap();
+        finished["compilation_units"].as_u64().unwrap()
+    };
+
+    // The `some` proc macro and the `hello` library.
+    assert_eq!(compilation_units("check"), 2);
+    assert_eq!(compilation_units("build"), 2);
+}
+
 #[test]
 fn compile_cairo_plugin_with_lib_target() {
     let t = TempDir::new().unwrap();
@@ -1,9 +1,13 @@
+use std::fs;
+use std::time::Duration;
+
 use assert_fs::TempDir;
 use assert_fs::prelude::*;
 use indoc::indoc;
 
 use scarb_test_support::command::Scarb;
 use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
+use scarb_test_support::registry::http::HttpRegistry;
 use scarb_test_support::registry::local::LocalRegistry;
 
 #[test]
@@ -48,3 +52,62 @@ fn checksum_mismatch() {
             failed to verify the checksum of downloaded archive
         "#});
 }
+
+#[test]
+fn corrupted_cache_checksum_mismatch() {
+    let cache_dir = TempDir::new().unwrap();
+
+    let mut registry = HttpRegistry::serve(None);
+    registry.publish(|t| {
+        ProjectBuilder::start()
+            .name("bar")
+            .version("1.0.0")
+            .lib_cairo(r#"fn f() -> felt252 { 0 }"#)
+            .build(t);
+    });
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("foo")
+        .version("0.1.0")
+        .dep("bar", Dep.version("1").registry(&registry))
+        .lib_cairo(r#"fn f() -> felt252 { bar::f() }"#)
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .env("SCARB_CACHE", cache_dir.path())
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .success();
+
+    let dl_dir = cache_dir.child("registry/dl");
+    let source_dir = fs::read_dir(dl_dir.path())
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    fs::write(
+        source_dir.join("bar-1.0.0.tar.zst"),
+        "This is not the archive that has been downloaded from the registry.",
+    )
+    .unwrap();
+
+    Scarb::quick_snapbox()
+        .arg("fetch")
+        .env("SCARB_CACHE", cache_dir.path())
+        .current_dir(&t)
+        .timeout(Duration::from_secs(10))
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+        error: failed to download package: bar v1.0.0 (registry+http://[..])
+
+        Caused by:
+            0: cached archive is corrupted: [..]bar-1.0.0.tar.zst
+               help: run the following to wipe entire cache: scarb cache clean
+            1: failed to verify the checksum of downloaded archive
+        "#});
+}
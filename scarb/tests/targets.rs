@@ -0,0 +1,78 @@
+use assert_fs::TempDir;
+use assert_fs::prelude::*;
+use indoc::indoc;
+
+use scarb_test_support::command::Scarb;
+use scarb_test_support::project_builder::ProjectBuilder;
+
+#[test]
+fn lists_auto_detected_targets() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .src("tests/foo.cairo", "")
+        .src("tests/bar.cairo", "")
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("targets")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            hello v0.1.0 ([..]Scarb.toml)
+                lib hello
+                test hello_unittest
+                test hello_[..] (group: hello_integrationtest)
+                test hello_[..] (group: hello_integrationtest)
+        "#});
+}
+
+#[test]
+fn json_output() {
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .version("0.1.0")
+        .src("tests/foo.cairo", "")
+        .build(&t);
+
+    let output = Scarb::quick_snapbox()
+        .arg("--json")
+        .arg("targets")
+        .current_dir(&t)
+        .assert()
+        .success();
+
+    let stdout = String::from_utf8(output.get_output().stdout.clone()).unwrap();
+    let packages: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+    let packages = packages.as_array().unwrap();
+    assert_eq!(packages.len(), 1);
+
+    let targets = packages[0]["targets"].as_array().unwrap();
+    let summary = targets
+        .iter()
+        .map(|target| {
+            (
+                target["kind"].as_str().unwrap(),
+                target["name"].as_str().unwrap(),
+                target["group_id"].as_str(),
+                target["params"]["test-type"].as_str(),
+            )
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(
+        summary,
+        vec![
+            ("lib", "hello", None, None),
+            ("test", "hello_unittest", None, Some("unit")),
+            (
+                "test",
+                "hello_foo",
+                Some("hello_integrationtest"),
+                Some("integration")
+            ),
+        ]
+    );
+}
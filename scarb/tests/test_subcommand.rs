@@ -1,4 +1,5 @@
 use assert_fs::TempDir;
+use assert_fs::prelude::*;
 use indoc::indoc;
 
 use scarb_test_support::command::Scarb;
@@ -93,3 +94,53 @@ fn assert_macros_available() {
         .assert()
         .success();
 }
+
+#[test]
+fn ignore_cairo_version_of_test_dependencies() {
+    let t = TempDir::new().unwrap();
+    let dep = t.child("dep");
+    ProjectBuilder::start()
+        .name("dep")
+        .cairo_version("33.33.0")
+        .build(&dep);
+    let hello = t.child("hello");
+    ProjectBuilder::start()
+        .name("hello")
+        .dev_dep("dep", &dep)
+        .manifest_extra(indoc! {r#"
+            [scripts]
+            test = "scarb build --test"
+        "#})
+        .build(&hello);
+
+    Scarb::quick_snapbox()
+        .arg("test")
+        .current_dir(&hello)
+        .assert()
+        .failure()
+        .stdout_matches(indoc! {r#"
+             Running test hello (scarb build --test)
+            error: the required Cairo version of package dep is not compatible with current version
+            Cairo version required: ^33.33.0
+            Cairo version of Scarb: [..]
+
+            error: the required Cairo version of each package must match the current Cairo version
+            help: pass `--ignore-cairo-version` to ignore Cairo version mismatch
+            ...
+        "#});
+
+    Scarb::quick_snapbox()
+        .args(["test", "--ignore-cairo-version"])
+        .current_dir(&hello)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+             Running test hello (scarb build --test)
+            warn: the required Cairo version of package dep is not compatible with current version
+            Cairo version required: ^33.33.0
+            Cairo version of Scarb: [..]
+
+            [..] Compiling test(hello_unittest) hello v1.0.0 ([..]Scarb.toml)
+            [..]  Finished `dev` profile target(s) in [..]
+        "#});
+}
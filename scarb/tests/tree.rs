@@ -3,6 +3,7 @@ use assert_fs::prelude::*;
 use indoc::indoc;
 
 use scarb_test_support::command::Scarb;
+use scarb_test_support::gitx;
 use scarb_test_support::project_builder::{Dep, DepBuilder, ProjectBuilder};
 use scarb_test_support::workspace_builder::WorkspaceBuilder;
 
@@ -512,3 +513,56 @@ fn no_dedupe_cycle() {
                 └── root v0.1.0 ([..]) (*)
         "#});
 }
+
+#[test]
+fn duplicate_sources() {
+    let git_dep = gitx::new("dep", |t| {
+        ProjectBuilder::start()
+            .name("first")
+            .build(&t.child("first"));
+        ProjectBuilder::start()
+            .name("second")
+            .build(&t.child("second"));
+    });
+    git_dep.tag("v1");
+
+    let t = TempDir::new().unwrap();
+    ProjectBuilder::start()
+        .name("hello")
+        .dep("first", &git_dep)
+        .dep("second", git_dep.with("tag", "v1"))
+        .build(&t);
+
+    Scarb::quick_snapbox()
+        .arg("tree")
+        .arg("--duplicate-sources")
+        .current_dir(&t)
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            file://[..]/dep is referenced by 2 sources:
+                git+file://[..]/dep?tag=v1#[..]
+                git+file://[..]/dep#[..]
+        "#});
+}
+
+#[test]
+fn no_duplicate_sources() {
+    let t = TempDir::new().unwrap();
+    let dep = t.child("dep");
+    ProjectBuilder::start().name("dep").build(&dep);
+    ProjectBuilder::start()
+        .name("hello")
+        .dep("dep", &dep)
+        .build(&t.child("hello"));
+
+    Scarb::quick_snapbox()
+        .arg("tree")
+        .arg("--duplicate-sources")
+        .current_dir(t.child("hello"))
+        .assert()
+        .success()
+        .stdout_matches(indoc! {r#"
+            no repositories referenced by multiple sources
+        "#});
+}
@@ -266,3 +266,42 @@ fn inherited_deps_can_override_features_list() {
         Some(vec!["other".to_string(), "some".to_string()])
     );
 }
+
+#[test]
+#[cfg(unix)]
+fn path_dependency_through_symlink_resolves_to_real_path() {
+    let t = TempDir::new().unwrap();
+    let dep = t.child("dep");
+    ProjectBuilder::start().name("dep").build(&dep);
+    let dep_link = t.child("dep_link");
+    std::os::unix::fs::symlink(dep.path(), dep_link.path()).unwrap();
+
+    ProjectBuilder::start()
+        .name("first")
+        .dep("dep", &dep)
+        .build(&t.child("first"));
+    ProjectBuilder::start()
+        .name("second")
+        .dep("dep", &dep_link)
+        .build(&t.child("second"));
+    WorkspaceBuilder::start()
+        .add_member("first")
+        .add_member("second")
+        .build(&t);
+
+    let metadata = Scarb::quick_snapbox()
+        .args(["--json", "metadata", "--format-version", "1"])
+        .current_dir(&t)
+        .stdout_json::<Metadata>();
+
+    let dep_packages = metadata
+        .packages
+        .iter()
+        .filter(|package| package.name == "dep")
+        .collect::<Vec<_>>();
+    assert_eq!(dep_packages.len(), 1);
+    assert_eq!(
+        dep_packages[0].root.as_std_path(),
+        fsx::canonicalize(dep.path()).unwrap()
+    );
+}
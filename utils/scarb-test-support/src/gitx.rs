@@ -42,6 +42,16 @@ impl GitProject {
     pub fn tag(&self, name: &str) {
         self.git(["tag", "-a", name, "-m", "test tag"])
     }
+
+    pub fn rev_parse(&self, rev: &str) -> String {
+        let output = git_command()
+            .args(["rev-parse", rev])
+            .current_dir(self.p.path())
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        String::from_utf8(output.stdout).unwrap().trim().to_string()
+    }
 }
 
 impl fmt::Display for GitProject {
@@ -25,6 +25,7 @@ pub struct ProjectBuilder {
     src: HashMap<Utf8PathBuf, String>,
     deps: Vec<(String, Value)>,
     dev_deps: Vec<(String, Value)>,
+    tools: Vec<(String, toml::Value)>,
     manifest_package_extra: String,
     manifest_extra: String,
 }
@@ -46,6 +47,7 @@ impl ProjectBuilder {
             )]),
             deps: Vec::new(),
             dev_deps: Vec::new(),
+            tools: Vec::new(),
             manifest_package_extra: String::new(),
             manifest_extra: String::new(),
         }
@@ -127,6 +129,12 @@ impl ProjectBuilder {
         self.dev_dep_builtin("cairo_test")
     }
 
+    /// Adds a `[tool.<name>]` table to the manifest, `value` must be a TOML table.
+    pub fn tool(mut self, name: impl ToString, value: toml::Value) -> Self {
+        self.tools.push((name.to_string(), value));
+        self
+    }
+
     pub fn manifest_package_extra(mut self, extra: impl ToString) -> Self {
         self.manifest_package_extra = extra.to_string();
         self
@@ -164,6 +172,18 @@ impl ProjectBuilder {
                 doc["dev-dependencies"][name.clone()] = Item::Value(dep.clone());
             }
         }
+        if !self.tools.is_empty() {
+            let mut tool = toml_edit::Table::new();
+            tool.set_implicit(true);
+            for (name, value) in &self.tools {
+                let value = toml::to_string(value)
+                    .expect("tool configuration must be a table")
+                    .parse::<DocumentMut>()
+                    .unwrap();
+                tool.insert(name, value.as_item().clone());
+            }
+            doc["tool"] = Item::Table(tool);
+        }
         let mut manifest = doc.to_string();
 
         if !self.manifest_extra.is_empty() {
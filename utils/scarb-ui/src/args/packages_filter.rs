@@ -2,8 +2,9 @@ use std::collections::HashSet;
 use std::ffi::OsString;
 use std::fmt;
 
-use anyhow::{Result, bail, ensure};
+use anyhow::{Context, Result, bail, ensure};
 use camino::{Utf8Path, Utf8PathBuf};
+use glob::Pattern;
 
 use scarb_metadata::{Metadata, PackageMetadata};
 
@@ -24,7 +25,7 @@ const PACKAGES_FILTER_DELIMITER: char = ',';
 #[derive(clap::Parser, Clone, Debug)]
 pub struct PackagesFilter {
     /// Packages to run this command on, can be a concrete package name (`foobar`) or
-    /// a prefix glob (`foo*`).
+    /// a glob pattern (`foo*`, `*-utils`).
     #[arg(
         short,
         long,
@@ -55,7 +56,7 @@ pub struct PackagesFilter {
 #[derive(clap::Parser, Clone, Debug)]
 pub struct PackagesFilterLong {
     /// Packages to run this command on, can be a concrete package name (`foobar`) or
-    /// a prefix glob (`foo*`).
+    /// a glob pattern (`foo*`, `*-utils`).
     #[arg(
         long,
         default_value = "*",
@@ -195,7 +196,8 @@ impl PackagesFilter {
                 members.clone().into_iter(),
             )?);
         }
-        packages.dedup_by_key(|p| S::package_name_of(p).to_string());
+        let mut seen = HashSet::new();
+        packages.retain(|p| seen.insert(S::package_name_of(p).to_string()));
         Ok(packages)
     }
 
@@ -247,40 +249,31 @@ impl From<PackagesFilterLong> for PackagesFilter {
 enum Spec<'a> {
     All,
     One(&'a str),
-    Glob(&'a str),
+    Glob(Pattern),
 }
 
 impl<'a> Spec<'a> {
     fn parse(string: &'a str) -> Result<Self> {
         let string = string.trim();
 
-        if !string.contains('*') {
+        if !string.contains(['*', '?', '[']) {
             return Ok(Self::One(string));
         }
 
-        ensure!(
-            string.chars().filter(|c| *c == '*').count() == 1,
-            "invalid package spec: * character can only occur once in the pattern"
-        );
-        ensure!(
-            string.ends_with('*'),
-            "invalid package spec: only `prefix*` patterns are allowed"
-        );
-
-        let string = string.trim_end_matches('*');
-
-        if string.is_empty() {
-            Ok(Self::All)
-        } else {
-            Ok(Self::Glob(string))
+        if string.chars().all(|c| c == '*') {
+            return Ok(Self::All);
         }
+
+        let pattern = Pattern::new(string)
+            .with_context(|| format!("invalid package spec: `{string}` is not a valid glob"))?;
+        Ok(Self::Glob(pattern))
     }
 
     fn matches(&self, name: &str) -> bool {
         match self {
             Spec::All => true,
             Spec::One(pat) => name == *pat,
-            Spec::Glob(pat) => name.starts_with(pat),
+            Spec::Glob(pat) => pat.matches(name),
         }
     }
 }
@@ -290,7 +283,7 @@ impl fmt::Display for Spec<'_> {
         match self {
             Spec::All => write!(f, "*"),
             Spec::One(name) => write!(f, "{name}"),
-            Spec::Glob(pat) => write!(f, "{pat}*"),
+            Spec::Glob(pat) => write!(f, "{pat}"),
         }
     }
 }
@@ -524,6 +517,36 @@ mod tests {
         );
     }
 
+    #[test]
+    fn can_match_with_non_prefix_glob() {
+        let mock = MockSource::new(mock_packages(vec![
+            "core-utils",
+            "core-math",
+            "app-utils",
+            "app",
+        ]));
+        let filter = PackagesFilter {
+            package: vec!["*-utils".into(), "core-?ath".into()],
+            workspace: false,
+        };
+        let packages = filter.match_many(&mock).unwrap();
+        cmp_no_order(
+            vec!["core-utils", "app-utils", "core-math"],
+            packages.into_iter().map(|p| p.name).collect(),
+        );
+    }
+
+    #[test]
+    fn glob_matching_nothing_is_an_error() {
+        let mock = MockSource::new(mock_packages(vec!["first", "second"]));
+        let filter = PackagesFilter {
+            package: vec!["*-utils".into()],
+            workspace: false,
+        };
+        let err = filter.match_many(&mock).unwrap_err();
+        assert_eq!(err.to_string(), "no workspace members match `*-utils`");
+    }
+
     #[test]
     fn match_one_ensures_single_package() {
         let mock = MockSource::new(mock_packages(vec!["package_1", "package_2"]));
@@ -27,6 +27,13 @@ pub struct SpinnerHandle {
     pb: ProgressBar,
 }
 
+impl SpinnerHandle {
+    /// Replace the message displayed by the associated [`Spinner`].
+    pub fn set_message(&self, message: impl Into<String>) {
+        self.pb.set_message(message.into());
+    }
+}
+
 impl Drop for SpinnerHandle {
     fn drop(&mut self) {
         self.pb.finish_and_clear()